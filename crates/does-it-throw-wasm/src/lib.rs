@@ -6,17 +6,34 @@ extern crate swc_ecma_parser;
 extern crate swc_ecma_visit;
 extern crate wasm_bindgen;
 
+pub mod sarif;
+
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use self::serde::{Deserialize, Serialize, Serializer};
-use self::swc_common::{sync::Lrc, SourceMap, SourceMapper, Span};
+use self::swc_common::{sync::Lrc, FileName, SourceMap, SourceMapper, Span};
 use swc_common::BytePos;
 use wasm_bindgen::prelude::*;
 
+use does_it_throw::builtins::{CallToThrowingBuiltin, ThrowingBuiltin};
+use does_it_throw::cache::AnalysisCache;
 use does_it_throw::call_finder::CallToThrowMap;
-use does_it_throw::throw_finder::{IdentifierUsage, ThrowMap};
-use does_it_throw::{analyze_code, AnalysisResult, UserSettings};
+use does_it_throw::class_hierarchy::ThrowingOverride;
+use does_it_throw::code_actions;
+use does_it_throw::jsdoc::{StaleThrowsTag, UndocumentedThrow};
+use does_it_throw::project::{
+  analyze_project_files_in, ConfigError, FileAnalysis, FileSystem, Location, ProjectAnalysis,
+};
+use does_it_throw::query::{self, ThrowInfo};
+use does_it_throw::rejection_finder::{FloatingPromise, Rejection, RejectionKind};
+use does_it_throw::throw_finder::{error_classes, IdentifierUsage, ThrowMap, ThrownValue};
+use does_it_throw::{analyze_code, AnalysisResult, ParseError, UserSettings};
+
+use sarif::{sarif_results, SarifLog, SarifResult, SarifSeverities};
 
 // Define an extern block with the `console.log` function.
 #[wasm_bindgen]
@@ -63,8 +80,8 @@ impl Serialize for DiagnosticSeverity {
 }
 
 impl DiagnosticSeverity {
-  fn to_int(&self) -> i32 {
-    match *self {
+  fn to_int(self) -> i32 {
+    match self {
       DiagnosticSeverity::Error => 0,
       DiagnosticSeverity::Warning => 1,
       DiagnosticSeverity::Information => 2,
@@ -73,7 +90,7 @@ impl DiagnosticSeverity {
   }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Clone, Deserialize, Debug)]
 pub struct DiagnosticSeverityInput(String);
 
 impl FromStr for DiagnosticSeverity {
@@ -143,6 +160,19 @@ fn get_relative_imports(import_sources: Vec<String>) -> Vec<String> {
   relative_imports
 }
 
+// e.g. "Throw statement: `ValidationError`." instead of "Throw statement." when the error class is known
+fn message_with_error_classes(message: &str, error_classes: &[String]) -> String {
+  if error_classes.is_empty() {
+    return format!("{}.", message);
+  }
+  let error_classes = error_classes
+    .iter()
+    .map(|error_class| format!("`{}`", error_class))
+    .collect::<Vec<String>>()
+    .join(", ");
+  format!("{}: {}.", message, error_classes)
+}
+
 #[derive(Serialize)]
 pub struct ImportedIdentifiers {
   pub diagnostics: Vec<Diagnostic>,
@@ -191,11 +221,17 @@ pub fn add_diagnostics_for_functions_that_throw(
           character: function_end.col_display,
         },
       },
-      message: "Function that may throw.".to_string(),
+      message: message_with_error_classes("Function that may throw", &fun.error_classes()),
       source: "Does it Throw?".to_string(),
     });
 
     for span in &fun.throw_spans {
+      let thrown_values = fun
+        .thrown_values
+        .iter()
+        .filter(|thrown_value| thrown_value.throw_span == *span)
+        .cloned()
+        .collect::<Vec<ThrownValue>>();
       let start = cm.lookup_char_pos(span.lo());
       let end = cm.lookup_char_pos(span.hi());
 
@@ -211,7 +247,14 @@ pub fn add_diagnostics_for_functions_that_throw(
             character: end.col_display,
           },
         },
-        message: "Throw statement.".to_string(),
+        message: message_with_error_classes(
+          if fun.is_rethrow(*span) {
+            "Rethrow of caught error"
+          } else {
+            "Throw statement"
+          },
+          &error_classes(&thrown_values),
+        ),
         source: "Does it Throw?".to_string(),
       });
     }
@@ -255,7 +298,322 @@ pub fn add_diagnostics_for_calls_to_throws(
           character: call_end.col_display,
         },
       },
-      message: "Function call that may throw.".to_string(),
+      message: message_with_error_classes("Function call that may throw", &call.error_classes()),
+      source: "Does it Throw?".to_string(),
+    });
+  }
+}
+
+// Config errors are reported at the top of the document, whose aliased imports they break
+pub fn add_diagnostics_for_config_errors(
+  diagnostics: &mut Vec<Diagnostic>,
+  config_errors: &[ConfigError],
+  debug: Option<bool>,
+) {
+  for config_error in config_errors {
+    if debug == Some(true) {
+      log(&format!(
+        "Could not parse {}: {}",
+        config_error.path.display(),
+        config_error.message
+      ));
+    }
+
+    diagnostics.push(Diagnostic {
+      severity: DiagnosticSeverity::Warning.to_int(),
+      range: DiagnosticRange {
+        start: DiagnosticPosition {
+          line: 0,
+          character: 0,
+        },
+        end: DiagnosticPosition {
+          line: 0,
+          character: 0,
+        },
+      },
+      message: format!(
+        "Could not parse {} (line {}): {}. Path aliases are not resolved.",
+        config_error.path.display(),
+        config_error.location.line,
+        config_error.message
+      ),
+      source: "Does it Throw?".to_string(),
+    });
+  }
+}
+
+pub fn add_diagnostics_for_parse_errors(
+  diagnostics: &mut Vec<Diagnostic>,
+  parse_errors: Vec<ParseError>,
+  cm: &SourceMap,
+  debug: Option<bool>,
+  parse_error_severity: DiagnosticSeverity,
+) {
+  for parse_error in &parse_errors {
+    let start = cm.lookup_char_pos(parse_error.span.lo());
+    let end = cm.lookup_char_pos(parse_error.span.hi());
+
+    if debug == Some(true) {
+      log(&format!("Parse error: {}", parse_error.message));
+      log(&format!(
+        "From line {} column {} to line {} column {}",
+        start.line, start.col_display, end.line, end.col_display
+      ));
+    }
+
+    diagnostics.push(Diagnostic {
+      severity: parse_error_severity.to_int(),
+      range: DiagnosticRange {
+        start: DiagnosticPosition {
+          line: start.line - 1,
+          character: start.col_display,
+        },
+        end: DiagnosticPosition {
+          line: end.line - 1,
+          character: end.col_display,
+        },
+      },
+      message: format!("Syntax error: {}", parse_error.message),
+      source: "Does it Throw?".to_string(),
+    });
+  }
+}
+
+pub fn add_diagnostics_for_calls_to_throwing_builtins(
+  diagnostics: &mut Vec<Diagnostic>,
+  calls_to_throwing_builtins: Vec<CallToThrowingBuiltin>,
+  cm: &SourceMap,
+  debug: Option<bool>,
+  call_to_throwing_builtin_severity: DiagnosticSeverity,
+) {
+  for builtin_call in &calls_to_throwing_builtins {
+    let start = cm.lookup_char_pos(builtin_call.call_span.lo());
+    let end = cm.lookup_char_pos(builtin_call.call_span.hi());
+
+    if debug == Some(true) {
+      log(&format!(
+        "Call to throwing builtin {} in: {}",
+        builtin_call.builtin.callee, builtin_call.call_function_or_method_name
+      ));
+      log(&format!(
+        "From line {} column {} to line {} column {}",
+        start.line, start.col_display, end.line, end.col_display
+      ));
+    }
+
+    diagnostics.push(Diagnostic {
+      severity: call_to_throwing_builtin_severity.to_int(),
+      range: DiagnosticRange {
+        start: DiagnosticPosition {
+          line: start.line - 1,
+          character: start.col_display,
+        },
+        end: DiagnosticPosition {
+          line: end.line - 1,
+          character: end.col_display,
+        },
+      },
+      message: message_with_error_classes(
+        &format!("Call to `{}`, which may throw", builtin_call.builtin.callee),
+        &builtin_call.builtin.error_classes,
+      ),
+      source: "Does it Throw?".to_string(),
+    });
+  }
+}
+
+pub fn add_diagnostics_for_rejections(
+  diagnostics: &mut Vec<Diagnostic>,
+  rejections: Vec<Rejection>,
+  cm: &SourceMap,
+  debug: Option<bool>,
+  promise_rejection_severity: DiagnosticSeverity,
+) {
+  // throws in async functions are already reported as throw statements
+  for rejection in rejections
+    .iter()
+    .filter(|rejection| rejection.kind != RejectionKind::AsyncThrow)
+  {
+    let start = cm.lookup_char_pos(rejection.span.lo());
+    let end = cm.lookup_char_pos(rejection.span.hi());
+
+    if debug == Some(true) {
+      log(&format!(
+        "Promise rejection in: {}",
+        rejection.function_or_method_name
+      ));
+      log(&format!(
+        "From line {} column {} to line {} column {}",
+        start.line, start.col_display, end.line, end.col_display
+      ));
+    }
+
+    let error_classes = rejection
+      .rejected_value
+      .iter()
+      .filter_map(|value| value.error_class.clone())
+      .collect::<Vec<String>>();
+    diagnostics.push(Diagnostic {
+      severity: promise_rejection_severity.to_int(),
+      range: DiagnosticRange {
+        start: DiagnosticPosition {
+          line: start.line - 1,
+          character: start.col_display,
+        },
+        end: DiagnosticPosition {
+          line: end.line - 1,
+          character: end.col_display,
+        },
+      },
+      message: message_with_error_classes("Promise rejection", &error_classes),
+      source: "Does it Throw?".to_string(),
+    });
+  }
+}
+
+pub fn add_diagnostics_for_floating_promises(
+  diagnostics: &mut Vec<Diagnostic>,
+  floating_promises: Vec<FloatingPromise>,
+  cm: &SourceMap,
+  debug: Option<bool>,
+  floating_promise_severity: DiagnosticSeverity,
+) {
+  for floating_promise in &floating_promises {
+    let start = cm.lookup_char_pos(floating_promise.span.lo());
+    let end = cm.lookup_char_pos(floating_promise.span.hi());
+
+    if debug == Some(true) {
+      log(&format!(
+        "Floating promise from: {}",
+        floating_promise.callee_id
+      ));
+      log(&format!(
+        "From line {} column {} to line {} column {}",
+        start.line, start.col_display, end.line, end.col_display
+      ));
+    }
+
+    diagnostics.push(Diagnostic {
+      severity: floating_promise_severity.to_int(),
+      range: DiagnosticRange {
+        start: DiagnosticPosition {
+          line: start.line - 1,
+          character: start.col_display,
+        },
+        end: DiagnosticPosition {
+          line: end.line - 1,
+          character: end.col_display,
+        },
+      },
+      message: "Promise that may reject is neither awaited nor handled.".to_string(),
+      source: "Does it Throw?".to_string(),
+    });
+  }
+}
+
+pub fn add_diagnostics_for_throwing_overrides(
+  diagnostics: &mut Vec<Diagnostic>,
+  throwing_overrides: Vec<ThrowingOverride>,
+  cm: &SourceMap,
+  debug: Option<bool>,
+  throwing_override_severity: DiagnosticSeverity,
+) {
+  for throwing_override in &throwing_overrides {
+    let start = cm.lookup_char_pos(throwing_override.span.lo());
+    let end = cm.lookup_char_pos(throwing_override.span.hi());
+
+    if debug == Some(true) {
+      log(&format!("Throwing override of: {}", throwing_override.base));
+      log(&format!(
+        "From line {} column {} to line {} column {}",
+        start.line, start.col_display, end.line, end.col_display
+      ));
+    }
+
+    diagnostics.push(Diagnostic {
+      severity: throwing_override_severity.to_int(),
+      range: DiagnosticRange {
+        start: DiagnosticPosition {
+          line: start.line - 1,
+          character: start.col_display,
+        },
+        end: DiagnosticPosition {
+          line: end.line - 1,
+          character: end.col_display,
+        },
+      },
+      message: message_with_error_classes(
+        &format!(
+          "Override that may throw while `{}` does not",
+          throwing_override.base
+        ),
+        &throwing_override.throw_map.error_classes(),
+      ),
+      source: "Does it Throw?".to_string(),
+    });
+  }
+}
+
+pub fn add_diagnostics_for_throws_tags(
+  diagnostics: &mut Vec<Diagnostic>,
+  undocumented_throws: Vec<UndocumentedThrow>,
+  stale_throws_tags: Vec<StaleThrowsTag>,
+  cm: &SourceMap,
+  debug: Option<bool>,
+  undocumented_throw_severity: DiagnosticSeverity,
+  stale_throws_tag_severity: DiagnosticSeverity,
+) {
+  let spans = undocumented_throws
+    .iter()
+    .map(|throw| {
+      (
+        throw.throw_span,
+        undocumented_throw_severity,
+        message_with_error_classes(
+          "Throw not documented by a @throws tag",
+          &throw.error_classes,
+        ),
+      )
+    })
+    .chain(stale_throws_tags.iter().map(|stale_tag| {
+      (
+        stale_tag.throws_tag.span,
+        stale_throws_tag_severity,
+        message_with_error_classes(
+          &format!(
+            "@throws tag of `{}` that matches no throw",
+            stale_tag.function_or_method_name
+          ),
+          &stale_tag.throws_tag.error_classes,
+        ),
+      )
+    }));
+
+  for (span, severity, message) in spans {
+    let start = cm.lookup_char_pos(span.lo());
+    let end = cm.lookup_char_pos(span.hi());
+
+    if debug == Some(true) {
+      log(&message);
+      log(&format!(
+        "From line {} column {} to line {} column {}",
+        start.line, start.col_display, end.line, end.col_display
+      ));
+    }
+
+    diagnostics.push(Diagnostic {
+      severity: severity.to_int(),
+      range: DiagnosticRange {
+        start: DiagnosticPosition {
+          line: start.line - 1,
+          character: start.col_display,
+        },
+        end: DiagnosticPosition {
+          line: end.line - 1,
+          character: end.col_display,
+        },
+      },
+      message,
       source: "Does it Throw?".to_string(),
     });
   }
@@ -271,7 +629,8 @@ pub fn identifier_usages_vec_to_combined_map(
 ) -> HashMap<String, ImportedIdentifiers> {
   let mut identifier_usages_map: HashMap<String, ImportedIdentifiers> = HashMap::new();
   for identifier_usage in identifier_usages {
-    let identifier_name = identifier_usage.id.clone();
+    // keyed like `throw_ids`, so that default and renamed imports match their export
+    let identifier_name = identifier_usage.import_id();
     let start = cm.lookup_char_pos(identifier_usage.usage_span.lo());
     let end = cm.lookup_char_pos(identifier_usage.usage_span.hi());
 
@@ -286,13 +645,12 @@ pub fn identifier_usages_vec_to_combined_map(
       ));
     }
 
-    let identifier_diagnostics =
-      identifier_usages_map
-        .entry(identifier_name)
-        .or_insert(ImportedIdentifiers {
-          diagnostics: Vec::new(),
-          id: identifier_usage.id,
-        });
+    let identifier_diagnostics = identifier_usages_map
+      .entry(identifier_name.clone())
+      .or_insert(ImportedIdentifiers {
+        diagnostics: Vec::new(),
+        id: identifier_name,
+      });
 
     identifier_diagnostics.diagnostics.push(Diagnostic {
       severity: call_to_imported_throw_severity.to_int(),
@@ -329,6 +687,17 @@ impl ParseResult {
     input_data: InputData,
   ) -> ParseResult {
     let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    add_diagnostics_for_parse_errors(
+      &mut diagnostics,
+      results.parse_errors,
+      cm,
+      debug,
+      DiagnosticSeverity::from(
+        input_data
+          .parse_error_severity
+          .unwrap_or(DiagnosticSeverityInput("Error".to_string())),
+      ),
+    );
     add_diagnostics_for_functions_that_throw(
       &mut diagnostics,
       results.functions_with_throws.clone(),
@@ -356,13 +725,74 @@ impl ParseResult {
           .unwrap_or(DiagnosticSeverityInput("Hint".to_string())),
       ),
     );
+    add_diagnostics_for_calls_to_throwing_builtins(
+      &mut diagnostics,
+      results.calls_to_throwing_builtins,
+      cm,
+      debug,
+      DiagnosticSeverity::from(
+        input_data
+          .call_to_throwing_builtin_severity
+          .unwrap_or(DiagnosticSeverityInput("Hint".to_string())),
+      ),
+    );
+    add_diagnostics_for_rejections(
+      &mut diagnostics,
+      results.rejections,
+      cm,
+      debug,
+      DiagnosticSeverity::from(
+        input_data
+          .promise_rejection_severity
+          .unwrap_or(DiagnosticSeverityInput("Hint".to_string())),
+      ),
+    );
+    add_diagnostics_for_floating_promises(
+      &mut diagnostics,
+      results.floating_promises,
+      cm,
+      debug,
+      DiagnosticSeverity::from(
+        input_data
+          .floating_promise_severity
+          .unwrap_or(DiagnosticSeverityInput("Hint".to_string())),
+      ),
+    );
+    add_diagnostics_for_throws_tags(
+      &mut diagnostics,
+      results.undocumented_throws,
+      results.stale_throws_tags,
+      cm,
+      debug,
+      DiagnosticSeverity::from(
+        input_data
+          .undocumented_throw_severity
+          .unwrap_or(DiagnosticSeverityInput("Hint".to_string())),
+      ),
+      DiagnosticSeverity::from(
+        input_data
+          .stale_throws_tag_severity
+          .unwrap_or(DiagnosticSeverityInput("Hint".to_string())),
+      ),
+    );
+    add_diagnostics_for_throwing_overrides(
+      &mut diagnostics,
+      results.throwing_overrides,
+      cm,
+      debug,
+      DiagnosticSeverity::from(
+        input_data
+          .throwing_override_severity
+          .unwrap_or(DiagnosticSeverityInput("Hint".to_string())),
+      ),
+    );
 
     ParseResult {
       diagnostics,
       throw_ids: results
         .functions_with_throws
         .into_iter()
-        .map(|f| f.id)
+        .map(|f| f.import_id())
         .collect(),
       relative_imports: get_relative_imports(results.import_sources.into_iter().collect()),
       imported_identifiers_diagnostics: identifier_usages_vec_to_combined_map(
@@ -377,6 +807,34 @@ impl ParseResult {
       ),
     }
   }
+
+  // Used when the module could not be parsed (or recovered), so only the syntax errors are reported.
+  pub fn from_parse_errors(
+    parse_errors: Vec<ParseError>,
+    cm: &SourceMap,
+    debug: Option<bool>,
+    input_data: InputData,
+  ) -> ParseResult {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    add_diagnostics_for_parse_errors(
+      &mut diagnostics,
+      parse_errors,
+      cm,
+      debug,
+      DiagnosticSeverity::from(
+        input_data
+          .parse_error_severity
+          .unwrap_or(DiagnosticSeverityInput("Error".to_string())),
+      ),
+    );
+
+    ParseResult {
+      diagnostics,
+      relative_imports: vec![],
+      throw_ids: vec![],
+      imported_identifiers_diagnostics: HashMap::new(),
+    }
+  }
 }
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -405,78 +863,805 @@ interface InputData {
   call_to_imported_throw_severity?: DiagnosticSeverityInput;
   include_try_statement_throws?: boolean;
   ignore_statements?: string[];
+  recover_from_parse_errors?: boolean;
+  parse_error_severity?: DiagnosticSeverityInput;
+  max_propagation_depth?: number;
+  promise_rejection_severity?: DiagnosticSeverityInput;
+  floating_promise_severity?: DiagnosticSeverityInput;
+  undocumented_throw_severity?: DiagnosticSeverityInput;
+  stale_throws_tag_severity?: DiagnosticSeverityInput;
+  call_to_throwing_builtin_severity?: DiagnosticSeverityInput;
+  throwing_override_severity?: DiagnosticSeverityInput;
+  throwing_builtins?: ThrowingBuiltinInput[];
+}
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const ThrowingBuiltinInput: &'static str = r#"
+interface ThrowingBuiltinInput {
+  callee: string;
+  error_classes?: string[];
+}
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const ImportedIdentifiers: &'static str = r#"
+interface ImportedIdentifiers {
+	diagnostics: any[];
+	id: string;
+}
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const ParseResult: &'static str = r#"
+interface ParseResult {
+	diagnostics: any[];
+	relative_imports: string[];
+	throw_ids: string[];
+	imported_identifiers_diagnostics: Map<string, ImportedIdentifiers>;
+}
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const SarifLog: &'static str = r#"
+interface SarifLog {
+	$schema: string;
+	version: "2.1.0";
+	runs: any[];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+  #[wasm_bindgen(typescript_type = "ParseResult")]
+  pub type ParseResultType;
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TypeScriptSettings {
+  decorators: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct InputData {
+  // TODO - maybe use this in the future
+  // typescript_settings: Option<TypeScriptSettings>,
+  pub uri: Option<String>,
+  // Ids of imported functions known to throw, only used for SARIF output
+  pub ids_to_check: Option<Vec<String>>,
+  pub file_content: String,
+  pub debug: Option<bool>,
+  pub throw_statement_severity: Option<DiagnosticSeverityInput>,
+  pub function_throw_severity: Option<DiagnosticSeverityInput>,
+  pub call_to_throw_severity: Option<DiagnosticSeverityInput>,
+  pub call_to_imported_throw_severity: Option<DiagnosticSeverityInput>,
+  pub include_try_statement_throws: Option<bool>,
+  pub ignore_statements: Option<Vec<String>>,
+  pub recover_from_parse_errors: Option<bool>,
+  pub parse_error_severity: Option<DiagnosticSeverityInput>,
+  pub max_propagation_depth: Option<usize>,
+  pub promise_rejection_severity: Option<DiagnosticSeverityInput>,
+  pub floating_promise_severity: Option<DiagnosticSeverityInput>,
+  pub undocumented_throw_severity: Option<DiagnosticSeverityInput>,
+  pub stale_throws_tag_severity: Option<DiagnosticSeverityInput>,
+  pub call_to_throwing_builtin_severity: Option<DiagnosticSeverityInput>,
+  pub throwing_override_severity: Option<DiagnosticSeverityInput>,
+  pub throwing_builtins: Option<Vec<ThrowingBuiltinInput>>,
+}
+
+// An API known to throw, added to the built-in catalog, e.g. `{ callee: "yaml.parse" }` or
+// `{ callee: "new Temporal.PlainDate", error_classes: ["RangeError"] }`
+#[derive(Deserialize, Debug)]
+pub struct ThrowingBuiltinInput {
+  pub callee: String,
+  pub error_classes: Option<Vec<String>>,
+}
+
+#[wasm_bindgen]
+pub fn parse_js(data: JsValue) -> JsValue {
+  // Parse the input data into a Rust struct.
+  let input_data: InputData = serde_wasm_bindgen::from_value(data).unwrap();
+
+  let cm: Lrc<SourceMap> = Default::default();
+
+  let user_settings = input_data.user_settings();
+
+  let parse_result = match analyze_code(&input_data.file_content, cm.clone(), &user_settings) {
+    Ok((results, cm)) => ParseResult::into(results, &cm, input_data.debug, input_data),
+    Err(error) => ParseResult::from_parse_errors(error.errors, &cm, input_data.debug, input_data),
+  };
+
+  // Convert the diagnostics to a JsValue and return it.
+  serde_wasm_bindgen::to_value(&parse_result).unwrap()
+}
+
+impl InputData {
+  fn user_settings(&self) -> UserSettings {
+    UserSettings {
+      include_try_statement_throws: self.include_try_statement_throws.unwrap_or(false),
+      ignore_statements: self.ignore_statements.clone().unwrap_or_default(),
+      recover_from_parse_errors: self.recover_from_parse_errors.unwrap_or(true),
+      max_propagation_depth: self.max_propagation_depth.unwrap_or(0),
+      throwing_builtins: self
+        .throwing_builtins
+        .iter()
+        .flatten()
+        .map(|builtin| {
+          ThrowingBuiltin::new(
+            &builtin.callee,
+            builtin.error_classes.clone().unwrap_or_default(),
+          )
+        })
+        .collect(),
+    }
+  }
+
+  fn sarif_severities(self) -> SarifSeverities {
+    let severity_or_hint = |severity: Option<DiagnosticSeverityInput>| {
+      DiagnosticSeverity::from(severity.unwrap_or(DiagnosticSeverityInput("Hint".to_string())))
+    };
+    SarifSeverities {
+      function_throw: severity_or_hint(self.function_throw_severity),
+      throw_statement: severity_or_hint(self.throw_statement_severity),
+      call_to_throw: severity_or_hint(self.call_to_throw_severity),
+      call_to_imported_throw: severity_or_hint(self.call_to_imported_throw_severity),
+      promise_rejection: severity_or_hint(self.promise_rejection_severity),
+      floating_promise: severity_or_hint(self.floating_promise_severity),
+      call_to_throwing_builtin: severity_or_hint(self.call_to_throwing_builtin_severity),
+      undocumented_throw: severity_or_hint(self.undocumented_throw_severity),
+      stale_throws_tag: severity_or_hint(self.stale_throws_tag_severity),
+      throwing_override: severity_or_hint(self.throwing_override_severity),
+    }
+  }
+}
+
+// Analyzes one file and returns its findings as SARIF results.
+// Files that cannot be parsed have no results, there is no SARIF rule for syntax errors.
+pub fn sarif_results_for_input(input_data: InputData) -> Vec<SarifResult> {
+  let cm: Lrc<SourceMap> = Default::default();
+  let user_settings = input_data.user_settings();
+  let Ok((results, cm)) = analyze_code(&input_data.file_content, cm, &user_settings) else {
+    return vec![];
+  };
+  let uri = input_data.uri.clone().unwrap_or_else(|| "file".to_string());
+  let imported_throw_ids = input_data
+    .ids_to_check
+    .clone()
+    .unwrap_or_default()
+    .into_iter()
+    .collect();
+  sarif_results(
+    &results,
+    &cm,
+    &uri,
+    &imported_throw_ids,
+    &input_data.sarif_severities(),
+  )
+}
+
+// Takes an array of `InputData`, one per file, and returns a single SARIF 2.1.0 log.
+#[wasm_bindgen]
+pub fn parse_js_sarif(data: JsValue) -> JsValue {
+  let inputs: Vec<InputData> = serde_wasm_bindgen::from_value(data).unwrap();
+  let results = inputs
+    .into_iter()
+    .flat_map(sarif_results_for_input)
+    .collect();
+
+  let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+  SarifLog::new(results).serialize(&serializer).unwrap()
+}
+
+// An LSP hover explaining why the call under the cursor may throw
+#[derive(Serialize)]
+pub struct Hover {
+  contents: MarkupContent,
+  range: DiagnosticRange,
+}
+
+#[derive(Serialize)]
+pub struct MarkupContent {
+  kind: String,
+  value: String,
+}
+
+// `path` is the file making the call, whose throw sites are listed by line only
+fn throw_info_markdown(throw_info: &ThrowInfo, project: &ProjectAnalysis, path: &Path) -> String {
+  let quoted = |names: &[String]| {
+    names
+      .iter()
+      .map(|name| format!("`{}`", name))
+      .collect::<Vec<String>>()
+  };
+  let mut markdown = format!("`{}` may throw", throw_info.callee);
+  if !throw_info.error_classes.is_empty() {
+    markdown.push_str(&format!(
+      " {}",
+      quoted(&throw_info.error_classes).join(", ")
+    ));
+  }
+  markdown.push('.');
+  if throw_info.propagation_path.len() > 1 {
+    markdown.push_str(&format!(
+      "\n\nThrough {}.",
+      quoted(&throw_info.propagation_path).join(" → ")
+    ));
+  }
+  if !throw_info.throw_sites.is_empty() {
+    markdown.push_str("\n\nThrown at:");
+    for throw_site in &throw_info.throw_sites {
+      let declaring_path = throw_site.file.as_deref().unwrap_or(path);
+      let Some(declaring_file) = project.files.get(declaring_path) else {
+        continue;
+      };
+      let line = declaring_file.location(throw_site.span.lo).line;
+      let snippet = declaring_file.snippet(throw_site.span);
+      let snippet = snippet.lines().next().unwrap_or_default().trim();
+      if declaring_path == path {
+        markdown.push_str(&format!("\n- line {}: `{}`", line, snippet));
+      } else {
+        markdown.push_str(&format!(
+          "\n- line {} of {}: `{}`",
+          line,
+          declaring_path
+            .strip_prefix(&project.root)
+            .unwrap_or(declaring_path)
+            .display(),
+          snippet
+        ));
+      }
+    }
+  }
+  markdown
+}
+
+// The hover for a 0-based line and character of the document at `path`, `None` when there is no
+// call that may throw there. Calls to functions imported from other files of the project are
+// explained too.
+pub fn hover_for_input(
+  cache: &mut AnalysisCache,
+  input_data: &InputData,
+  root: &Path,
+  path: &Path,
+  file_system: &dyn FileSystem,
+  line: usize,
+  character: usize,
+) -> Option<Hover> {
+  let (project, _) = analyze_document(cache, input_data, root, path, file_system);
+  let file = project.files.get(path)?;
+  let pos = file.byte_pos(Location {
+    line: line + 1,
+    column: character,
+  })?;
+  let throw_info = query::file_throw_info_at(file, pos)?;
+  Some(Hover {
+    contents: MarkupContent {
+      kind: "markdown".to_string(),
+      value: throw_info_markdown(&throw_info, &project, path),
+    },
+    range: file_range(file, throw_info.call_span),
+  })
+}
+
+// Takes an `InputData`, the project root, the path of the document, a `FileSystemHost` and a
+// 0-based position, and returns a `Hover` or `null`
+#[wasm_bindgen]
+pub fn hover_js(
+  data: JsValue,
+  root: &str,
+  path: &str,
+  host: &FileSystemHost,
+  line: usize,
+  character: usize,
+) -> JsValue {
+  let input_data: InputData = serde_wasm_bindgen::from_value(data).unwrap();
+  let hover = CACHE.with(|cache| {
+    hover_for_input(
+      &mut cache.borrow_mut(),
+      &input_data,
+      Path::new(root),
+      Path::new(path),
+      host,
+      line,
+      character,
+    )
+  });
+  match hover {
+    Some(hover) => serde_wasm_bindgen::to_value(&hover).unwrap(),
+    None => JsValue::NULL,
+  }
+}
+
+// An LSP quick fix for what is reported under the cursor, see `code_actions`
+#[derive(Serialize)]
+pub struct CodeAction {
+  title: String,
+  kind: String,
+  edit: WorkspaceEdit,
+}
+
+#[derive(Serialize)]
+pub struct WorkspaceEdit {
+  // uri -> edits of the file
+  changes: HashMap<String, Vec<TextEdit>>,
+}
+
+#[derive(Serialize)]
+pub struct TextEdit {
+  range: DiagnosticRange,
+  #[serde(rename = "newText")]
+  new_text: String,
+}
+
+// The code actions for a 0-based line and character of the document at `path`, including those
+// for calls to functions imported from other files of the project
+pub fn code_actions_for_input(
+  cache: &mut AnalysisCache,
+  input_data: &InputData,
+  root: &Path,
+  path: &Path,
+  file_system: &dyn FileSystem,
+  line: usize,
+  character: usize,
+) -> Vec<CodeAction> {
+  let (project, _) = analyze_document(cache, input_data, root, path, file_system);
+  let Some(file) = project.files.get(path) else {
+    return vec![];
+  };
+  let Some(pos) = file.byte_pos(Location {
+    line: line + 1,
+    column: character,
+  }) else {
+    return vec![];
+  };
+  let ignore_statement = input_data
+    .ignore_statements
+    .iter()
+    .flatten()
+    .next()
+    .map_or("@it-throws", |ignore_statement| ignore_statement.as_str());
+  let uri = input_data.uri.clone().unwrap_or_default();
+  code_actions::file_code_actions_at(file, pos, ignore_statement)
+    .into_iter()
+    .map(|code_action| {
+      let edits = code_action
+        .edits
+        .into_iter()
+        .map(|edit| TextEdit {
+          range: file_range(file, edit.span),
+          new_text: edit.new_text,
+        })
+        .collect();
+      CodeAction {
+        title: code_action.title,
+        kind: "quickfix".to_string(),
+        edit: WorkspaceEdit {
+          changes: HashMap::from([(uri.clone(), edits)]),
+        },
+      }
+    })
+    .collect()
+}
+
+// Takes an `InputData`, the project root, the path of the document, a `FileSystemHost` and a
+// 0-based position, and returns an array of `CodeAction`s
+#[wasm_bindgen]
+pub fn code_actions_js(
+  data: JsValue,
+  root: &str,
+  path: &str,
+  host: &FileSystemHost,
+  line: usize,
+  character: usize,
+) -> JsValue {
+  let input_data: InputData = serde_wasm_bindgen::from_value(data).unwrap();
+  let code_actions = CACHE.with(|cache| {
+    code_actions_for_input(
+      &mut cache.borrow_mut(),
+      &input_data,
+      Path::new(root),
+      Path::new(path),
+      host,
+      line,
+      character,
+    )
+  });
+  // `changes` is a plain object rather than a `Map`
+  let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+  code_actions.serialize(&serializer).unwrap()
+}
+
+thread_local! {
+  // The analyses of the documents and the files they import, kept between calls so that unchanged
+  // files are not analyzed again
+  static CACHE: RefCell<AnalysisCache> = RefCell::new(AnalysisCache::new());
+}
+
+// The cache as JSON, to be loaded with `load_cache_js` on the next start
+#[wasm_bindgen]
+pub fn save_cache_js() -> String {
+  CACHE.with(|cache| cache.borrow().to_json().to_string())
+}
+
+#[wasm_bindgen]
+pub fn load_cache_js(json: &str) {
+  // a corrupt cache is only a cold start
+  if let Ok(value) = serde_json::from_str(json) {
+    CACHE.with(|cache| *cache.borrow_mut() = AnalysisCache::from_json(&value));
+  }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const FileSystemHost: &'static str = r#"
+interface FileSystemHost {
+  isFile(path: string): boolean;
+  isDirectory(path: string): boolean;
+  readFile(path: string): string | undefined;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+  // The editor's view of the file system: files on disk, and the unsaved content of the documents
+  // it has open
+  #[wasm_bindgen(typescript_type = "FileSystemHost")]
+  pub type FileSystemHost;
+
+  #[wasm_bindgen(method, js_name = isFile)]
+  fn is_file(this: &FileSystemHost, path: &str) -> bool;
+
+  #[wasm_bindgen(method, js_name = isDirectory)]
+  fn is_directory(this: &FileSystemHost, path: &str) -> bool;
+
+  #[wasm_bindgen(method, js_name = readFile)]
+  fn read_file(this: &FileSystemHost, path: &str) -> Option<String>;
+}
+
+impl FileSystem for FileSystemHost {
+  fn is_file(&self, path: &Path) -> bool {
+    FileSystemHost::is_file(self, &path.to_string_lossy())
+  }
+
+  fn is_dir(&self, path: &Path) -> bool {
+    self.is_directory(&path.to_string_lossy())
+  }
+
+  fn read_to_string(&self, path: &Path) -> io::Result<String> {
+    self
+      .read_file(&path.to_string_lossy())
+      .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+  }
+}
+
+// `file_system`, with `content` as the content of the document at `path`
+struct DocumentOverlay<'a> {
+  path: &'a Path,
+  content: &'a str,
+  file_system: &'a dyn FileSystem,
+}
+
+impl FileSystem for DocumentOverlay<'_> {
+  fn is_file(&self, path: &Path) -> bool {
+    path == self.path || self.file_system.is_file(path)
+  }
+
+  fn is_dir(&self, path: &Path) -> bool {
+    self.file_system.is_dir(path)
+  }
+
+  fn read_to_string(&self, path: &Path) -> io::Result<String> {
+    if path == self.path {
+      Ok(self.content.to_string())
+    } else {
+      self.file_system.read_to_string(path)
+    }
+  }
+}
+
+// Analyzes the document at `path` along with the files it imports, the way the command line
+// analyzes a project, so that imports resolve through `tsconfig.json`, index files and barrel
+// files the same way. Returns the project and the files whose diagnostics may have changed with
+// the document.
+fn analyze_document(
+  cache: &mut AnalysisCache,
+  input_data: &InputData,
+  root: &Path,
+  path: &Path,
+  file_system: &dyn FileSystem,
+) -> (ProjectAnalysis, Vec<String>) {
+  analyze_document_with(cache, input_data, root, path, file_system, vec![])
+}
+
+// Like `analyze_document`, also analyzing `other_files` along with the files they import
+fn analyze_document_with(
+  cache: &mut AnalysisCache,
+  input_data: &InputData,
+  root: &Path,
+  path: &Path,
+  file_system: &dyn FileSystem,
+  other_files: Vec<PathBuf>,
+) -> (ProjectAnalysis, Vec<String>) {
+  let user_settings = input_data.user_settings();
+  let update = cache.update(path, input_data.file_content.clone(), &user_settings);
+  let overlay = DocumentOverlay {
+    path,
+    content: &input_data.file_content,
+    file_system,
+  };
+  let mut entry_files = vec![path.to_path_buf()];
+  entry_files.extend(other_files);
+  let project =
+    match analyze_project_files_in(root, &entry_files, &user_settings, cache, 1, &overlay) {
+      Ok(project) => project,
+      Err(_) => ProjectAnalysis {
+        root: root.to_path_buf(),
+        files: Default::default(),
+        config_errors: vec![],
+      },
+    };
+  let invalidated = update
+    .invalidated
+    .iter()
+    .map(|path| path.to_string_lossy().to_string())
+    .collect();
+  (project, invalidated)
+}
+
+// A source map holding the source of `file` at the positions of its analysis, for the diagnostic
+// helpers. Each file is analyzed with a source map of its own, so it starts where a new one starts.
+fn source_map_for(file: &FileAnalysis) -> Lrc<SourceMap> {
+  let cm: Lrc<SourceMap> = Default::default();
+  cm.new_source_file(FileName::Anon, file.source.clone());
+  cm
+}
+
+fn file_range(file: &FileAnalysis, span: Span) -> DiagnosticRange {
+  let position = |pos: BytePos| {
+    let location = file.location(pos);
+    DiagnosticPosition {
+      line: location.line - 1,
+      character: location.column,
+    }
+  };
+  DiagnosticRange {
+    start: position(span.lo),
+    end: position(span.hi),
+  }
 }
-"#;
 
 #[wasm_bindgen(typescript_custom_section)]
-const ImportedIdentifiers: &'static str = r#"
-interface ImportedIdentifiers {
-	diagnostics: any[];
-	id: string;
+const ProjectParseResult: &'static str = r#"
+interface ProjectParseResult {
+  diagnostics: any[];
+  invalidated: string[];
 }
-"#;
 
-#[wasm_bindgen(typescript_custom_section)]
-const ParseResult: &'static str = r#"
-interface ParseResult {
-	diagnostics: any[];
-	relative_imports: string[];
-	throw_ids: string[];
-	imported_identifiers_diagnostics: Map<string, ImportedIdentifiers>;
+interface ThrowSiteLocation {
+  path: string;
+  range: { start: { line: number; character: number }; end: { line: number; character: number } };
 }
-"#;
 
-#[wasm_bindgen]
-extern "C" {
-  #[wasm_bindgen(typescript_type = "ParseResult")]
-  pub type ParseResultType;
+interface CallSiteLocation {
+  path: string;
+  range: { start: { line: number; character: number }; end: { line: number; character: number } };
 }
+"#;
 
-#[derive(Serialize, Deserialize)]
-pub struct TypeScriptSettings {
-  decorators: Option<bool>,
+#[derive(Serialize)]
+pub struct ProjectParseResult {
+  pub diagnostics: Vec<Diagnostic>,
+  // the files importing the document whose diagnostics may have changed with it
+  pub invalidated: Vec<String>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct InputData {
-  // TODO - maybe use this in the future
-  // uri: String,
-  // typescript_settings: Option<TypeScriptSettings>,
-  // ids_to_check: Vec<String>,
-  pub file_content: String,
-  pub debug: Option<bool>,
-  pub throw_statement_severity: Option<DiagnosticSeverityInput>,
-  pub function_throw_severity: Option<DiagnosticSeverityInput>,
-  pub call_to_throw_severity: Option<DiagnosticSeverityInput>,
-  pub call_to_imported_throw_severity: Option<DiagnosticSeverityInput>,
-  pub include_try_statement_throws: Option<bool>,
-  pub ignore_statements: Option<Vec<String>>,
+// The diagnostics of the document at `path`, including calls to throwing functions it imports
+pub fn parse_project_input(
+  cache: &mut AnalysisCache,
+  input_data: InputData,
+  root: &Path,
+  path: &Path,
+  file_system: &dyn FileSystem,
+) -> ProjectParseResult {
+  let (project, invalidated) = analyze_document(cache, &input_data, root, path, file_system);
+  let Some(file) = project.files.get(path) else {
+    return ProjectParseResult {
+      diagnostics: vec![],
+      invalidated,
+    };
+  };
+  let cm = source_map_for(file);
+  let debug = input_data.debug;
+  let call_to_imported_throw_severity = DiagnosticSeverity::from(
+    input_data
+      .call_to_imported_throw_severity
+      .clone()
+      .unwrap_or(DiagnosticSeverityInput("Hint".to_string())),
+  );
+  let imported_throw_usages: HashSet<IdentifierUsage> = file
+    .imported_throws
+    .iter()
+    .map(|imported_throw| imported_throw.usage.clone())
+    .collect();
+  let mut imported_identifiers: Vec<ImportedIdentifiers> = identifier_usages_vec_to_combined_map(
+    imported_throw_usages,
+    &cm,
+    debug,
+    call_to_imported_throw_severity,
+  )
+  .into_values()
+  .collect();
+  imported_identifiers.sort_by(|a, b| a.id.cmp(&b.id));
+  let mut diagnostics = ParseResult::into(file.result.clone(), &cm, debug, input_data).diagnostics;
+  diagnostics.extend(
+    imported_identifiers
+      .into_iter()
+      .flat_map(|imported_identifiers| imported_identifiers.diagnostics),
+  );
+  add_diagnostics_for_config_errors(&mut diagnostics, &project.config_errors, debug);
+  ProjectParseResult {
+    diagnostics,
+    invalidated,
+  }
 }
 
+// Takes an `InputData`, the project root, the path of the document and a `FileSystemHost`, and
+// returns a `ProjectParseResult`. Imported files are cached between calls.
 #[wasm_bindgen]
-pub fn parse_js(data: JsValue) -> JsValue {
-  // Parse the input data into a Rust struct.
+pub fn parse_project_js(data: JsValue, root: &str, path: &str, host: &FileSystemHost) -> JsValue {
   let input_data: InputData = serde_wasm_bindgen::from_value(data).unwrap();
+  let result = CACHE.with(|cache| {
+    parse_project_input(
+      &mut cache.borrow_mut(),
+      input_data,
+      Path::new(root),
+      Path::new(path),
+      host,
+    )
+  });
+  serde_wasm_bindgen::to_value(&result).unwrap()
+}
 
-  let cm: Lrc<SourceMap> = Default::default();
+#[derive(Serialize)]
+pub struct ThrowSiteLocation {
+  // the file declaring the throw statement
+  pub path: String,
+  pub range: DiagnosticRange,
+}
 
-  let user_settings = UserSettings {
-    include_try_statement_throws: input_data.include_try_statement_throws.unwrap_or(false),
-    ignore_statements: input_data.ignore_statements.clone().unwrap_or_else(Vec::new),
+// The throw statements the call at a 0-based line and character of the document at `path` may
+// reach, in the document or in the files it imports
+pub fn project_throw_sites_for_input(
+  cache: &mut AnalysisCache,
+  input_data: &InputData,
+  root: &Path,
+  path: &Path,
+  file_system: &dyn FileSystem,
+  line: usize,
+  character: usize,
+) -> Vec<ThrowSiteLocation> {
+  let (project, _) = analyze_document(cache, input_data, root, path, file_system);
+  let Some(file) = project.files.get(path) else {
+    return vec![];
+  };
+  let Some(pos) = file.byte_pos(Location {
+    line: line + 1,
+    column: character,
+  }) else {
+    return vec![];
   };
+  query::file_throw_sites_at(file, pos)
+    .iter()
+    .filter_map(|throw_site| {
+      let declaring_path = throw_site.file.as_deref().unwrap_or(path);
+      let declaring_file = project.files.get(declaring_path)?;
+      Some(ThrowSiteLocation {
+        path: declaring_path.to_string_lossy().to_string(),
+        range: file_range(declaring_file, throw_site.span),
+      })
+    })
+    .collect()
+}
+
+// Takes an `InputData`, the project root, the path of the document, a `FileSystemHost` and a
+// 0-based position, and returns an array of `ThrowSiteLocation`
+#[wasm_bindgen]
+pub fn project_throw_sites_js(
+  data: JsValue,
+  root: &str,
+  path: &str,
+  host: &FileSystemHost,
+  line: usize,
+  character: usize,
+) -> JsValue {
+  let input_data: InputData = serde_wasm_bindgen::from_value(data).unwrap();
+  let throw_sites = CACHE.with(|cache| {
+    project_throw_sites_for_input(
+      &mut cache.borrow_mut(),
+      &input_data,
+      Path::new(root),
+      Path::new(path),
+      host,
+      line,
+      character,
+    )
+  });
+  serde_wasm_bindgen::to_value(&throw_sites).unwrap()
+}
 
-  let (results, cm) = analyze_code(&input_data.file_content, cm, &user_settings);
+#[derive(Serialize)]
+pub struct CallSiteLocation {
+  // the file making the call
+  pub path: String,
+  pub range: DiagnosticRange,
+}
 
-  let parse_result = ParseResult::into(results, &cm, input_data.debug, input_data);
+// The calls to the throwing function around a 0-based line and character of the document at
+// `path`: those of the document first, then those of the files importing it. The importing files
+// are those the cache knows of, from their own analyses in this session or a previous one.
+pub fn references_for_input(
+  cache: &mut AnalysisCache,
+  input_data: &InputData,
+  root: &Path,
+  path: &Path,
+  file_system: &dyn FileSystem,
+  line: usize,
+  character: usize,
+) -> Vec<CallSiteLocation> {
+  let dependents = cache.dependents(path).into_iter().collect();
+  let (project, _) = analyze_document_with(cache, input_data, root, path, file_system, dependents);
+  let Some(file) = project.files.get(path) else {
+    return vec![];
+  };
+  let Some(pos) = file.byte_pos(Location {
+    line: line + 1,
+    column: character,
+  }) else {
+    return vec![];
+  };
+  query::project_calls_to_function_at(&project, path, pos)
+    .iter()
+    .filter_map(|call_site| {
+      let calling_path = call_site.file.as_deref().unwrap_or(path);
+      let calling_file = project.files.get(calling_path)?;
+      Some(CallSiteLocation {
+        path: calling_path.to_string_lossy().to_string(),
+        range: file_range(calling_file, call_site.span),
+      })
+    })
+    .collect()
+}
 
-  // Convert the diagnostics to a JsValue and return it.
-  serde_wasm_bindgen::to_value(&parse_result).unwrap()
+// Takes an `InputData`, the project root, the path of the document, a `FileSystemHost` and a
+// 0-based position, and returns an array of `CallSiteLocation`
+#[wasm_bindgen]
+pub fn references_js(
+  data: JsValue,
+  root: &str,
+  path: &str,
+  host: &FileSystemHost,
+  line: usize,
+  character: usize,
+) -> JsValue {
+  let input_data: InputData = serde_wasm_bindgen::from_value(data).unwrap();
+  let references = CACHE.with(|cache| {
+    references_for_input(
+      &mut cache.borrow_mut(),
+      &input_data,
+      Path::new(root),
+      Path::new(path),
+      host,
+      line,
+      character,
+    )
+  });
+  serde_wasm_bindgen::to_value(&references).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
 
   use super::*;
+  use does_it_throw::throw_finder::{ThrowRegion, ThrownValueKind};
   use swc_common::FileName;
 
   #[test]
@@ -600,9 +1785,14 @@ mod tests {
     let functions_with_throws = HashSet::from([ThrowMap {
       throw_statement: throw_span,
       throw_spans: vec![throw_span],
+      propagation_path: vec![],
+      thrown_values: vec![],
+      throws_tags: None,
       function_or_method_name: "foo".to_string(),
       class_name: None,
       id: "foo".to_string(),
+      symbol: Default::default(),
+      class_member: None,
     }]);
 
     let mut diagnostics: Vec<Diagnostic> = Vec::new();
@@ -621,6 +1811,66 @@ mod tests {
     assert_eq!(diagnostics[0].message, "Function that may throw.");
   }
 
+  #[test]
+  fn test_add_diagnostics_for_functions_that_throw_with_error_classes() {
+    let cm = Lrc::new(SourceMap::default());
+    let source_file = cm.new_source_file(
+      FileName::Custom("test_file".into()),
+      "function foo() {\n  throw new NotFoundError();\n}".into(),
+    );
+
+    let function_span = Span::new(
+      source_file.start_pos,
+      source_file.start_pos + BytePos(46),
+      Default::default(),
+    );
+    let throw_span = Span::new(
+      source_file.start_pos + BytePos(19),
+      source_file.start_pos + BytePos(44),
+      Default::default(),
+    );
+
+    let functions_with_throws = HashSet::from([ThrowMap {
+      throw_statement: function_span,
+      throw_spans: vec![throw_span],
+      propagation_path: vec![],
+      thrown_values: vec![ThrownValue {
+        throw_span,
+        expression_span: throw_span,
+        kind: ThrownValueKind::Constructor,
+        error_class: Some("NotFoundError".to_string()),
+        message: None,
+        region: ThrowRegion::Body,
+        rethrown: vec![],
+        causes: vec![],
+      }],
+      throws_tags: None,
+      function_or_method_name: "foo".to_string(),
+      class_name: None,
+      id: "foo".to_string(),
+      symbol: Default::default(),
+      class_member: None,
+    }]);
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    add_diagnostics_for_functions_that_throw(
+      &mut diagnostics,
+      functions_with_throws,
+      &cm,
+      None,
+      DiagnosticSeverity::Hint,
+      DiagnosticSeverity::Hint,
+    );
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+      diagnostics[0].message,
+      "Function that may throw: `NotFoundError`."
+    );
+    assert_eq!(diagnostics[1].message, "Throw statement: `NotFoundError`.");
+  }
+
   #[test]
   fn test_add_diagnostics_for_functions_that_throw_multiple() {
     let cm = Lrc::new(SourceMap::default());
@@ -644,9 +1894,14 @@ mod tests {
     let functions_with_throws = HashSet::from([ThrowMap {
       throw_statement: first_throw_span,
       throw_spans: vec![first_throw_span, second_throw_span],
+      propagation_path: vec![],
+      thrown_values: vec![],
+      throws_tags: None,
       function_or_method_name: "foo".to_string(),
       class_name: None,
       id: "foo".to_string(),
+      symbol: Default::default(),
+      class_member: None,
     }]);
 
     let mut diagnostics: Vec<Diagnostic> = Vec::new();
@@ -688,10 +1943,12 @@ mod tests {
 
     let call_to_throws = HashSet::from([CallToThrowMap {
       call_span,
+      propagation_path: vec![],
       call_function_or_method_name: "foo".to_string(),
       call_class_name: None,
       class_name: None,
       id: "foo".to_string(),
+      symbol: Default::default(),
       throw_map: ThrowMap {
         throw_statement: Span::new(
           source_file.start_pos + BytePos(13),
@@ -699,9 +1956,14 @@ mod tests {
           Default::default(),
         ),
         throw_spans: vec![],
+        propagation_path: vec![],
+        thrown_values: vec![],
+        throws_tags: None,
         function_or_method_name: "foo".to_string(),
         class_name: None,
         id: "foo".to_string(),
+        symbol: Default::default(),
+        class_member: None,
       },
     }]);
 
@@ -769,10 +2031,12 @@ mod tests {
     let call_to_throws = HashSet::from([
       CallToThrowMap {
         call_span: call_span_foo,
+        propagation_path: vec![],
         call_function_or_method_name: "foo".to_string(),
         call_class_name: None,
         class_name: None,
         id: "foo".to_string(),
+        symbol: Default::default(),
         throw_map: ThrowMap {
           throw_statement: Span::new(
             source_file.start_pos + BytePos(13),
@@ -780,17 +2044,24 @@ mod tests {
             Default::default(),
           ),
           throw_spans: vec![],
+          propagation_path: vec![],
+          thrown_values: vec![],
+          throws_tags: None,
           function_or_method_name: "foo".to_string(),
           class_name: None,
           id: "foo".to_string(),
+          symbol: Default::default(),
+          class_member: None,
         },
       },
       CallToThrowMap {
         call_span: call_span_bar,
+        propagation_path: vec![],
         call_function_or_method_name: "bar".to_string(),
         call_class_name: None,
         class_name: None,
         id: "foo".to_string(),
+        symbol: Default::default(),
         throw_map: ThrowMap {
           throw_statement: Span::new(
             source_file.start_pos + BytePos(13),
@@ -798,9 +2069,14 @@ mod tests {
             Default::default(),
           ),
           throw_spans: vec![],
+          propagation_path: vec![],
+          thrown_values: vec![],
+          throws_tags: None,
           function_or_method_name: "foo".to_string(),
           class_name: None,
           id: "foo".to_string(),
+          symbol: Default::default(),
+          class_member: None,
         },
       },
     ]);
@@ -841,12 +2117,14 @@ mod tests {
     let identifier_usages = HashSet::from([
       IdentifierUsage {
         id: "foo".to_string(),
+        symbol: Default::default(),
         usage_span: first_usage_span,
         identifier_name: "foo".to_string(),
         usage_context: "import".to_string(),
       },
       IdentifierUsage {
         id: "foo".to_string(),
+        symbol: Default::default(),
         usage_span: second_usage_span,
         identifier_name: "foo".to_string(),
         usage_context: "import".to_string(),
@@ -886,7 +2164,8 @@ mod tests {
     let cm = Lrc::new(SourceMap::default());
     let source_file = cm.new_source_file(
       FileName::Custom("test_file".into()),
-      "function foo() {\n  try {\n    throw new Error();\n  } catch (e) {\n    throw e;\n  }\n}".into(),
+      "function foo() {\n  try {\n    throw new Error();\n  } catch (e) {\n    throw e;\n  }\n}"
+        .into(),
     );
 
     let throw_span = Span::new(
@@ -898,9 +2177,14 @@ mod tests {
     let functions_with_throws = HashSet::from([ThrowMap {
       throw_statement: throw_span,
       throw_spans: vec![throw_span],
+      propagation_path: vec![],
+      thrown_values: vec![],
+      throws_tags: None,
       function_or_method_name: "foo".to_string(),
       class_name: None,
       id: "foo".to_string(),
+      symbol: Default::default(),
+      class_member: None,
     }]);
 
     let mut diagnostics: Vec<Diagnostic> = Vec::new();
@@ -918,4 +2202,584 @@ mod tests {
     assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Hint.to_int());
     assert_eq!(diagnostics[0].message, "Function that may throw.");
   }
+
+  #[test]
+  fn test_add_diagnostics_for_rejections_and_floating_promises() {
+    let cm: Lrc<SourceMap> = Default::default();
+    let (results, cm) = analyze_code(
+      "function foo() {\n  return Promise.reject(new TypeError());\n}\nasync function bar() {\n  throw new Error();\n}\nbar();",
+      cm,
+      &UserSettings::default(),
+    )
+    .unwrap();
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    add_diagnostics_for_rejections(
+      &mut diagnostics,
+      results.rejections,
+      &cm,
+      None,
+      DiagnosticSeverity::Warning,
+    );
+    add_diagnostics_for_floating_promises(
+      &mut diagnostics,
+      results.floating_promises,
+      &cm,
+      None,
+      DiagnosticSeverity::Hint,
+    );
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+      diagnostics[0].severity,
+      DiagnosticSeverity::Warning.to_int()
+    );
+    assert_eq!(diagnostics[0].message, "Promise rejection: `TypeError`.");
+    assert_eq!(diagnostics[0].range.start.line, 1);
+    assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Hint.to_int());
+    assert_eq!(
+      diagnostics[1].message,
+      "Promise that may reject is neither awaited nor handled."
+    );
+    assert_eq!(diagnostics[1].range.start.line, 6);
+  }
+
+  #[test]
+  fn test_add_diagnostics_for_throws_tags() {
+    let cm: Lrc<SourceMap> = Default::default();
+    let (results, cm) = analyze_code(
+      "/**\n * @throws {TypeError} on bad input\n */\nfunction foo() {\n  throw new RangeError();\n}",
+      cm,
+      &UserSettings::default(),
+    )
+    .unwrap();
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    add_diagnostics_for_throws_tags(
+      &mut diagnostics,
+      results.undocumented_throws,
+      results.stale_throws_tags,
+      &cm,
+      None,
+      DiagnosticSeverity::Warning,
+      DiagnosticSeverity::Information,
+    );
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+      diagnostics[0].severity,
+      DiagnosticSeverity::Warning.to_int()
+    );
+    assert_eq!(
+      diagnostics[0].message,
+      "Throw not documented by a @throws tag: `RangeError`."
+    );
+    assert_eq!(diagnostics[0].range.start.line, 4);
+    assert_eq!(
+      diagnostics[1].severity,
+      DiagnosticSeverity::Information.to_int()
+    );
+    assert_eq!(
+      diagnostics[1].message,
+      "@throws tag of `foo` that matches no throw: `TypeError`."
+    );
+    assert_eq!(diagnostics[1].range.start.line, 1);
+    assert_eq!(diagnostics[1].range.start.character, 3);
+  }
+
+  #[test]
+  fn test_add_diagnostics_for_throwing_overrides() {
+    let cm: Lrc<SourceMap> = Default::default();
+    let (results, cm) = analyze_code(
+      "class Base {\n  save() {}\n}\nclass Child extends Base {\n  save() {\n    throw new TypeError();\n  }\n}",
+      cm,
+      &UserSettings::default(),
+    )
+    .unwrap();
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    add_diagnostics_for_throwing_overrides(
+      &mut diagnostics,
+      results.throwing_overrides,
+      &cm,
+      None,
+      DiagnosticSeverity::Warning,
+    );
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+      diagnostics[0].severity,
+      DiagnosticSeverity::Warning.to_int()
+    );
+    assert_eq!(
+      diagnostics[0].message,
+      "Override that may throw while `Base.save` does not: `TypeError`."
+    );
+    assert_eq!(diagnostics[0].range.start.line, 4);
+    assert_eq!(diagnostics[0].range.start.character, 2);
+  }
+
+  #[test]
+  fn test_hover_for_input() {
+    let mut cache = AnalysisCache::new();
+    let input_data = |file_content: &str| -> InputData {
+      serde_json::from_value(serde_json::json!({
+        "uri": "file:///app/main.ts",
+        "file_content": file_content,
+        "max_propagation_depth": 2,
+      }))
+      .unwrap()
+    };
+    let file_system = MemoryFileSystem(HashMap::from([(
+      PathBuf::from("/app/lib/store.ts"),
+      "export function save(value) {\n  throw new Error('read-only');\n}".to_string(),
+    )]));
+    let root = Path::new("/app");
+    let main_path = Path::new("/app/main.ts");
+
+    let main = "function parse(text) {\n  throw new SyntaxError('bad');\n}\nfunction load(text) {\n  return parse(text);\n}\nload('');";
+    let hover = hover_for_input(
+      &mut cache,
+      &input_data(main),
+      root,
+      main_path,
+      &file_system,
+      6,
+      1,
+    )
+    .unwrap();
+    assert_eq!(hover.contents.kind, "markdown");
+    assert_eq!(
+      hover.contents.value,
+      "`load` may throw `SyntaxError`.\n\nThrough `NOT_SET-load` → `NOT_SET-parse`.\n\nThrown at:\n- line 2: `throw new SyntaxError('bad');`"
+    );
+    assert_eq!(hover.range.start.line, 6);
+    assert_eq!(hover.range.start.character, 0);
+    assert_eq!(hover.range.end.character, 8);
+
+    // no call that may throw under the cursor
+    assert!(hover_for_input(
+      &mut cache,
+      &input_data(main),
+      root,
+      main_path,
+      &file_system,
+      0,
+      3
+    )
+    .is_none());
+
+    // a call to a function imported from another file of the project
+    let main = "import { save } from './lib/store';\nsave(1);";
+    let hover = hover_for_input(
+      &mut cache,
+      &input_data(main),
+      root,
+      main_path,
+      &file_system,
+      1,
+      1,
+    )
+    .unwrap();
+    assert_eq!(
+      hover.contents.value,
+      "`save` may throw `Error`.\n\nThrown at:\n- line 2 of lib/store.ts: `throw new Error('read-only');`"
+    );
+    assert_eq!(hover.range.start.line, 1);
+  }
+
+  #[test]
+  fn test_code_actions_for_input() {
+    let mut cache = AnalysisCache::new();
+    let input_data = |file_content: &str| -> InputData {
+      serde_json::from_value(serde_json::json!({
+        "uri": "file:///app/user.ts",
+        "file_content": file_content,
+        "ignore_statements": ["@does-it-throw-ignore"],
+      }))
+      .unwrap()
+    };
+    let file_system = MemoryFileSystem(HashMap::from([(
+      PathBuf::from("/app/store.ts"),
+      "export function save(value) {\n  throw new Error('read-only');\n}".to_string(),
+    )]));
+    let root = Path::new("/app");
+    let user_path = Path::new("/app/user.ts");
+    let code_actions_at = |cache: &mut AnalysisCache, content: &str, line, character| {
+      code_actions_for_input(
+        cache,
+        &input_data(content),
+        root,
+        user_path,
+        &file_system,
+        line,
+        character,
+      )
+    };
+
+    let user = "function parse(text) {\n  throw new SyntaxError('bad');\n}\nparse('');";
+    let code_actions = code_actions_at(&mut cache, user, 3, 0);
+    assert_eq!(code_actions.len(), 1);
+    assert_eq!(
+      code_actions[0].title,
+      "Wrap the call to `parse` in try/catch"
+    );
+    assert_eq!(code_actions[0].kind, "quickfix");
+    let edits = &code_actions[0].edit.changes["file:///app/user.ts"];
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].range.start.line, 3);
+    assert_eq!(edits[0].range.start.character, 0);
+    assert_eq!(edits[0].range.end.character, 10);
+    assert_eq!(
+      edits[0].new_text,
+      "try {\n  parse('');\n} catch (error) {\n}"
+    );
+
+    let code_actions = code_actions_at(&mut cache, user, 1, 2);
+    assert_eq!(code_actions.len(), 2);
+    let edits = &code_actions[0].edit.changes["file:///app/user.ts"];
+    assert_eq!(edits[0].new_text, "  // @does-it-throw-ignore\n");
+    assert_eq!(edits[0].range.start.line, 1);
+    let edits = &code_actions[1].edit.changes["file:///app/user.ts"];
+    assert_eq!(edits[0].new_text, "/**\n * @throws {SyntaxError}\n */\n");
+    assert_eq!(edits[0].range.start.line, 0);
+
+    // a call to a function imported from another file of the project
+    let user = "import { save } from './store';\nsave(1);";
+    let code_actions = code_actions_at(&mut cache, user, 1, 0);
+    assert_eq!(code_actions.len(), 1);
+    assert_eq!(
+      code_actions[0].title,
+      "Wrap the call to `save` in try/catch"
+    );
+    let edits = &code_actions[0].edit.changes["file:///app/user.ts"];
+    assert_eq!(edits[0].range.start.line, 1);
+    assert_eq!(edits[0].new_text, "try {\n  save(1);\n} catch (error) {\n}");
+  }
+
+  // Files at their paths, for the tests that read files through the editor
+  struct MemoryFileSystem(HashMap<PathBuf, String>);
+
+  impl FileSystem for MemoryFileSystem {
+    fn is_file(&self, path: &Path) -> bool {
+      self.0.contains_key(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+      self
+        .0
+        .keys()
+        .any(|file| file.starts_with(path) && file != path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+      self
+        .0
+        .get(path)
+        .cloned()
+        .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+  }
+
+  #[test]
+  fn test_parse_project_input() {
+    let mut cache = AnalysisCache::new();
+    let input_data = |file_content: &str| -> InputData {
+      serde_json::from_value(serde_json::json!({
+        "uri": "file:///app/main.ts",
+        "file_content": file_content,
+      }))
+      .unwrap()
+    };
+    let store = "export function save(value) {\n  throw new Error('read-only');\n}";
+    let file_system = MemoryFileSystem(HashMap::from([
+      (
+        PathBuf::from("/app/tsconfig.json"),
+        r#"{ "compilerOptions": { "baseUrl": ".", "paths": { "@lib": ["lib"] } } }"#.to_string(),
+      ),
+      (
+        PathBuf::from("/app/lib/index.ts"),
+        "export { save as store } from './store'".to_string(),
+      ),
+      (PathBuf::from("/app/lib/store.ts"), store.to_string()),
+    ]));
+    let root = Path::new("/app");
+    let main_path = Path::new("/app/main.ts");
+    let store_path = Path::new("/app/lib/store.ts");
+
+    // the unsaved document imports through a `paths` alias, a directory index and a renaming
+    // barrel, like the command line would resolve them
+    let main = "import { store } from '@lib';\nstore(1);\nconst ok = 1;";
+    let result = parse_project_input(&mut cache, input_data(main), root, main_path, &file_system);
+    let imported_throw_lines: Vec<usize> = result
+      .diagnostics
+      .iter()
+      .filter(|diagnostic| diagnostic.message == "Function imported that may throw.")
+      .map(|diagnostic| diagnostic.range.start.line)
+      .collect();
+    assert_eq!(imported_throw_lines, vec![1]);
+    assert!(result.invalidated.is_empty());
+
+    let throw_sites = project_throw_sites_for_input(
+      &mut cache,
+      &input_data(main),
+      root,
+      main_path,
+      &file_system,
+      1,
+      1,
+    );
+    assert_eq!(throw_sites.len(), 1);
+    assert_eq!(throw_sites[0].path, "/app/lib/store.ts");
+    assert_eq!(throw_sites[0].range.start.line, 1);
+
+    // `save` no longer throws: the document importing it through the barrel is invalidated
+    let store_data: InputData = serde_json::from_value(serde_json::json!({
+      "uri": "file:///app/lib/store.ts",
+      "file_content": "export function save(value) {}",
+    }))
+    .unwrap();
+    let result = parse_project_input(&mut cache, store_data, root, store_path, &file_system);
+    assert!(result.invalidated.contains(&"/app/main.ts".to_string()));
+  }
+
+  #[test]
+  fn test_references_for_input() {
+    let mut cache = AnalysisCache::new();
+    let input_data = |uri: &str, file_content: &str| -> InputData {
+      serde_json::from_value(serde_json::json!({
+        "uri": uri,
+        "file_content": file_content,
+      }))
+      .unwrap()
+    };
+    let store = "export function save(value) {\n  throw new Error('read-only');\n}\nsave(0);";
+    let main = "import { save } from './store';\nsave(1);\nsave(2);";
+    let file_system = MemoryFileSystem(HashMap::from([
+      (PathBuf::from("/app/store.ts"), store.to_string()),
+      (PathBuf::from("/app/main.ts"), main.to_string()),
+    ]));
+    let root = Path::new("/app");
+    let store_path = Path::new("/app/store.ts");
+
+    // `main.ts` was analyzed, so the cache knows it imports `store.ts`
+    parse_project_input(
+      &mut cache,
+      input_data("file:///app/main.ts", main),
+      root,
+      Path::new("/app/main.ts"),
+      &file_system,
+    );
+    let references = references_for_input(
+      &mut cache,
+      &input_data("file:///app/store.ts", store),
+      root,
+      store_path,
+      &file_system,
+      0,
+      17,
+    );
+    let locations: Vec<(&str, usize)> = references
+      .iter()
+      .map(|reference| (reference.path.as_str(), reference.range.start.line))
+      .collect();
+    assert_eq!(
+      locations,
+      vec![
+        ("/app/store.ts", 3),
+        ("/app/main.ts", 1),
+        ("/app/main.ts", 2)
+      ]
+    );
+
+    // nothing throws under the cursor
+    assert!(references_for_input(
+      &mut cache,
+      &input_data("file:///app/store.ts", store),
+      root,
+      store_path,
+      &file_system,
+      3,
+      8,
+    )
+    .is_empty());
+  }
+
+  #[test]
+  fn test_parse_project_input_with_saved_cache() {
+    let main = "import { save } from './store';\nsave(1);";
+    let input_data = || -> InputData {
+      serde_json::from_value(serde_json::json!({
+        "uri": "file:///app/main.ts",
+        "file_content": main,
+      }))
+      .unwrap()
+    };
+    let file_system = MemoryFileSystem(HashMap::from([(
+      PathBuf::from("/app/store.ts"),
+      "export function save(value) {\n  throw new Error('read-only');\n}".to_string(),
+    )]));
+    let diagnostics = |cache: &mut AnalysisCache| -> Vec<(String, usize)> {
+      parse_project_input(
+        cache,
+        input_data(),
+        Path::new("/app"),
+        Path::new("/app/main.ts"),
+        &file_system,
+      )
+      .diagnostics
+      .into_iter()
+      .map(|diagnostic| (diagnostic.message, diagnostic.range.start.line))
+      .collect()
+    };
+    let mut cache = AnalysisCache::new();
+    let cold = diagnostics(&mut cache);
+    assert!(!cold.is_empty());
+
+    // the next session gets the saved results of the unchanged files back
+    let mut cache = AnalysisCache::from_json(&cache.to_json());
+    let update = cache.analyze_all(
+      vec![(PathBuf::from("/app/main.ts"), main.to_string())],
+      &input_data().user_settings(),
+      1,
+    );
+    assert!(!update[0].analyzed);
+    assert_eq!(diagnostics(&mut cache), cold);
+  }
+
+  #[test]
+  fn test_parse_project_input_reports_invalid_config() {
+    let mut cache = AnalysisCache::new();
+    let input_data: InputData = serde_json::from_value(serde_json::json!({
+      "uri": "file:///app/main.ts",
+      "file_content": "import { save } from '@lib/store';\nsave(1);",
+    }))
+    .unwrap();
+    let file_system = MemoryFileSystem(HashMap::from([(
+      PathBuf::from("/app/tsconfig.json"),
+      "{\n  \"compilerOptions\": { \"paths\": { \"@lib/*\": [\"lib/*\"] } }\n  \"extends\": \"./base\"\n}"
+        .to_string(),
+    )]));
+    let result = parse_project_input(
+      &mut cache,
+      input_data,
+      Path::new("/app"),
+      Path::new("/app/main.ts"),
+      &file_system,
+    );
+    let messages: Vec<&str> = result
+      .diagnostics
+      .iter()
+      .map(|diagnostic| diagnostic.message.as_str())
+      .collect();
+    assert_eq!(
+      messages,
+      vec!["Could not parse /app/tsconfig.json (line 3): expected `,` or `}`. Path aliases are not resolved."]
+    );
+  }
+
+  #[test]
+  fn test_add_diagnostics_for_calls_to_throwing_builtins() {
+    let input_data: InputData = serde_json::from_str(
+      r#"{
+        "file_content": "function foo(text) {\n  return JSON.parse(text);\n}\nconst bar = () => toml.parse('');",
+        "throwing_builtins": [{ "callee": "toml.parse" }]
+      }"#,
+    )
+    .unwrap();
+    let cm: Lrc<SourceMap> = Default::default();
+    let (results, cm) =
+      analyze_code(&input_data.file_content, cm, &input_data.user_settings()).unwrap();
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    add_diagnostics_for_calls_to_throwing_builtins(
+      &mut diagnostics,
+      results.calls_to_throwing_builtins,
+      &cm,
+      None,
+      DiagnosticSeverity::Warning,
+    );
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+      diagnostics[0].severity,
+      DiagnosticSeverity::Warning.to_int()
+    );
+    assert_eq!(
+      diagnostics[0].message,
+      "Call to `JSON.parse`, which may throw: `SyntaxError`."
+    );
+    assert_eq!(diagnostics[0].range.start.line, 1);
+    assert_eq!(diagnostics[0].range.start.character, 9);
+    assert_eq!(
+      diagnostics[1].message,
+      "Call to `toml.parse`, which may throw."
+    );
+  }
+
+  #[test]
+  fn test_default_imports_match_the_throw_ids_of_their_export() {
+    let parse_result_for = |file_content: &str| {
+      let input_data: InputData = serde_json::from_value(serde_json::json!({
+        "file_content": file_content,
+      }))
+      .unwrap();
+      let cm: Lrc<SourceMap> = Default::default();
+      let (results, cm) =
+        analyze_code(&input_data.file_content, cm, &input_data.user_settings()).unwrap();
+      ParseResult::into(results, &cm, None, input_data)
+    };
+
+    let exporting = parse_result_for("export default function () {\n  throw new Error();\n}");
+    assert_eq!(exporting.throw_ids, vec!["NOT_SET-default"]);
+
+    let importing = parse_result_for("import parseInput from './parse';\nparseInput();");
+    let imported = importing
+      .imported_identifiers_diagnostics
+      .get("NOT_SET-default")
+      .unwrap();
+    assert_eq!(imported.id, "NOT_SET-default");
+    assert_eq!(imported.diagnostics.len(), 1);
+    assert_eq!(imported.diagnostics[0].range.start.line, 1);
+  }
+
+  #[test]
+  fn test_add_diagnostics_for_parse_errors() {
+    let cm = Lrc::new(SourceMap::default());
+    let source_file = cm.new_source_file(
+      FileName::Custom("test_file".into()),
+      "function foo() {\n  const a = bar(\n}".into(),
+    );
+
+    let error_span = Span::new(
+      source_file.start_pos + BytePos(34),
+      source_file.start_pos + BytePos(35),
+      Default::default(),
+    );
+
+    let parse_errors = vec![ParseError {
+      span: error_span,
+      message: "Expression expected".to_string(),
+    }];
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    add_diagnostics_for_parse_errors(
+      &mut diagnostics,
+      parse_errors,
+      &cm,
+      None,
+      DiagnosticSeverity::Error,
+    );
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error.to_int());
+    assert_eq!(diagnostics[0].message, "Syntax error: Expression expected");
+    assert_eq!(diagnostics[0].range.start.line, 2);
+    assert_eq!(diagnostics[0].range.start.character, 0);
+  }
 }
@@ -0,0 +1,216 @@
+// SARIF 2.1.0 output of `parse_js_sarif`, for code-scanning dashboards that consume
+// static-analysis results. The log is built by `does_it_throw::sarif`, the levels of its results
+// follow the severities of the LSP diagnostics.
+
+use std::collections::HashSet;
+
+use swc_common::SourceMap;
+
+pub use does_it_throw::sarif::{
+  sarif_findings, ArtifactLocation, Location, Message, PhysicalLocation, Region, SarifLevel,
+  SarifLog, SarifResult, SarifRule, FINGERPRINT_KEY, SARIF_SCHEMA, SARIF_VERSION,
+};
+use does_it_throw::AnalysisResult;
+
+use crate::DiagnosticSeverity;
+
+impl DiagnosticSeverity {
+  fn to_sarif_level(self) -> SarifLevel {
+    match self {
+      DiagnosticSeverity::Error => SarifLevel::Error,
+      DiagnosticSeverity::Warning => SarifLevel::Warning,
+      DiagnosticSeverity::Information | DiagnosticSeverity::Hint => SarifLevel::Note,
+    }
+  }
+}
+
+pub struct SarifSeverities {
+  pub function_throw: DiagnosticSeverity,
+  pub throw_statement: DiagnosticSeverity,
+  pub call_to_throw: DiagnosticSeverity,
+  pub call_to_imported_throw: DiagnosticSeverity,
+  pub promise_rejection: DiagnosticSeverity,
+  pub floating_promise: DiagnosticSeverity,
+  pub call_to_throwing_builtin: DiagnosticSeverity,
+  pub undocumented_throw: DiagnosticSeverity,
+  pub stale_throws_tag: DiagnosticSeverity,
+  pub throwing_override: DiagnosticSeverity,
+}
+
+impl SarifSeverities {
+  fn for_rule(&self, rule: SarifRule) -> DiagnosticSeverity {
+    match rule {
+      SarifRule::FunctionThatMayThrow => self.function_throw,
+      SarifRule::ThrowStatement => self.throw_statement,
+      SarifRule::CallToThrow => self.call_to_throw,
+      SarifRule::CallToImportedThrow => self.call_to_imported_throw,
+      SarifRule::PromiseRejection => self.promise_rejection,
+      SarifRule::FloatingPromise => self.floating_promise,
+      SarifRule::CallToThrowingBuiltin => self.call_to_throwing_builtin,
+      SarifRule::UndocumentedThrow => self.undocumented_throw,
+      SarifRule::StaleThrowsTag => self.stale_throws_tag,
+      SarifRule::ThrowingOverride => self.throwing_override,
+    }
+  }
+}
+
+// Builds the SARIF results for one analyzed file.
+// `imported_throw_ids` are the ids of imported functions that are known to throw; calls to any
+// other imported identifier are not reported (the same matching the language server does).
+pub fn sarif_results(
+  results: &AnalysisResult,
+  cm: &SourceMap,
+  uri: &str,
+  imported_throw_ids: &HashSet<String>,
+  severities: &SarifSeverities,
+) -> Vec<SarifResult> {
+  does_it_throw::sarif::sarif_results(
+    sarif_findings(results, imported_throw_ids),
+    uri,
+    |span| Region::from_span(cm, span),
+    |rule| severities.for_rule(rule).to_sarif_level(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use does_it_throw::{analyze_code, UserSettings};
+  use swc_common::sync::Lrc;
+
+  fn hints() -> SarifSeverities {
+    SarifSeverities {
+      function_throw: DiagnosticSeverity::Hint,
+      throw_statement: DiagnosticSeverity::Warning,
+      call_to_throw: DiagnosticSeverity::Error,
+      call_to_imported_throw: DiagnosticSeverity::Information,
+      promise_rejection: DiagnosticSeverity::Hint,
+      floating_promise: DiagnosticSeverity::Hint,
+      call_to_throwing_builtin: DiagnosticSeverity::Hint,
+      undocumented_throw: DiagnosticSeverity::Hint,
+      stale_throws_tag: DiagnosticSeverity::Hint,
+      throwing_override: DiagnosticSeverity::Hint,
+    }
+  }
+
+  fn results_for(code: &str, imported_throw_ids: &[&str]) -> Vec<SarifResult> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let (results, cm) = analyze_code(code, cm, &UserSettings::default()).unwrap();
+    let imported_throw_ids = imported_throw_ids.iter().map(|id| id.to_string()).collect();
+    sarif_results(
+      &results,
+      &cm,
+      "file:///project/src/a.ts",
+      &imported_throw_ids,
+      &hints(),
+    )
+  }
+
+  #[test]
+  fn test_sarif_results_have_rules_regions_and_fingerprints() {
+    let code = "import { imported, other } from './b'\n\
+      function throws() {\n  throw new Error('a')\n}\n\
+      function calls() {\n  throws()\n  throws()\n  imported()\n  other()\n}\n";
+    let results = results_for(code, &["NOT_SET-imported"]);
+
+    let summary: Vec<_> = results
+      .iter()
+      .map(|result| {
+        (
+          result.rule_id.as_str(),
+          result.level.as_str(),
+          result.partial_fingerprints[FINGERPRINT_KEY].as_str(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        (
+          "does-it-throw/function-that-may-throw",
+          "note",
+          "does-it-throw/function-that-may-throw:NOT_SET-throws:0"
+        ),
+        (
+          "does-it-throw/throw-statement",
+          "warning",
+          "does-it-throw/throw-statement:NOT_SET-throws:0"
+        ),
+        (
+          "does-it-throw/call-to-throw",
+          "error",
+          "does-it-throw/call-to-throw:NOT_SET-throws:0"
+        ),
+        (
+          "does-it-throw/call-to-throw",
+          "error",
+          "does-it-throw/call-to-throw:NOT_SET-throws:1"
+        ),
+        (
+          "does-it-throw/call-to-imported-throw",
+          "note",
+          "does-it-throw/call-to-imported-throw:NOT_SET-imported:0"
+        ),
+      ]
+    );
+
+    let location = &results[1].locations[0].physical_location;
+    assert_eq!(location.artifact_location.uri, "file:///project/src/a.ts");
+    assert_eq!(
+      location.region,
+      Region {
+        start_line: 3,
+        start_column: 3,
+        end_line: 3,
+        end_column: 23,
+      }
+    );
+    assert_eq!(results[2].rule_index, 2);
+  }
+
+  #[test]
+  fn test_sarif_fingerprints_are_stable_when_code_moves() {
+    let before = results_for("function throws() {\n  throw new Error('a')\n}\n", &[]);
+    let after = results_for(
+      "\n\n// moved\nfunction throws() {\n  throw new Error('a')\n}\n",
+      &[],
+    );
+
+    let fingerprints = |results: &[SarifResult]| {
+      results
+        .iter()
+        .map(|result| result.partial_fingerprints.clone())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(fingerprints(&before), fingerprints(&after));
+    assert_ne!(
+      before[0].locations[0].physical_location.region,
+      after[0].locations[0].physical_location.region
+    );
+  }
+
+  #[test]
+  fn test_sarif_log_serialization() {
+    let log = SarifLog::new(results_for(
+      "function throws() {\n  throw new Error('a')\n}\n",
+      &[],
+    ));
+    let json = serde_json::to_value(&log).unwrap();
+
+    assert_eq!(json["version"], "2.1.0");
+    assert_eq!(json["$schema"], SARIF_SCHEMA);
+    let run = &json["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "does-it-throw");
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 10);
+    assert_eq!(
+      run["tool"]["driver"]["rules"][1]["id"],
+      "does-it-throw/throw-statement"
+    );
+    assert_eq!(run["results"][1]["ruleId"], "does-it-throw/throw-statement");
+    assert_eq!(
+      run["results"][1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+      2
+    );
+    assert!(run["results"][1]["partialFingerprints"][FINGERPRINT_KEY].is_string());
+  }
+}
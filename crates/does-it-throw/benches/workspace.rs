@@ -0,0 +1,90 @@
+#[macro_use]
+extern crate criterion;
+extern crate does_it_throw;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use does_it_throw::project::{
+  analyze_project_files_cached, analyze_sources, default_threads, find_source_files,
+};
+use does_it_throw::{cache::AnalysisCache, UserSettings};
+
+// Copies of the fixtures, each under its own directory, standing in for a large workspace
+const WORKSPACE_COPIES: usize = 20;
+
+fn fixtures() -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR")).join("src/fixtures")
+}
+
+fn workspace_sources() -> Vec<(PathBuf, String)> {
+  let fixtures = fixtures();
+  let files = find_source_files(&fixtures).unwrap();
+  (0..WORKSPACE_COPIES)
+    .flat_map(|copy| {
+      let fixtures = &fixtures;
+      files.iter().map(move |file| {
+        let source = fs::read_to_string(file).unwrap();
+        let path = Path::new("/workspace")
+          .join(format!("copy{}", copy))
+          .join(file.strip_prefix(fixtures).unwrap());
+        (path, source)
+      })
+    })
+    .collect()
+}
+
+fn thread_counts() -> Vec<usize> {
+  let mut thread_counts = vec![1, 2, default_threads()];
+  thread_counts.sort();
+  thread_counts.dedup();
+  thread_counts
+}
+
+fn bench_analyze_sources(c: &mut Criterion) {
+  let sources = workspace_sources();
+  let user_settings = UserSettings::default();
+  let mut group = c.benchmark_group("analyze_sources");
+  group.throughput(Throughput::Elements(sources.len() as u64));
+  group.sample_size(10);
+  for threads in thread_counts() {
+    group.bench_with_input(
+      BenchmarkId::from_parameter(threads),
+      &threads,
+      |b, &threads| b.iter(|| analyze_sources(sources.clone(), &user_settings, threads)),
+    );
+  }
+  group.finish();
+}
+
+// Includes reading the files, resolving imports and linking them
+fn bench_analyze_project(c: &mut Criterion) {
+  let root = fixtures();
+  let files = find_source_files(&root).unwrap();
+  let user_settings = UserSettings::default();
+  let mut group = c.benchmark_group("analyze_project");
+  group.throughput(Throughput::Elements(files.len() as u64));
+  for threads in thread_counts() {
+    group.bench_with_input(
+      BenchmarkId::from_parameter(threads),
+      &threads,
+      |b, &threads| {
+        b.iter(|| {
+          analyze_project_files_cached(
+            &root,
+            &files,
+            &user_settings,
+            &mut AnalysisCache::new(),
+            threads,
+          )
+          .unwrap()
+        })
+      },
+    );
+  }
+  group.finish();
+}
+
+criterion_group!(benches, bench_analyze_sources, bench_analyze_project);
+criterion_main!(benches);
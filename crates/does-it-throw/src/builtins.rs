@@ -0,0 +1,129 @@
+extern crate serde;
+extern crate serde_json;
+extern crate swc_common;
+
+use std::collections::HashMap;
+use std::fmt;
+
+use self::serde::{Deserialize, Serialize};
+use self::serde_json::Value;
+use self::swc_common::Span;
+
+// The standard library and Node APIs known to throw, see `builtins.json`
+const DEFAULT_CATALOG: &str = include_str!("builtins.json");
+
+pub const JSON_PARSE_CATEGORY: &str = "json_parse";
+pub const FS_ACCESS_CATEGORY: &str = "fs_access";
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ThrowingBuiltin {
+  // `JSON.parse` for calls and `new URL` for constructors. APIs of Node modules are named after
+  // the module, e.g. `fs.readFileSync` or `child_process.execSync`.
+  pub callee: String,
+  pub error_classes: Vec<String>,
+  // calls in the `json_parse` and `fs_access` categories are also listed in
+  // `json_parse_calls` and `fs_access_calls`
+  pub category: Option<String>,
+}
+
+impl ThrowingBuiltin {
+  pub fn new(callee: &str, error_classes: Vec<String>) -> Self {
+    Self {
+      callee: callee.split_whitespace().collect::<Vec<&str>>().join(" "),
+      error_classes,
+      category: None,
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct CatalogError {
+  pub message: String,
+}
+
+impl fmt::Display for CatalogError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "invalid throwing builtins catalog: {}", self.message)
+  }
+}
+
+impl std::error::Error for CatalogError {}
+
+fn string_array(entry: &Value, key: &str) -> Result<Vec<String>, CatalogError> {
+  match entry.get(key) {
+    None | Some(Value::Null) => Ok(vec![]),
+    Some(Value::Array(values)) => values
+      .iter()
+      .map(|value| {
+        value
+          .as_str()
+          .map(|value| value.to_string())
+          .ok_or(CatalogError {
+            message: format!("`{}` must only contain strings", key),
+          })
+      })
+      .collect(),
+    Some(_) => Err(CatalogError {
+      message: format!("`{}` must be an array of strings", key),
+    }),
+  }
+}
+
+// Parses a catalog in the format of `builtins.json`: an array of
+// `{ "callee": "...", "error_classes": ["..."], "category": "..." }` entries
+pub fn parse_catalog(json: &str) -> Result<Vec<ThrowingBuiltin>, CatalogError> {
+  let value: Value = serde_json::from_str(json).map_err(|error| CatalogError {
+    message: error.to_string(),
+  })?;
+  let entries = value.as_array().ok_or(CatalogError {
+    message: "expected an array of entries".to_string(),
+  })?;
+  entries
+    .iter()
+    .map(|entry| {
+      let callee = entry
+        .get("callee")
+        .and_then(Value::as_str)
+        .ok_or(CatalogError {
+          message: "every entry needs a `callee` string".to_string(),
+        })?;
+      let mut builtin = ThrowingBuiltin::new(callee, string_array(entry, "error_classes")?);
+      builtin.category = entry
+        .get("category")
+        .and_then(Value::as_str)
+        .map(|category| category.to_string());
+      Ok(builtin)
+    })
+    .collect()
+}
+
+pub fn default_catalog() -> Vec<ThrowingBuiltin> {
+  parse_catalog(DEFAULT_CATALOG).expect("the built-in catalog is valid JSON")
+}
+
+// The default catalog keyed by callee, with `extra_builtins` added.
+// An extra entry for a callee that is already in the catalog replaces it.
+pub fn catalog_with(extra_builtins: &[ThrowingBuiltin]) -> HashMap<String, ThrowingBuiltin> {
+  default_catalog()
+    .into_iter()
+    .chain(extra_builtins.iter().cloned())
+    .map(|builtin| (builtin.callee.clone(), builtin))
+    .collect()
+}
+
+// `node:fs` and `fs` are the same module
+pub fn builtin_module_name(specifier: &str) -> &str {
+  specifier.strip_prefix("node:").unwrap_or(specifier)
+}
+
+// A call to (or construction of) a builtin of the catalog
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CallToThrowingBuiltin {
+  pub call_span: Span,
+  pub builtin: ThrowingBuiltin,
+  pub call_function_or_method_name: String,
+  pub call_class_name: Option<String>,
+  pub id: String,
+  // inside the try block of a try statement with a catch clause
+  pub in_try_block: bool,
+}
@@ -0,0 +1,506 @@
+extern crate serde_json;
+extern crate swc_common;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use self::serde_json::{json, Value};
+use self::swc_common::BytePos;
+
+use crate::project::{analyze_sources, FileAnalysis};
+use crate::symbol::{ReExport, ReExportKind};
+use crate::{AnalysisResult, UserSettings};
+
+// ----- Analysis cache -----
+// Keeps the analysis of each file along with its content hash, so that files are only analyzed
+// again when their content (or the settings) changed. Each file is summarized by what it imports,
+// what it re-exports and its throwing functions. The cache can be saved to disk and loaded on
+// the next start, with each file's summary and analysis result: files whose content is unchanged
+// are then not analyzed again, and the first change to a file invalidates the files that
+// depended on it in the previous session.
+//
+// Other files only see a file through its exports, so when a file's exports change (a function
+// starts or stops throwing, a re-export is added...), the files depending on it are invalidated:
+// the files importing it, and the files importing those, so that barrel files are covered.
+
+// Bump when the format of the saved cache changes, older caches are then ignored
+const CACHE_VERSION: u64 = 2;
+
+// FNV-1a, which unlike the std hashers is stable across Rust versions, so that saved hashes stay
+// valid
+pub fn content_hash(content: &str) -> u64 {
+  content.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+    (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+  })
+}
+
+// Every setting that changes what an analysis finds, serialized explicitly so that the hash does
+// not depend on how the settings are formatted for debugging
+fn settings_hash(user_settings: &UserSettings) -> u64 {
+  let throwing_builtins: Vec<Value> = user_settings
+    .throwing_builtins
+    .iter()
+    .map(|builtin| {
+      json!({
+        "callee": builtin.callee,
+        "error_classes": builtin.error_classes,
+        "category": builtin.category,
+      })
+    })
+    .collect();
+  let settings = json!({
+    "include_try_statement_throws": user_settings.include_try_statement_throws,
+    "ignore_statements": user_settings.ignore_statements,
+    "recover_from_parse_errors": user_settings.recover_from_parse_errors,
+    "max_propagation_depth": user_settings.max_propagation_depth,
+    "throwing_builtins": throwing_builtins,
+  });
+  content_hash(&settings.to_string())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ThrowingFunction {
+  // the id calls from other modules are matched by, see `ThrowMap::import_id`
+  pub id: String,
+  // one for each name the function is exported under, empty for functions that are not exported
+  pub export_paths: Vec<Vec<String>>,
+  pub error_classes: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSummary {
+  pub content_hash: u64,
+  // module specifiers as written, e.g. `./api`
+  pub imports: BTreeSet<String>,
+  pub re_exports: Vec<ReExport>,
+  // sorted by id
+  pub throwing_functions: Vec<ThrowingFunction>,
+}
+
+impl FileSummary {
+  pub fn new(content_hash: u64, result: &AnalysisResult) -> Self {
+    let mut throwing_functions: Vec<ThrowingFunction> = result
+      .functions_with_throws
+      .iter()
+      .map(|throw_map| ThrowingFunction {
+        id: throw_map.import_id(),
+        export_paths: throw_map.symbol.export_paths(),
+        error_classes: throw_map.error_classes(),
+      })
+      .collect();
+    throwing_functions.sort();
+    throwing_functions.dedup();
+    FileSummary {
+      content_hash,
+      imports: result.import_sources.iter().cloned().collect(),
+      re_exports: result.re_exports.clone(),
+      throwing_functions,
+    }
+  }
+
+  // What other files see of this one: its re-exports and its exported functions that throw
+  pub fn same_exports(&self, other: &FileSummary) -> bool {
+    let throwing_exports = |summary: &FileSummary| -> Vec<ThrowingFunction> {
+      summary
+        .throwing_functions
+        .iter()
+        .filter(|function| !function.export_paths.is_empty())
+        .cloned()
+        .collect()
+    };
+    self.re_exports == other.re_exports && throwing_exports(self) == throwing_exports(other)
+  }
+}
+
+// What an update changed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheUpdate {
+  // whether the file was analyzed again, `false` when its content was unchanged, in this session
+  // or the one the cache was saved by
+  pub analyzed: bool,
+  // the files depending on the updated one that need their imports linked again
+  pub invalidated: BTreeSet<PathBuf>,
+}
+
+struct CacheEntry {
+  summary: FileSummary,
+  // the files the imports of the file resolved to
+  dependencies: BTreeSet<PathBuf>,
+  // `None` for entries loaded from disk until their file is updated
+  analysis: Option<FileAnalysis>,
+  // the analysis result and its start position loaded from disk, `None` when the entry was saved
+  // without one
+  saved_result: Option<(BytePos, AnalysisResult)>,
+}
+
+#[derive(Default)]
+pub struct AnalysisCache {
+  settings_hash: Option<u64>,
+  entries: BTreeMap<PathBuf, CacheEntry>,
+}
+
+impl AnalysisCache {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  // Analyzes `source` as the content of `path`, unless it was already analyzed with the same
+  // content and settings, in this session or a previous one whose cache was loaded
+  pub fn update(
+    &mut self,
+    path: &Path,
+    source: String,
+    user_settings: &UserSettings,
+  ) -> CacheUpdate {
+    self
+      .update_all(vec![(path.to_path_buf(), source)], user_settings, 1)
+      .pop()
+      .unwrap_or_default()
+  }
+
+  // Like `update` for each (path, source) of `sources`, analyzing the changed files on up to
+  // `threads` threads. The updates are returned in the order of `sources`.
+  pub fn update_all(
+    &mut self,
+    sources: Vec<(PathBuf, String)>,
+    user_settings: &UserSettings,
+    threads: usize,
+  ) -> Vec<CacheUpdate> {
+    self.update_sources(sources, user_settings, threads, false)
+  }
+
+  // Like `update_all`, also analyzing the unchanged files that are only known by a summary
+  // loaded from disk, so that `analysis` returns the analysis of every file of `sources`
+  pub fn analyze_all(
+    &mut self,
+    sources: Vec<(PathBuf, String)>,
+    user_settings: &UserSettings,
+    threads: usize,
+  ) -> Vec<CacheUpdate> {
+    self.update_sources(sources, user_settings, threads, true)
+  }
+
+  fn update_sources(
+    &mut self,
+    sources: Vec<(PathBuf, String)>,
+    user_settings: &UserSettings,
+    threads: usize,
+    needs_analysis: bool,
+  ) -> Vec<CacheUpdate> {
+    let settings_hash = settings_hash(user_settings);
+    if self.settings_hash != Some(settings_hash) {
+      // anything analyzed with other settings may be wrong
+      self.entries.clear();
+      self.settings_hash = Some(settings_hash);
+    }
+
+    let mut updates = vec![CacheUpdate::default(); sources.len()];
+    let mut changed: Vec<(usize, u64)> = vec![];
+    let mut changed_sources: Vec<(PathBuf, String)> = vec![];
+    for (index, (path, source)) in sources.into_iter().enumerate() {
+      let hash = content_hash(&source);
+      let Some(entry) = self
+        .entries
+        .get_mut(&path)
+        .filter(|entry| entry.summary.content_hash == hash)
+      else {
+        changed.push((index, hash));
+        changed_sources.push((path, source));
+        continue;
+      };
+      if entry.analysis.is_none() {
+        // the saved result is still right, only the source was missing
+        if let Some((start_pos, result)) = entry.saved_result.take() {
+          entry.analysis = Some(FileAnalysis {
+            path,
+            source,
+            start_pos,
+            result,
+            resolved_imports: BTreeMap::new(),
+            imported_throws: vec![],
+          });
+        } else if needs_analysis {
+          changed.push((index, hash));
+          changed_sources.push((path, source));
+        }
+      }
+    }
+    let analyses = analyze_sources(changed_sources, user_settings, threads);
+    for ((index, hash), analysis) in changed.into_iter().zip(analyses) {
+      updates[index] = self.insert(hash, analysis);
+    }
+    updates
+  }
+
+  fn insert(&mut self, hash: u64, analysis: FileAnalysis) -> CacheUpdate {
+    let path = analysis.path.clone();
+    if let Some(entry) = self.entries.get_mut(&path) {
+      if entry.summary.content_hash == hash {
+        // loaded from disk: the summary is still right, only the analysis was missing
+        entry.analysis = Some(analysis);
+        return CacheUpdate {
+          analyzed: true,
+          invalidated: BTreeSet::new(),
+        };
+      }
+    }
+
+    let summary = FileSummary::new(hash, &analysis.result);
+    let exports_changed = self
+      .entries
+      .get(&path)
+      .is_none_or(|entry| !entry.summary.same_exports(&summary));
+    let dependencies = self
+      .entries
+      .remove(&path)
+      .map(|entry| entry.dependencies)
+      .unwrap_or_default();
+    self.entries.insert(
+      path.clone(),
+      CacheEntry {
+        summary,
+        dependencies,
+        analysis: Some(analysis),
+        saved_result: None,
+      },
+    );
+    CacheUpdate {
+      analyzed: true,
+      invalidated: if exports_changed {
+        self.dependents(&path)
+      } else {
+        BTreeSet::new()
+      },
+    }
+  }
+
+  // Reads and analyzes the file at `path`, see `update`
+  pub fn update_file(
+    &mut self,
+    path: &Path,
+    user_settings: &UserSettings,
+  ) -> io::Result<CacheUpdate> {
+    let source = fs::read_to_string(path)?;
+    Ok(self.update(path, source, user_settings))
+  }
+
+  // Records the files the imports of `path` resolved to, the files it is invalidated by
+  pub fn set_dependencies(&mut self, path: &Path, dependencies: BTreeSet<PathBuf>) {
+    if let Some(entry) = self.entries.get_mut(path) {
+      entry.dependencies = dependencies;
+    }
+  }
+
+  // Forgets a deleted file, returning the files that depended on it
+  pub fn remove(&mut self, path: &Path) -> BTreeSet<PathBuf> {
+    let dependents = self.dependents(path);
+    self.entries.remove(path);
+    dependents
+  }
+
+  pub fn summary(&self, path: &Path) -> Option<&FileSummary> {
+    self.entries.get(path).map(|entry| &entry.summary)
+  }
+
+  pub fn analysis(&self, path: &Path) -> Option<&FileAnalysis> {
+    self.entries.get(path)?.analysis.as_ref()
+  }
+
+  // The files importing `path`, directly or through other files
+  pub fn dependents(&self, path: &Path) -> BTreeSet<PathBuf> {
+    let mut dependents: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dependency) = pending.pop() {
+      for (dependent, entry) in &self.entries {
+        if entry.dependencies.contains(&dependency)
+          && dependent != path
+          && dependents.insert(dependent.clone())
+        {
+          pending.push(dependent.clone());
+        }
+      }
+    }
+    dependents
+  }
+
+  pub fn to_json(&self) -> Value {
+    let files: serde_json::Map<String, Value> = self
+      .entries
+      .iter()
+      .map(|(path, entry)| {
+        let mut file = summary_to_json(&entry.summary, &entry.dependencies);
+        let result = match (&entry.analysis, &entry.saved_result) {
+          (Some(analysis), _) => Some((analysis.start_pos, &analysis.result)),
+          (None, Some((start_pos, result))) => Some((*start_pos, result)),
+          (None, None) => None,
+        };
+        if let Some((start_pos, result)) = result {
+          file["start_pos"] = json!(start_pos.0);
+          file["result"] = serde_json::to_value(result).unwrap_or_default();
+        }
+        (path.to_string_lossy().to_string(), file)
+      })
+      .collect();
+    json!({
+      "version": CACHE_VERSION,
+      "settings_hash": self.settings_hash.map(|hash| format!("{:016x}", hash)),
+      "files": files,
+    })
+  }
+
+  // The summaries and analysis results of a saved cache. Caches of another version are ignored,
+  // as are entries that cannot be read.
+  pub fn from_json(value: &Value) -> Self {
+    if value["version"].as_u64() != Some(CACHE_VERSION) {
+      return Self::default();
+    }
+    let entries = value["files"]
+      .as_object()
+      .into_iter()
+      .flatten()
+      .filter_map(|(path, file)| {
+        let (summary, dependencies) = summary_from_json(file)?;
+        // without its result, the file is analyzed again when its analysis is needed
+        let saved_result = file["start_pos"].as_u64().and_then(|start_pos| {
+          let result = serde_json::from_value(file["result"].clone()).ok()?;
+          Some((BytePos(start_pos as u32), result))
+        });
+        Some((
+          PathBuf::from(path),
+          CacheEntry {
+            summary,
+            dependencies,
+            analysis: None,
+            saved_result,
+          },
+        ))
+      })
+      .collect();
+    Self {
+      settings_hash: value["settings_hash"].as_str().and_then(parse_hash),
+      entries,
+    }
+  }
+
+  // Loads a cache saved with `save`, an empty cache when there is none at `path` yet
+  pub fn load(path: &Path) -> io::Result<Self> {
+    let content = match fs::read_to_string(path) {
+      Ok(content) => content,
+      Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+      Err(error) => return Err(error),
+    };
+    // a corrupt cache is only a cold start
+    Ok(
+      serde_json::from_str(&content)
+        .map(|value| Self::from_json(&value))
+        .unwrap_or_default(),
+    )
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::write(path, self.to_json().to_string())
+  }
+}
+
+fn parse_hash(hash: &str) -> Option<u64> {
+  u64::from_str_radix(hash, 16).ok()
+}
+
+fn strings(value: &Value) -> Option<Vec<String>> {
+  value
+    .as_array()?
+    .iter()
+    .map(|string| string.as_str().map(str::to_string))
+    .collect()
+}
+
+fn re_export_to_json(re_export: &ReExport) -> Value {
+  match &re_export.kind {
+    ReExportKind::All => json!({ "source": re_export.source, "kind": "all" }),
+    ReExportKind::Namespace(name) => {
+      json!({ "source": re_export.source, "kind": "namespace", "name": name })
+    }
+    ReExportKind::Named { orig, exported } => json!({
+      "source": re_export.source,
+      "kind": "named",
+      "orig": orig,
+      "exported": exported,
+    }),
+  }
+}
+
+fn re_export_from_json(value: &Value) -> Option<ReExport> {
+  let kind = match value["kind"].as_str()? {
+    "all" => ReExportKind::All,
+    "namespace" => ReExportKind::Namespace(value["name"].as_str()?.to_string()),
+    "named" => ReExportKind::Named {
+      orig: value["orig"].as_str()?.to_string(),
+      exported: value["exported"].as_str()?.to_string(),
+    },
+    _ => return None,
+  };
+  Some(ReExport {
+    source: value["source"].as_str()?.to_string(),
+    kind,
+  })
+}
+
+fn summary_to_json(summary: &FileSummary, dependencies: &BTreeSet<PathBuf>) -> Value {
+  json!({
+    // hex, as JSON numbers cannot hold every `u64` in JavaScript
+    "content_hash": format!("{:016x}", summary.content_hash),
+    "imports": summary.imports,
+    "re_exports": summary.re_exports.iter().map(re_export_to_json).collect::<Vec<Value>>(),
+    "throwing_functions": summary
+      .throwing_functions
+      .iter()
+      .map(|function| json!({
+        "id": function.id,
+        "export_paths": function.export_paths,
+        "error_classes": function.error_classes,
+      }))
+      .collect::<Vec<Value>>(),
+    "dependencies": dependencies
+      .iter()
+      .map(|dependency| dependency.to_string_lossy().to_string())
+      .collect::<Vec<String>>(),
+  })
+}
+
+fn summary_from_json(value: &Value) -> Option<(FileSummary, BTreeSet<PathBuf>)> {
+  let throwing_functions = value["throwing_functions"]
+    .as_array()?
+    .iter()
+    .map(|function| {
+      Some(ThrowingFunction {
+        id: function["id"].as_str()?.to_string(),
+        export_paths: function["export_paths"]
+          .as_array()?
+          .iter()
+          .map(strings)
+          .collect::<Option<Vec<Vec<String>>>>()?,
+        error_classes: strings(&function["error_classes"])?,
+      })
+    })
+    .collect::<Option<Vec<ThrowingFunction>>>()?;
+  let summary = FileSummary {
+    content_hash: parse_hash(value["content_hash"].as_str()?)?,
+    imports: strings(&value["imports"])?.into_iter().collect(),
+    re_exports: value["re_exports"]
+      .as_array()?
+      .iter()
+      .map(re_export_from_json)
+      .collect::<Option<Vec<ReExport>>>()?,
+    throwing_functions,
+  };
+  let dependencies = strings(&value["dependencies"])?
+    .into_iter()
+    .map(PathBuf::from)
+    .collect();
+  Some((summary, dependencies))
+}
@@ -1,3 +1,4 @@
+extern crate serde;
 extern crate swc_common;
 extern crate swc_ecma_ast;
 extern crate swc_ecma_parser;
@@ -8,18 +9,28 @@ use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
 use swc_ecma_ast::{
-  ArrowExpr, AwaitExpr, BinExpr, BlockStmtOrExpr, Callee, ClassDecl, ClassMethod, Decl, FnDecl,
-  JSXAttr, JSXAttrOrSpread, JSXAttrValue, JSXExpr, JSXOpeningElement, MemberExpr, OptChainBase,
-  OptChainExpr, ParenExpr, Stmt, VarDeclarator,
+  ArrowExpr, AwaitExpr, BinExpr, BlockStmtOrExpr, Callee, Class, ClassDecl, ClassMethod, Decl,
+  DefaultDecl, ExportDefaultDecl, FnDecl, Ident, ImportDecl, ImportSpecifier, JSXAttr,
+  JSXAttrOrSpread, JSXAttrValue, JSXExpr, JSXOpeningElement, MemberExpr, ModuleExportName, NewExpr,
+  OptChainBase, OptChainExpr, ParenExpr, PrivateMethod, Stmt, SuperProp, TryStmt, VarDeclarator,
 };
 
+use crate::builtins::{builtin_module_name, CallToThrowingBuiltin, ThrowingBuiltin};
+use crate::class_hierarchy::ClassHierarchy;
+use crate::commonjs::required_bindings;
+use crate::scope_analysis::ScopeAnalysis;
+use crate::symbol::{
+  default_export_name, member_prop_name, private_name, prop_name, ModuleSymbols, SymbolId,
+};
 use crate::throw_finder::ThrowMap;
 
-use self::swc_common::Span;
+use self::serde::{Deserialize, Serialize};
+use self::swc_common::{sync::Lrc, Span, Spanned};
 use self::swc_ecma_ast::{CallExpr, Expr, Function, MemberProp};
 
 use self::swc_ecma_visit::Visit;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CallToThrowMap {
   pub call_span: Span,
   pub call_function_or_method_name: String,
@@ -27,11 +38,47 @@ pub struct CallToThrowMap {
   pub throw_map: ThrowMap,
   pub class_name: Option<String>,
   pub id: String,
+  // The function making the call, or the module for top-level calls
+  pub symbol: SymbolId,
+  // Ids from the called function down to the function containing the throw statement
+  pub propagation_path: Vec<String>,
+}
+
+// The path of a call to `throw_map`'s function. Recursive functions are already on their own
+// path, which stops at the first function it would visit twice so that it does not grow by the
+// same callee every propagation round.
+pub fn propagation_path_through(throw_map: &ThrowMap) -> Vec<String> {
+  let mut propagation_path = vec![throw_map.id.clone()];
+  for id in &throw_map.propagation_path {
+    if propagation_path.contains(id) {
+      break;
+    }
+    propagation_path.push(id.clone());
+  }
+  propagation_path
+}
+
+// Whether the throwing function is passed to the call, e.g. `onReady(() => { throw ... })`
+fn receives_throwing_callback(call: &CallExpr, throw_map: &ThrowMap) -> bool {
+  call
+    .args
+    .iter()
+    .any(|arg| arg.expr.span() == throw_map.throw_statement)
+}
+
+impl CallToThrowMap {
+  // Error classes the called function may throw, e.g. `["ValidationError", "NotFoundError"]`
+  pub fn error_classes(&self) -> Vec<String> {
+    self.throw_map.error_classes()
+  }
 }
 
 impl PartialEq for CallToThrowMap {
   fn eq(&self, other: &Self) -> bool {
-    self.id == other.id
+    // must agree with `Hash`, otherwise calls from the same symbol are deduplicated at random
+    self.symbol == other.symbol
+      && self.call_span.lo == other.call_span.lo
+      && self.call_span.hi == other.call_span.hi
   }
 }
 
@@ -39,7 +86,7 @@ impl Eq for CallToThrowMap {}
 
 impl Hash for CallToThrowMap {
   fn hash<H: Hasher>(&self, state: &mut H) {
-    self.id.hash(state);
+    self.symbol.hash(state);
     self.call_span.lo.hash(state);
     self.call_span.hi.hash(state);
   }
@@ -69,21 +116,28 @@ impl Hash for InstantiationsMap {
 // This module defines structures and implements functionality for identifying and mapping
 // function calls to their respective functions or methods that throw exceptions. It uses
 // SWC's visitor pattern to traverse the AST (Abstract Syntax Tree) of JavaScript or TypeScript code.
-///
+//
 // `CallToThrowMap` records the mapping of a function call to a function that throws.
 // It captures the span of the call, the name of the function/method being called,
 // the class name if the call is a method call, and the `ThrowMap` that provides details
 // about the throw statement in the called function/method.
-///
+//
 // `InstantiationsMap` keeps track of class instantiations by recording the class name
 // and the variable name that holds the instance.
-///
+//
 // `CallFinder` is the core structure that uses the Visitor pattern to traverse the AST nodes.
 // It maintains state as it goes through the code, keeping track of current class names,
 // function name stacks, and object property stacks. As it finds function calls, it tries
 // to match them with known functions or methods that throw exceptions using the data
 // accumulated in `functions_with_throws`. When a match is found, it records the mapping
 // in `calls`. It also tracks instantiations of classes to help resolve method calls.
+//
+// Calls to the standard library and Node APIs in `builtins` are recorded in `builtin_calls`.
+// Imports of Node modules are tracked in `builtin_aliases`, so that `readFileSync` imported
+// from `node:fs` is known as `fs.readFileSync`. Only globals and such imports are matched, so
+// that a local `JSON` or a parameter named `fs` are not taken for the builtins. Builtin calls
+// are reported where they are made but do not propagate: the functions making them are not
+// marked as throwing, and neither are their callers.
 
 pub struct CallFinder {
   pub calls: HashSet<CallToThrowMap>,
@@ -93,6 +147,88 @@ pub struct CallFinder {
   pub function_name_stack: Vec<String>,
   pub object_property_stack: Vec<String>,
   pub processed_calls: HashSet<u64>,
+  // The throwing builtins catalog, keyed by callee (see `builtins::catalog_with`)
+  pub builtins: HashMap<String, ThrowingBuiltin>,
+  // Local bindings of imports, keyed by the span of the identifier declaring them, e.g. `cp` ->
+  // `child_process` for `import * as cp from 'child_process'` or `read` -> `fs.readFileSync` for
+  // `import { readFileSync as read } from 'node:fs'`
+  pub builtin_aliases: HashMap<Span, String>,
+  pub builtin_calls: Vec<CallToThrowingBuiltin>,
+  // How many try blocks with a catch clause enclose the current position
+  pub try_block_depth: usize,
+  pub symbols: Lrc<ModuleSymbols>,
+  pub scope_analysis: Lrc<ScopeAnalysis>,
+  pub class_hierarchy: Lrc<ClassHierarchy>,
+}
+
+// The class or object literal a method is called on, and the path to the method, e.g. the
+// `Config` object literal and `["nested", "load"]` for `Config.nested.load()`.
+// `None` when the object is not declared in the module, e.g. a parameter or an import.
+fn member_call_target(
+  symbols: &ModuleSymbols,
+  scope_analysis: &ScopeAnalysis,
+  member_expr: &MemberExpr,
+  call_span: Span,
+) -> Option<(SymbolId, Vec<String>)> {
+  let mut path = vec![];
+  let mut member_expr = member_expr;
+  loop {
+    path.insert(0, member_prop_name(&member_expr.prop)?);
+    match &*member_expr.obj {
+      Expr::Member(obj) => member_expr = obj,
+      Expr::This(_) => return Some((symbols.enclosing_container(call_span)?, path)),
+      Expr::Ident(obj) => {
+        let declared_span = scope_analysis.declared_span(obj.span)?;
+        return Some((symbols.symbol_declared_at(declared_span)?, path));
+      }
+      _ => return None,
+    }
+  }
+}
+
+// The function or method declared in the module that a call to `callee` runs: functions are
+// found through the scope analysis, so that a local binding shadowing them is not taken for
+// them, and methods through the class hierarchy, e.g. `service.load()` on a `new Service()`
+pub fn called_symbol(
+  symbols: &ModuleSymbols,
+  scope_analysis: &ScopeAnalysis,
+  class_hierarchy: &ClassHierarchy,
+  callee: &Expr,
+  call_span: Span,
+) -> Option<SymbolId> {
+  match callee {
+    Expr::Ident(ident) => symbols.symbol_declared_at(scope_analysis.declared_span(ident.span)?),
+    Expr::Member(member_expr) => {
+      let (container, path) = member_call_target(symbols, scope_analysis, member_expr, call_span)?;
+      class_hierarchy.called_member(symbols, &container, &path)
+    }
+    _ => None,
+  }
+}
+
+// `a` in `a.b.c(...)`
+fn root_ident(expr: &Expr) -> Option<&Ident> {
+  match expr {
+    Expr::Ident(ident) => Some(ident),
+    Expr::Member(member_expr) => root_ident(&member_expr.obj),
+    Expr::Paren(paren_expr) => root_ident(&paren_expr.expr),
+    _ => None,
+  }
+}
+
+// `a`, `a.b` and `a.b.c` style names, `None` for anything computed
+fn callee_name(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Ident(ident) => Some(ident.sym.to_string()),
+    Expr::Member(member_expr) => match &member_expr.prop {
+      MemberProp::Ident(prop) => {
+        callee_name(&member_expr.obj).map(|obj| format!("{}.{}", obj, prop.sym))
+      }
+      _ => None,
+    },
+    Expr::Paren(paren_expr) => callee_name(&paren_expr.expr),
+    _ => None,
+  }
 }
 
 impl CallFinder {
@@ -104,14 +240,14 @@ impl CallFinder {
     let mut hasher = DefaultHasher::new();
 
     if let Callee::Expr(expr) = &call.callee {
-        if let Expr::Member(member_expr) = &**expr {
-            if let Expr::Ident(instance_ident) = &*member_expr.obj {
-                if let Some(inst_map) = self.instantiations.get(&instance_ident.sym.to_string()) {
-                    // Use the instantiation span for instances
-                    inst_map.instantiation_span.hash(&mut hasher);
-                }
-            }
+      if let Expr::Member(member_expr) = &**expr {
+        if let Expr::Ident(instance_ident) = &*member_expr.obj {
+          if let Some(inst_map) = self.instantiations.get(&instance_ident.sym.to_string()) {
+            // Use the instantiation span for instances
+            inst_map.instantiation_span.hash(&mut hasher);
+          }
         }
+      }
     }
 
     // Also hash the call span to ensure uniqueness
@@ -119,6 +255,113 @@ impl CallFinder {
     hasher.finish()
   }
 
+  // The catalog name of a callee, with the import it is bound to resolved. `None` when it is
+  // bound to a local declaration rather than a global or an import of a Node module.
+  fn builtin_callee_name(&self, callee: &Expr) -> Option<String> {
+    let root = root_ident(callee)?;
+    let name = callee_name(callee)?;
+    match self.scope_analysis.binding_at(root.span) {
+      None => Some(
+        name
+          .strip_prefix("globalThis.")
+          .unwrap_or(&name)
+          .to_string(),
+      ),
+      Some(binding) => {
+        let qualified_name = self.builtin_aliases.get(&binding.span)?;
+        Some(format!("{}{}", qualified_name, &name[root.sym.len()..]))
+      }
+    }
+  }
+
+  fn record_builtin_call(&mut self, call_span: Span, callee: &str) {
+    let builtin = match self.builtins.get(callee) {
+      Some(builtin) => builtin.clone(),
+      None => return,
+    };
+    if self
+      .builtin_calls
+      .iter()
+      .any(|builtin_call| builtin_call.call_span == call_span)
+    {
+      return;
+    }
+    let call_function_or_method_name = self
+      .function_name_stack
+      .last()
+      .or(self.object_property_stack.last())
+      .cloned()
+      .unwrap_or_else(|| "<anonymous>".to_string());
+    self.builtin_calls.push(CallToThrowingBuiltin {
+      call_span,
+      builtin,
+      call_class_name: self.current_class_name.clone(),
+      id: format!(
+        "{}-{}",
+        self
+          .current_class_name
+          .clone()
+          .unwrap_or_else(|| "NOT_SET".to_string()),
+        call_function_or_method_name
+      ),
+      call_function_or_method_name,
+      in_try_block: self.try_block_depth > 0,
+    });
+  }
+
+  // `current_class_name` is restored afterwards, for classes declared inside the methods of
+  // another class
+  fn visit_class_named(&mut self, class_name: String, class: &Class) {
+    let outer_class_name = self.current_class_name.replace(class_name);
+    self.visit_class(class);
+    self.current_class_name = outer_class_name;
+  }
+
+  // `super.name(...)`, or `super(...)` for the constructor, runs the method of the nearest
+  // ancestor of the enclosing class that declares it
+  fn record_super_call(&mut self, call: &CallExpr, name: &str) {
+    let Some(class) = self.symbols.enclosing_container(call.span) else {
+      return;
+    };
+    let Some(parent) = self.class_hierarchy.parent(&class) else {
+      return;
+    };
+    let Some(called) =
+      self
+        .class_hierarchy
+        .called_member(&self.symbols, parent, &[name.to_string()])
+    else {
+      return;
+    };
+    let Some(throw_map) = self
+      .functions_with_throws
+      .iter()
+      .find(|throw_map| throw_map.symbol == called)
+      .cloned()
+    else {
+      return;
+    };
+    let caller = self.symbols.enclosing_symbol(call.span);
+    let call_to_throw_map = CallToThrowMap {
+      call_span: call.span,
+      propagation_path: propagation_path_through(&throw_map),
+      call_function_or_method_name: caller.name.clone(),
+      call_class_name: self.current_class_name.clone(),
+      class_name: Some(parent.name.clone()),
+      id: format!(
+        "{}-{}",
+        self
+          .current_class_name
+          .clone()
+          .unwrap_or_else(|| "NOT_SET".to_string()),
+        caller.name
+      ),
+      throw_map,
+      symbol: caller,
+    };
+    self.calls.insert(call_to_throw_map);
+  }
+
   fn handle_bin_expr(&mut self, bin_expr: &BinExpr) {
     if let Expr::Call(call_expr) = &*bin_expr.left {
       self.visit_call_expr(call_expr);
@@ -175,9 +418,26 @@ impl CallFinder {
 
 impl Visit for CallFinder {
   fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
-    self.current_class_name = Some(class_decl.ident.sym.to_string());
-    self.visit_class(&class_decl.class);
-    self.current_class_name = None;
+    self.visit_class_named(class_decl.ident.sym.to_string(), &class_decl.class);
+  }
+
+  fn visit_export_default_decl(&mut self, export_default_decl: &ExportDefaultDecl) {
+    match &export_default_decl.decl {
+      DefaultDecl::Fn(fn_expr) => {
+        self
+          .function_name_stack
+          .push(default_export_name(fn_expr.ident.as_ref()));
+        self.visit_function(&fn_expr.function);
+        self.function_name_stack.pop();
+      }
+      DefaultDecl::Class(class_expr) => {
+        self.visit_class_named(
+          default_export_name(class_expr.ident.as_ref()),
+          &class_expr.class,
+        );
+      }
+      DefaultDecl::TsInterfaceDecl(_) => {}
+    }
   }
 
   fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
@@ -198,14 +458,23 @@ impl Visit for CallFinder {
   }
 
   fn visit_class_method(&mut self, method: &ClassMethod) {
-    if let Some(method_ident) = method.key.as_ident() {
-      self
-        .object_property_stack
-        .push(method_ident.sym.to_string());
+    let method_name = prop_name(&method.key);
+    if let Some(method_name) = &method_name {
+      self.object_property_stack.push(method_name.clone());
     }
 
     swc_ecma_visit::visit_class_method(self, method);
 
+    if method_name.is_some() {
+      self.object_property_stack.pop();
+    }
+  }
+
+  fn visit_private_method(&mut self, method: &PrivateMethod) {
+    self.object_property_stack.push(private_name(&method.key));
+
+    swc_ecma_visit::visit_private_method(self, method);
+
     self.object_property_stack.pop();
   }
 
@@ -229,6 +498,16 @@ impl Visit for CallFinder {
   }
 
   fn visit_call_expr(&mut self, call: &CallExpr) {
+    if let Callee::Super(_) = &call.callee {
+      if self
+        .processed_calls
+        .insert(self.generate_unique_call_id(call))
+      {
+        self.record_super_call(call, "constructor");
+        swc_ecma_visit::visit_call_expr(self, call);
+      }
+      return;
+    }
     if let Callee::Expr(expr) = &call.callee {
       let call_id = self.generate_unique_call_id(call);
       // If we've already processed this call, skip it
@@ -236,6 +515,9 @@ impl Visit for CallFinder {
         // This call was already processed, so return early
         return;
       }
+      if let Some(builtin_callee) = self.builtin_callee_name(expr) {
+        self.record_builtin_call(call.span, &builtin_callee);
+      }
       match &**expr {
         Expr::Member(member_expr) => {
           let mut possible_class_name = None;
@@ -254,8 +536,14 @@ impl Visit for CallFinder {
             }
           }
 
-          if let MemberProp::Ident(method_ident) = &member_expr.prop {
-            let called_method_name = method_ident.sym.to_string();
+          if let Some(method_name) = member_prop_name(&member_expr.prop) {
+            let called_member = called_symbol(
+              &self.symbols,
+              &self.scope_analysis,
+              &self.class_hierarchy,
+              expr,
+              call.span,
+            );
             for throw_map in self.functions_with_throws.iter() {
               let call_function_or_method_name =
                 if let Some(function_name) = self.function_name_stack.last() {
@@ -265,7 +553,8 @@ impl Visit for CallFinder {
                 } else {
                   "<anonymous>".to_string()
                 };
-              if throw_map.function_or_method_name == called_method_name {
+              let calls_throw_map = called_member.as_ref() == Some(&throw_map.symbol);
+              if calls_throw_map || receives_throwing_callback(call, throw_map) {
                 let class_name_or_not_set = self
                   .current_class_name
                   .clone()
@@ -273,6 +562,7 @@ impl Visit for CallFinder {
                   .unwrap_or_else(|| "NOT_SET".to_string());
                 let call_to_throw_map = CallToThrowMap {
                   call_span: call.span,
+                  propagation_path: propagation_path_through(throw_map),
                   throw_map: throw_map.clone(),
                   call_class_name: Some(class_name_or_not_set.clone()),
                   call_function_or_method_name: call_function_or_method_name.clone(),
@@ -282,13 +572,14 @@ impl Visit for CallFinder {
                     class_name_or_not_set,
                     call_function_or_method_name.clone()
                   ),
+                  symbol: self.symbols.enclosing_symbol(call.span),
                 };
                 self.calls.insert(call_to_throw_map);
                 break;
               }
             }
             for arg in &call.args {
-              self.function_name_stack.push(method_ident.sym.to_string());
+              self.function_name_stack.push(method_name.clone());
               self.current_class_name = possible_class_name.clone();
               if let Expr::Arrow(arrow_expr) = &*arg.expr {
                 self.visit_arrow_expr(arrow_expr);
@@ -301,18 +592,24 @@ impl Visit for CallFinder {
             }
           }
         }
+        Expr::SuperProp(super_prop_expr) => {
+          if let SuperProp::Ident(method_ident) = &super_prop_expr.prop {
+            self.record_super_call(call, &method_ident.sym);
+          }
+        }
         Expr::Ident(ident) => {
           let called_function_name = ident.sym.to_string();
+          let called_function = called_symbol(
+            &self.symbols,
+            &self.scope_analysis,
+            &self.class_hierarchy,
+            expr,
+            call.span,
+          );
           for throw_map in self.functions_with_throws.iter() {
-            let potential_throw_id = format!(
-              "{}-{}",
-              self
-                .current_class_name
-                .clone()
-                .unwrap_or_else(|| "NOT_SET".to_string()),
-              called_function_name
-            );
-            if throw_map.id == potential_throw_id {
+            if called_function.as_ref() == Some(&throw_map.symbol)
+              || receives_throwing_callback(call, throw_map)
+            {
               let call_function_or_method_name = self
                 .function_name_stack
                 .last()
@@ -321,6 +618,7 @@ impl Visit for CallFinder {
               // The function being called is known to throw
               let call_to_throw_map = CallToThrowMap {
                 call_span: call.span,
+                propagation_path: propagation_path_through(throw_map),
                 throw_map: throw_map.clone(),
                 call_class_name: self.current_class_name.clone(),
                 call_function_or_method_name: call_function_or_method_name.clone(),
@@ -333,6 +631,7 @@ impl Visit for CallFinder {
                     .unwrap_or_else(|| "NOT_SET".to_string()),
                   call_function_or_method_name
                 ),
+                symbol: self.symbols.enclosing_symbol(call.span),
               };
               self.calls.insert(call_to_throw_map);
               break;
@@ -351,28 +650,107 @@ impl Visit for CallFinder {
         }
         _ => {}
       }
+      // calls nested in the arguments, e.g. `JSON.parse(fs.readFileSync(path))`
+      for arg in &call.args {
+        if !matches!(&*arg.expr, Expr::Arrow(_) | Expr::Fn(_)) {
+          self.visit_expr(&arg.expr);
+        }
+      }
+    }
+  }
+
+  fn visit_new_expr(&mut self, new_expr: &NewExpr) {
+    if let Some(builtin_callee) = self.builtin_callee_name(&new_expr.callee) {
+      self.record_builtin_call(new_expr.span, &format!("new {}", builtin_callee));
+    }
+    swc_ecma_visit::visit_new_expr(self, new_expr);
+  }
+
+  fn visit_import_decl(&mut self, import: &ImportDecl) {
+    let module_name = builtin_module_name(&import.src.value).to_string();
+    for specifier in &import.specifiers {
+      match specifier {
+        ImportSpecifier::Default(default_spec) => {
+          self
+            .builtin_aliases
+            .insert(default_spec.local.span, module_name.clone());
+        }
+        ImportSpecifier::Namespace(namespace_spec) => {
+          self
+            .builtin_aliases
+            .insert(namespace_spec.local.span, module_name.clone());
+        }
+        ImportSpecifier::Named(named_spec) => {
+          let imported_name = match &named_spec.imported {
+            Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+            Some(ModuleExportName::Str(str_)) => str_.value.to_string(),
+            None => named_spec.local.sym.to_string(),
+          };
+          self.builtin_aliases.insert(
+            named_spec.local.span,
+            format!("{}.{}", module_name, imported_name),
+          );
+        }
+      }
+    }
+  }
+
+  fn visit_try_stmt(&mut self, try_stmt: &TryStmt) {
+    let catches = try_stmt.handler.is_some();
+    if catches {
+      self.try_block_depth += 1;
+    }
+    self.visit_block_stmt(&try_stmt.block);
+    if catches {
+      self.try_block_depth -= 1;
+    }
+    if let Some(handler) = &try_stmt.handler {
+      self.visit_catch_clause(handler);
+    }
+    if let Some(finalizer) = &try_stmt.finalizer {
+      self.visit_block_stmt(finalizer);
     }
-    
   }
 
   fn visit_var_declarator(&mut self, var_declarator: &VarDeclarator) {
+    // class expressions are named after their binding, e.g. `const Service = class { ... }`
+    if let (Some(ident), Some(Expr::Class(class_expr))) = (
+      var_declarator.name.as_ident(),
+      var_declarator.init.as_deref(),
+    ) {
+      self.visit_class_named(ident.sym.to_string(), &class_expr.class);
+      return;
+    }
+    // `const fs = require('fs')` and `const { readFileSync } = require('fs')` bind Node modules
+    // like their `import` counterparts
+    if let Some((source, bindings)) = required_bindings(var_declarator) {
+      let module_name = builtin_module_name(&source).to_string();
+      for binding in bindings {
+        let mut qualified_name = vec![module_name.clone()];
+        qualified_name.extend(binding.export_path);
+        self
+          .builtin_aliases
+          .insert(binding.local.span, qualified_name.join("."));
+      }
+      return;
+    }
     if let Some(init_expr) = &var_declarator.init {
-      match &**init_expr {
-        Expr::New(new_expr) => {
-          if let Expr::Ident(expr) = &*new_expr.callee {
-            let class_name = expr.sym.to_string();
-            if let Some(var_ident) = &var_declarator.name.as_ident() {
-              let var_name = var_ident.sym.to_string();
-              let instantiation_span = var_ident.span;
-              self.instantiations.insert(var_name.clone(), InstantiationsMap {
+      if let Expr::New(new_expr) = &**init_expr {
+        if let Expr::Ident(expr) = &*new_expr.callee {
+          let class_name = expr.sym.to_string();
+          if let Some(var_ident) = &var_declarator.name.as_ident() {
+            let var_name = var_ident.sym.to_string();
+            let instantiation_span = var_ident.span;
+            self.instantiations.insert(
+              var_name.clone(),
+              InstantiationsMap {
                 class_name,
                 variable_name: var_name,
                 instantiation_span,
-              });
-            }
+              },
+            );
           }
         }
-        _ => {}
       }
       if let Expr::Bin(bin_expr) = &**init_expr {
         self.handle_bin_expr(bin_expr)
@@ -516,7 +894,6 @@ impl Visit for CallFinder {
                 // Handle Spread syntax
                 self.visit_expr(&spread.expr);
               }
-              _ => {}
             }
           }
         }
@@ -0,0 +1,217 @@
+extern crate serde;
+extern crate swc_common;
+extern crate swc_ecma_ast;
+extern crate swc_ecma_visit;
+
+use std::collections::{HashMap, HashSet};
+
+use self::serde::{Deserialize, Serialize};
+use self::swc_common::{Span, Spanned};
+use self::swc_ecma_ast::{Class, ClassMethod, Expr, Module};
+use self::swc_ecma_visit::Visit;
+
+use crate::scope_analysis::ScopeAnalysis;
+use crate::symbol::{dotted_name, ModuleSymbols, SymbolId, SymbolKind};
+use crate::throw_finder::ThrowMap;
+
+// ----- Class hierarchy -----
+// Records which class each class of a module extends, when the parent class is declared in the
+// same module, so that `super.method()`, `super(...)` and calls to inherited methods can be
+// bound to the method that actually runs: the one declared by the class itself, or else by its
+// nearest ancestor.
+
+#[derive(Debug, Default)]
+pub struct ClassHierarchy {
+  // class -> the class it extends
+  parents: HashMap<SymbolId, SymbolId>,
+  // span of a class method (and of its function) -> span of its key, to report overrides on
+  // the method name
+  method_keys: HashMap<Span, Span>,
+  // name of a class declared at the top level -> name of the class it extends, which may be
+  // declared elsewhere, e.g. `Error`. `None` for classes that extend nothing.
+  extends: HashMap<String, Option<String>>,
+}
+
+// A method that throws while the method it overrides does not, so that code written against
+// the base class is not prepared for it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ThrowingOverride {
+  // the name of the overriding method
+  pub span: Span,
+  pub throw_map: ThrowMap,
+  pub base: SymbolId,
+}
+
+impl ClassHierarchy {
+  pub fn new(module: &Module, symbols: &ModuleSymbols, scope_analysis: &ScopeAnalysis) -> Self {
+    let mut collector = ClassCollector {
+      symbols,
+      scope_analysis,
+      hierarchy: ClassHierarchy::default(),
+    };
+    collector.visit_module(module);
+    collector.hierarchy
+  }
+
+  pub fn parent(&self, class: &SymbolId) -> Option<&SymbolId> {
+    self.parents.get(class)
+  }
+
+  // The class followed by its ancestors, nearest first
+  pub fn ancestors(&self, class: &SymbolId) -> Vec<SymbolId> {
+    let mut ancestors = vec![class.clone()];
+    let mut seen: HashSet<&SymbolId> = HashSet::from([class]);
+    let mut current = class;
+    while let Some(parent) = self.parents.get(current) {
+      // `class A extends B {}` and `class B extends A {}` do not loop forever
+      if !seen.insert(parent) {
+        break;
+      }
+      ancestors.push(parent.clone());
+      current = parent;
+    }
+    ancestors
+  }
+
+  // The names of `class_name` and of the classes it extends, nearest first. The last one is not
+  // declared at the top level of the module, or extends nothing.
+  fn class_names(&self, class_name: &str) -> Vec<String> {
+    let mut class_names = vec![class_name.to_string()];
+    while let Some(Some(parent)) = self.extends.get(class_names.last().unwrap()) {
+      if class_names.contains(parent) {
+        break;
+      }
+      class_names.push(parent.clone());
+    }
+    class_names
+  }
+
+  // Whether instances of the class named `class_name` are instances of `ancestor` too, as
+  // `instanceof` checks it. Classes that are not declared in the module, e.g. imported or
+  // builtin ones, are assumed to be error classes, so every class is an `Error` but those
+  // extending nothing.
+  pub fn is_instance_of(&self, class_name: &str, ancestor: &str) -> bool {
+    let class_names = self.class_names(class_name);
+    if class_names.iter().any(|name| name == ancestor) {
+      return true;
+    }
+    let root = class_names.last().unwrap();
+    ancestor == "Error" && self.extends.get(root) != Some(&None)
+  }
+
+  // The method `container.path` runs: for a class, the one it declares or else inherits from
+  // its nearest ancestor; for an object literal, the one declared through `path`
+  pub fn called_member(
+    &self,
+    symbols: &ModuleSymbols,
+    container: &SymbolId,
+    path: &[String],
+  ) -> Option<SymbolId> {
+    if container.kind != SymbolKind::Class {
+      return symbols.member(container, path);
+    }
+    self
+      .ancestors(container)
+      .iter()
+      .find_map(|class| symbols.member(class, path))
+  }
+
+  // The throwing methods overriding a method of an ancestor that does not throw
+  pub fn throwing_overrides(
+    &self,
+    symbols: &ModuleSymbols,
+    functions_with_throws: &HashSet<ThrowMap>,
+  ) -> Vec<ThrowingOverride> {
+    let throwing_symbols: HashSet<&SymbolId> = functions_with_throws
+      .iter()
+      .map(|throw_map| &throw_map.symbol)
+      .collect();
+    let mut throwing_overrides: Vec<ThrowingOverride> = vec![];
+    for throw_map in functions_with_throws {
+      if !matches!(
+        throw_map.symbol.kind,
+        SymbolKind::Method | SymbolKind::Getter | SymbolKind::Setter
+      ) {
+        continue;
+      }
+      let Some(class) = symbols.container_of(&throw_map.symbol) else {
+        continue;
+      };
+      let Some(parent) = self.parent(&class) else {
+        continue;
+      };
+      let path = [throw_map.symbol.name.clone()];
+      let Some(base) = self.called_member(symbols, parent, &path) else {
+        continue;
+      };
+      if throwing_symbols.contains(&base) {
+        continue;
+      }
+      let span = self
+        .method_keys
+        .get(&throw_map.throw_statement)
+        .cloned()
+        .unwrap_or(throw_map.throw_statement);
+      // the same method can be recorded more than once, e.g. a class method and its function
+      if throwing_overrides
+        .iter()
+        .any(|throwing_override| throwing_override.span == span)
+      {
+        continue;
+      }
+      throwing_overrides.push(ThrowingOverride {
+        span,
+        throw_map: throw_map.clone(),
+        base,
+      });
+    }
+    throwing_overrides.sort_by_key(|throwing_override| throwing_override.span.lo);
+    throwing_overrides
+  }
+}
+
+struct ClassCollector<'a> {
+  symbols: &'a ModuleSymbols,
+  scope_analysis: &'a ScopeAnalysis,
+  hierarchy: ClassHierarchy,
+}
+
+impl<'a> Visit for ClassCollector<'a> {
+  fn visit_class(&mut self, class: &Class) {
+    if let Some(Expr::Ident(super_class)) = class.super_class.as_deref() {
+      let parent = self
+        .scope_analysis
+        .declared_span(super_class.span)
+        .and_then(|declared_span| self.symbols.symbol_declared_at(declared_span))
+        .filter(|parent| parent.kind == SymbolKind::Class);
+      let child = self.symbols.symbol_declared_at(class.span);
+      if let (Some(child), Some(parent)) = (child, parent) {
+        self.hierarchy.parents.insert(child, parent);
+      }
+    }
+    let class_symbol = self.symbols.symbol_declared_at(class.span);
+    // a class extending a computed expression, e.g. `mixin(Base)`, is left out like the classes
+    // declared elsewhere
+    let super_class = match class.super_class.as_deref() {
+      Some(super_class) => dotted_name(super_class).map(Some),
+      None => Some(None),
+    };
+    if let (Some(class_symbol), Some(super_class)) = (class_symbol, super_class) {
+      if class_symbol.scope.is_empty() {
+        self
+          .hierarchy
+          .extends
+          .insert(class_symbol.name, super_class);
+      }
+    }
+    swc_ecma_visit::visit_class(self, class);
+  }
+
+  fn visit_class_method(&mut self, class_method: &ClassMethod) {
+    let key_span = class_method.key.span();
+    let method_keys = &mut self.hierarchy.method_keys;
+    method_keys.insert(class_method.span, key_span);
+    method_keys.insert(class_method.function.span, key_span);
+    swc_ecma_visit::visit_class_method(self, class_method);
+  }
+}
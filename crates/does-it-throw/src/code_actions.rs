@@ -0,0 +1,542 @@
+extern crate swc_common;
+extern crate swc_ecma_ast;
+extern crate swc_ecma_visit;
+
+use self::swc_common::{BytePos, Span, Spanned};
+use self::swc_ecma_ast::{
+  ArrowExpr, Class, Decl, Function, Module, Pat, Stmt, VarDecl, VarDeclKind,
+};
+use self::swc_ecma_visit::Visit;
+
+use crate::project::FileAnalysis;
+use crate::query::{file_throw_info_at, throw_info_at, ThrowInfo};
+use crate::scope_analysis::{pat_idents, ScopeAnalysis};
+use crate::throw_finder::{error_classes, ThrowMap};
+use crate::{parse_module, AnalysisResult};
+
+// ----- Code actions -----
+// Text edits for what the analysis reports at a position: wrapping a throwing call in try/catch,
+// handling a floating promise with `.catch()`, ignoring a throw statement with one of the ignore
+// statements and documenting a throw with a `@throws` tag. Inserted code is indented like the
+// code around it.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeActionKind {
+  WrapInTryCatch,
+  AddCatch,
+  AddIgnoreComment,
+  AddThrowsTag,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+  // the replaced text, an empty span for insertions
+  pub span: Span,
+  pub new_text: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeAction {
+  pub kind: CodeActionKind,
+  pub title: String,
+  pub edits: Vec<TextEdit>,
+}
+
+// The analyzed source, to find lines and indentation around positions of the analysis
+struct SourceText<'source> {
+  source: &'source str,
+  start_pos: BytePos,
+}
+
+impl<'source> SourceText<'source> {
+  fn offset(&self, pos: BytePos) -> usize {
+    let mut offset = (pos.0.saturating_sub(self.start_pos.0) as usize).min(self.source.len());
+    while !self.source.is_char_boundary(offset) {
+      offset -= 1;
+    }
+    offset
+  }
+
+  fn pos(&self, offset: usize) -> BytePos {
+    self.start_pos + BytePos(offset as u32)
+  }
+
+  fn slice(&self, lo: BytePos, hi: BytePos) -> &'source str {
+    &self.source[self.offset(lo)..self.offset(hi)]
+  }
+
+  fn line_start(&self, pos: BytePos) -> BytePos {
+    let offset = self.offset(pos);
+    self.pos(
+      self.source[..offset]
+        .rfind('\n')
+        .map_or(0, |index| index + 1),
+    )
+  }
+
+  // before the line break, `\r\n` included
+  fn line_end(&self, pos: BytePos) -> BytePos {
+    let offset = self.offset(pos);
+    let line_end = self.source[offset..]
+      .find('\n')
+      .map_or(self.source.len(), |index| offset + index);
+    let line_end = if self.source[..line_end].ends_with('\r') {
+      line_end - 1
+    } else {
+      line_end
+    };
+    self.pos(line_end.max(offset))
+  }
+
+  // the whitespace the line of `pos` starts with
+  fn indentation(&self, pos: BytePos) -> &'source str {
+    let line = self.slice(self.line_start(pos), self.line_end(pos));
+    &line[..line.len() - line.trim_start().len()]
+  }
+
+  // whether only whitespace precedes `pos` on its line
+  fn starts_line(&self, pos: BytePos) -> bool {
+    self.slice(self.line_start(pos), pos).trim().is_empty()
+  }
+
+  // A tab when the file is indented with tabs, otherwise its smallest indentation, e.g. 4 spaces
+  fn indent_unit(&self) -> &'source str {
+    let indentations = self
+      .source
+      .lines()
+      // ignoring blank lines and the ` * ...` lines of block comments
+      .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('*'))
+      .map(|line| &line[..line.len() - line.trim_start().len()])
+      .filter(|indentation| !indentation.is_empty());
+    let mut indent_unit: Option<&str> = None;
+    for indentation in indentations {
+      if indentation.starts_with('\t') {
+        return "\t";
+      }
+      if indent_unit.is_none_or(|indent_unit| indentation.len() < indent_unit.len()) {
+        indent_unit = Some(indentation);
+      }
+    }
+    indent_unit.unwrap_or("  ")
+  }
+
+  fn newline(&self) -> &'static str {
+    if self.source.contains("\r\n") {
+      "\r\n"
+    } else {
+      "\n"
+    }
+  }
+}
+
+fn encloses(span: Span, inner: Span) -> bool {
+  span.lo <= inner.lo && inner.hi <= span.hi
+}
+
+// Finds the innermost statement making the call at `call_span`. There is none when the call is
+// made by a nested function or class, e.g. `const load = () => parse(text)`, since wrapping the
+// statement would not catch what the call throws, or by an export declaration.
+struct StatementFinder {
+  call_span: Span,
+  statement: Option<Stmt>,
+}
+
+impl Visit for StatementFinder {
+  fn visit_stmt(&mut self, stmt: &Stmt) {
+    if encloses(stmt.span(), self.call_span) {
+      self.statement = Some(stmt.clone());
+      swc_ecma_visit::visit_stmt(self, stmt);
+    }
+  }
+
+  fn visit_function(&mut self, function: &Function) {
+    if encloses(function.span, self.call_span) {
+      self.statement = None;
+      swc_ecma_visit::visit_function(self, function);
+    }
+  }
+
+  fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr) {
+    if encloses(arrow_expr.span, self.call_span) {
+      self.statement = None;
+      swc_ecma_visit::visit_arrow_expr(self, arrow_expr);
+    }
+  }
+
+  fn visit_class(&mut self, class: &Class) {
+    if encloses(class.span, self.call_span) {
+      self.statement = None;
+      swc_ecma_visit::visit_class(self, class);
+    }
+  }
+}
+
+// Whether a binding of the declaration is referred to outside of `statement_span`, so that it
+// would go out of scope in a try block
+fn is_used_outside(module: &Module, var_decl: &VarDecl, statement_span: Span) -> bool {
+  let scope_analysis = ScopeAnalysis::new(module);
+  var_decl
+    .decls
+    .iter()
+    .flat_map(|declarator| pat_idents(&declarator.name))
+    .flat_map(|ident| scope_analysis.references_to(ident.span))
+    .any(|reference| !encloses(statement_span, reference))
+}
+
+// `const user: User = load(id);` becomes the `let user: User;` declaration and the
+// `user = load(id);` assignment made in the try block, with semicolons if the declaration had one
+fn hoist_bindings(text: &SourceText, var_decl: &VarDecl) -> (String, String) {
+  let mut bindings: Vec<String> = vec![];
+  let mut assignments: Vec<String> = vec![];
+  for declarator in &var_decl.decls {
+    let target = match &declarator.name {
+      Pat::Ident(binding_ident) => {
+        let hi = binding_ident
+          .type_ann
+          .as_ref()
+          .map_or(binding_ident.id.span.hi, |type_ann| type_ann.span.hi);
+        bindings.push(text.slice(binding_ident.id.span.lo, hi).to_string());
+        binding_ident.id.sym.to_string()
+      }
+      pat => {
+        bindings.extend(pat_idents(pat).iter().map(|ident| ident.sym.to_string()));
+        // the type annotation of a pattern is left out, it has no place in an assignment
+        let type_ann = match pat {
+          Pat::Object(object_pat) => object_pat.type_ann.as_ref(),
+          Pat::Array(array_pat) => array_pat.type_ann.as_ref(),
+          _ => None,
+        };
+        let hi = type_ann.map_or(pat.span().hi, |type_ann| {
+          type_ann.span.lo.min(pat.span().hi)
+        });
+        text.slice(pat.span().lo, hi).trim_end().to_string()
+      }
+    };
+    if let Some(init) = &declarator.init {
+      let assignment = format!(
+        "{} = {}",
+        target,
+        text.slice(init.span().lo, init.span().hi)
+      );
+      assignments.push(match declarator.name {
+        // `{ ... } = value` would be read as a block
+        Pat::Object(_) => format!("({})", assignment),
+        _ => assignment,
+      });
+    }
+  }
+  let semicolon = if text
+    .slice(var_decl.span.lo, var_decl.span.hi)
+    .ends_with(';')
+  {
+    ";"
+  } else {
+    ""
+  };
+  (
+    format!("let {}{}", bindings.join(", "), semicolon),
+    format!("{}{}", assignments.join(", "), semicolon),
+  )
+}
+
+// Wraps the statement making the call in a try statement. A `const` or `let` declaration whose
+// bindings are used after it is split so that they stay in scope. Calls in the head of an `if`,
+// a loop or a `switch` are not wrapped: the try block would have to hold the whole statement.
+fn wrap_in_try_catch(text: &SourceText, throw_info: &ThrowInfo) -> Option<CodeAction> {
+  // the analysis may have recovered from syntax errors, which parsing the same way finds again
+  let module = parse_module(text.source, text.start_pos, true).ok()?.module;
+  let mut statement_finder = StatementFinder {
+    call_span: throw_info.call_span,
+    statement: None,
+  };
+  statement_finder.visit_module(&module);
+  let statement = statement_finder.statement?;
+  let span = statement.span();
+  let (declaration, body) = match &statement {
+    Stmt::If(_)
+    | Stmt::Switch(_)
+    | Stmt::While(_)
+    | Stmt::DoWhile(_)
+    | Stmt::For(_)
+    | Stmt::ForIn(_)
+    | Stmt::ForOf(_)
+    | Stmt::With(_) => return None,
+    Stmt::Decl(Decl::Var(var_decl))
+      if var_decl.kind != VarDeclKind::Var && is_used_outside(&module, var_decl, span) =>
+    {
+      let (declaration, assignments) = hoist_bindings(text, var_decl);
+      (Some(declaration), assignments)
+    }
+    _ => (None, text.slice(span.lo, span.hi).to_string()),
+  };
+
+  let newline = text.newline();
+  let (lo, new_text) = if text.starts_line(span.lo) {
+    let lo = text.line_start(span.lo);
+    let indentation = text.indentation(lo);
+    let indent_unit = text.indent_unit();
+    let block = format!("{}{}", indentation, body)
+      .lines()
+      .map(|line| {
+        if line.trim().is_empty() {
+          line.to_string()
+        } else {
+          format!("{}{}", indent_unit, line)
+        }
+      })
+      .collect::<Vec<String>>()
+      .join(newline);
+    let declaration = declaration
+      .map(|declaration| format!("{indentation}{declaration}{newline}"))
+      .unwrap_or_default();
+    (
+      lo,
+      format!(
+        "{declaration}{indentation}try {{{newline}{block}{newline}{indentation}}} catch (error) {{{newline}{indentation}}}",
+      ),
+    )
+  } else {
+    // the statement shares its line with other code, e.g. `if (!input) return parse(input);`
+    let declaration = declaration
+      .map(|declaration| format!("{} ", declaration))
+      .unwrap_or_default();
+    (
+      span.lo,
+      format!("{declaration}try {{ {body} }} catch (error) {{}}"),
+    )
+  };
+  Some(CodeAction {
+    kind: CodeActionKind::WrapInTryCatch,
+    title: format!("Wrap the call to `{}` in try/catch", throw_info.callee),
+    edits: vec![TextEdit {
+      span: Span::new(lo, span.hi, Default::default()),
+      new_text,
+    }],
+  })
+}
+
+fn add_catch(span: Span) -> CodeAction {
+  CodeAction {
+    kind: CodeActionKind::AddCatch,
+    title: "Add `.catch()` to the promise".to_string(),
+    edits: vec![TextEdit {
+      span: Span::new(span.hi, span.hi, Default::default()),
+      new_text: ".catch((error) => {})".to_string(),
+    }],
+  }
+}
+
+// A comment line above the throw statement, or a block comment in front of it when it shares its
+// line with other code, e.g. `if (!id) throw ...`: comments only apply to the statement they lead.
+fn add_ignore_comment(text: &SourceText, throw_span: Span, ignore_statement: &str) -> CodeAction {
+  let (pos, new_text) = if text.starts_line(throw_span.lo) {
+    (
+      text.line_start(throw_span.lo),
+      format!(
+        "{}// {}{}",
+        text.indentation(throw_span.lo),
+        ignore_statement,
+        text.newline()
+      ),
+    )
+  } else {
+    (throw_span.lo, format!("/* {} */ ", ignore_statement))
+  };
+  CodeAction {
+    kind: CodeActionKind::AddIgnoreComment,
+    title: format!("Ignore this throw statement with `{}`", ignore_statement),
+    edits: vec![TextEdit {
+      span: Span::new(pos, pos, Default::default()),
+      new_text,
+    }],
+  }
+}
+
+// The span of the doc comment ending right before the function, e.g. on the line above it or in
+// front of an `export` keyword on its line
+fn doc_comment_span(text: &SourceText, function: &ThrowMap) -> Option<Span> {
+  let line_start = text.line_start(function.throw_statement.lo);
+  let before_function = text.slice(text.start_pos, function.throw_statement.lo);
+  let close = match text
+    .slice(line_start, function.throw_statement.lo)
+    .rfind("*/")
+  {
+    Some(index) => text.offset(line_start) + index,
+    None => {
+      let before_line = text.slice(text.start_pos, line_start).trim_end();
+      if !before_line.ends_with("*/") {
+        return None;
+      }
+      before_line.len() - 2
+    }
+  };
+  let open = before_function[..close].rfind("/**")?;
+  Some(Span::new(
+    text.pos(open),
+    text.pos(close + 2),
+    Default::default(),
+  ))
+}
+
+fn add_throws_tag(text: &SourceText, function: &ThrowMap, throw_span: Span) -> Option<CodeAction> {
+  let thrown_values: Vec<_> = function
+    .thrown_values
+    .iter()
+    .filter(|thrown_value| thrown_value.throw_span == throw_span)
+    .cloned()
+    .collect();
+  let mut thrown_classes = error_classes(&thrown_values);
+  if thrown_classes.is_empty() {
+    thrown_classes.push("Error".to_string());
+  }
+  let undocumented_classes: Vec<String> = thrown_classes
+    .into_iter()
+    .filter(|error_class| {
+      !function
+        .throws_tags
+        .iter()
+        .flatten()
+        .any(|tag| tag.documents(error_class))
+    })
+    .collect();
+  if undocumented_classes.is_empty() {
+    return None;
+  }
+
+  let newline = text.newline();
+  let tag_lines = |indentation: &str| -> String {
+    undocumented_classes
+      .iter()
+      .map(|error_class| format!("{} * @throws {{{}}}{}", indentation, error_class, newline))
+      .collect()
+  };
+  let doc_comment_span = function
+    .throws_tags
+    .as_ref()
+    .and_then(|_| doc_comment_span(text, function));
+  let edit = match doc_comment_span {
+    Some(doc_comment_span) => {
+      let indentation = text.indentation(doc_comment_span.lo);
+      let tags = tag_lines(indentation);
+      let close = doc_comment_span.hi - BytePos(2);
+      if text.starts_line(close) {
+        // a multiline comment: the tags go right above its closing line
+        let pos = text.line_start(close);
+        TextEdit {
+          span: Span::new(pos, pos, Default::default()),
+          new_text: tags,
+        }
+      } else {
+        // `/** Loads the user */` becomes multiline
+        let content = text.slice(doc_comment_span.lo, close).trim_end();
+        let content_end = doc_comment_span.lo + BytePos(content.len() as u32);
+        TextEdit {
+          span: Span::new(content_end, doc_comment_span.hi, Default::default()),
+          new_text: format!("{}{}{} */", newline, tags, indentation),
+        }
+      }
+    }
+    None => {
+      let pos = text.line_start(function.throw_statement.lo);
+      let indentation = text.indentation(pos);
+      let tags = tag_lines(indentation);
+      TextEdit {
+        span: Span::new(pos, pos, Default::default()),
+        new_text: format!("{indentation}/**{newline}{tags}{indentation} */{newline}"),
+      }
+    }
+  };
+  let tags = undocumented_classes
+    .iter()
+    .map(|error_class| format!("`@throws {{{}}}`", error_class))
+    .collect::<Vec<String>>()
+    .join(", ");
+  Some(CodeAction {
+    kind: CodeActionKind::AddThrowsTag,
+    title: format!("Document the throw with {}", tags),
+    edits: vec![edit],
+  })
+}
+
+fn code_actions(
+  result: &AnalysisResult,
+  text: &SourceText,
+  pos: BytePos,
+  throw_info: Option<ThrowInfo>,
+  ignore_statement: &str,
+) -> Vec<CodeAction> {
+  let mut code_actions = vec![];
+  if let Some(throw_info) = throw_info {
+    code_actions.extend(wrap_in_try_catch(text, &throw_info));
+  }
+  if let Some(floating_promise) = result
+    .floating_promises
+    .iter()
+    .filter(|floating_promise| floating_promise.span.lo <= pos && pos < floating_promise.span.hi)
+    .min_by_key(|floating_promise| floating_promise.span.hi.0 - floating_promise.span.lo.0)
+  {
+    code_actions.push(add_catch(floating_promise.span));
+  }
+
+  // the innermost function declaring a throw statement at `pos`
+  let throw_statement = result
+    .functions_with_throws
+    .iter()
+    .flat_map(|function| {
+      function
+        .throw_spans
+        .iter()
+        .filter(|throw_span| throw_span.lo <= pos && pos < throw_span.hi)
+        .map(move |throw_span| (function, *throw_span))
+    })
+    .min_by_key(|(function, throw_span)| {
+      (
+        throw_span.hi.0 - throw_span.lo.0,
+        function.throw_statement.hi.0 - function.throw_statement.lo.0,
+      )
+    });
+  if let Some((function, throw_span)) = throw_statement {
+    code_actions.push(add_ignore_comment(text, throw_span, ignore_statement));
+    code_actions.extend(add_throws_tag(text, function, throw_span));
+  }
+  code_actions
+}
+
+// The code actions for `pos` of the file whose `source` starts at `start_pos`. Ignore comments
+// use `ignore_statement`, e.g. `@it-throws`.
+pub fn code_actions_at(
+  result: &AnalysisResult,
+  source: &str,
+  start_pos: BytePos,
+  pos: BytePos,
+  ignore_statement: &str,
+) -> Vec<CodeAction> {
+  let text = SourceText { source, start_pos };
+  code_actions(
+    result,
+    &text,
+    pos,
+    throw_info_at(result, pos),
+    ignore_statement,
+  )
+}
+
+// Like `code_actions_at`, including calls to throwing functions imported from other files of the
+// project
+pub fn file_code_actions_at(
+  file: &FileAnalysis,
+  pos: BytePos,
+  ignore_statement: &str,
+) -> Vec<CodeAction> {
+  let text = SourceText {
+    source: &file.source,
+    start_pos: file.start_pos,
+  };
+  code_actions(
+    &file.result,
+    &text,
+    pos,
+    file_throw_info_at(file, pos),
+    ignore_statement,
+  )
+}
@@ -0,0 +1,133 @@
+extern crate swc_ecma_ast;
+
+use self::swc_ecma_ast::{
+  Callee, Expr, Ident, Lit, MemberExpr, MemberProp, ObjectPatProp, Pat, PatOrExpr, PropName,
+  VarDeclarator,
+};
+
+// ----- CommonJS -----
+// `require` calls and `module.exports` / `exports` assignments, so that CommonJS modules are
+// analyzed like their ES module counterparts: `const x = require('./x')` binds the module like
+// `import * as x from './x'`, and `exports.parse = ...` exports `parse`.
+
+// A local binding created from a `require` call
+#[derive(Clone, Debug)]
+pub struct RequiredBinding {
+  pub local: Ident,
+  // the export the binding stands for, e.g. `["parse"]` for `const { parse } = require('./x')`.
+  // Empty when the binding is the whole module.
+  pub export_path: Vec<String>,
+}
+
+// `./x` for `require('./x')`
+pub fn require_source(expr: &Expr) -> Option<String> {
+  let Expr::Call(call) = expr else {
+    return None;
+  };
+  let Callee::Expr(callee) = &call.callee else {
+    return None;
+  };
+  match (&**callee, call.args.as_slice()) {
+    (Expr::Ident(ident), [arg]) if &*ident.sym == "require" && arg.spread.is_none() => {
+      match &*arg.expr {
+        Expr::Lit(Lit::Str(str_)) => Some(str_.value.to_string()),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+// The module a declarator requires and the bindings it creates from it:
+// `const x = require('./x')` binds the whole module, while `const { a, b: c } = require('./a')`
+// and `const a = require('./a').a` bind some of its exports
+pub fn required_bindings(declarator: &VarDeclarator) -> Option<(String, Vec<RequiredBinding>)> {
+  let init = declarator.init.as_deref()?;
+  if let Some(source) = require_source(init) {
+    let bindings = match &declarator.name {
+      Pat::Ident(binding_ident) => vec![RequiredBinding {
+        local: binding_ident.id.clone(),
+        export_path: vec![],
+      }],
+      Pat::Object(object_pat) => object_pat
+        .props
+        .iter()
+        .filter_map(|prop| match prop {
+          ObjectPatProp::Assign(assign_prop) => Some(RequiredBinding {
+            local: assign_prop.key.clone(),
+            export_path: vec![assign_prop.key.sym.to_string()],
+          }),
+          ObjectPatProp::KeyValue(key_value_prop) => {
+            let local = match &*key_value_prop.value {
+              Pat::Ident(binding_ident) => binding_ident.id.clone(),
+              Pat::Assign(assign_pat) => assign_pat.left.as_ident()?.id.clone(),
+              _ => return None,
+            };
+            let export_name = match &key_value_prop.key {
+              PropName::Ident(ident) => ident.sym.to_string(),
+              PropName::Str(str_) => str_.value.to_string(),
+              _ => return None,
+            };
+            Some(RequiredBinding {
+              local,
+              export_path: vec![export_name],
+            })
+          }
+          ObjectPatProp::Rest(_) => None,
+        })
+        .collect(),
+      _ => return None,
+    };
+    return Some((source, bindings));
+  }
+  if let Expr::Member(member_expr) = init {
+    let source = require_source(&member_expr.obj)?;
+    let MemberProp::Ident(export_name) = &member_expr.prop else {
+      return None;
+    };
+    let local = declarator.name.as_ident()?.id.clone();
+    return Some((
+      source,
+      vec![RequiredBinding {
+        local,
+        export_path: vec![export_name.sym.to_string()],
+      }],
+    ));
+  }
+  None
+}
+
+// `module.exports`
+pub fn is_module_exports(expr: &Expr) -> bool {
+  match expr {
+    Expr::Member(member_expr) => {
+      matches!(&*member_expr.obj, Expr::Ident(obj) if &*obj.sym == "module")
+        && matches!(&member_expr.prop, MemberProp::Ident(prop) if &*prop.sym == "exports")
+    }
+    _ => false,
+  }
+}
+
+// `parse` for `exports.parse` and `module.exports.parse`
+pub fn exports_member(expr: &Expr) -> Option<String> {
+  let Expr::Member(MemberExpr { obj, prop, .. }) = expr else {
+    return None;
+  };
+  let MemberProp::Ident(prop) = prop else {
+    return None;
+  };
+  let exports_object =
+    matches!(&**obj, Expr::Ident(obj) if &*obj.sym == "exports") || is_module_exports(obj);
+  exports_object.then(|| prop.sym.to_string())
+}
+
+// The expression assigned to, e.g. `exports.parse` in `exports.parse = ...`
+pub fn assigned_expr(left: &PatOrExpr) -> Option<&Expr> {
+  match left {
+    PatOrExpr::Expr(expr) => Some(expr),
+    PatOrExpr::Pat(pat) => match &**pat {
+      Pat::Expr(expr) => Some(expr),
+      _ => None,
+    },
+  }
+}
@@ -0,0 +1,11 @@
+export function parse(input: string) {
+  throw new Error('parse failed')
+}
+
+export function format(input: string) {
+  return input
+}
+
+export default function load() {
+  throw new Error('load failed')
+}
@@ -0,0 +1,7 @@
+import loadAll, { parse as parseInput, format as parse } from './errors'
+
+export function run(input: string) {
+  parseInput(input)
+  parse(input)
+  loadAll()
+}
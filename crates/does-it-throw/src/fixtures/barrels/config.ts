@@ -0,0 +1,3 @@
+export function loadConfig(path: string) {
+  throw new Error(`missing config: ${path}`)
+}
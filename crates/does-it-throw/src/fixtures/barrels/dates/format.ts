@@ -0,0 +1,7 @@
+export function formatDate(date: Date) {
+  throw new TypeError('not a date')
+}
+
+export function today() {
+  return new Date()
+}
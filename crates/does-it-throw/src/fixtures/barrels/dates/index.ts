@@ -0,0 +1,2 @@
+export * from './parse'
+export { formatDate as format } from './format'
@@ -0,0 +1,7 @@
+export function parseDate(input: string) {
+  throw new Error(`invalid date: ${input}`)
+}
+
+export default function parseTime(input: string) {
+  throw new Error(`invalid time: ${input}`)
+}
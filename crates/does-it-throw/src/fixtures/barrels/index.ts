@@ -0,0 +1,5 @@
+export * from './dates'
+export * as config from './config'
+export { default as parseTime } from './dates/parse'
+// re-export cycles are followed once
+export * from './index'
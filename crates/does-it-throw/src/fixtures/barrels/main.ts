@@ -0,0 +1,11 @@
+import { parseDate, format, today, parseTime, config } from './index'
+import * as lib from './index'
+
+export function run(input: string) {
+  parseDate(input)
+  format(new Date())
+  today()
+  parseTime(input)
+  config.loadConfig(input)
+  lib.parseDate(input)
+}
@@ -0,0 +1,41 @@
+// @ts-nocheck
+import * as fs from 'node:fs'
+import { execSync as run } from 'child_process'
+import yaml from 'js-yaml'
+
+export function readConfig(path) {
+  return JSON.parse(fs.readFileSync(path, 'utf8'))
+}
+
+export function safeReadConfig(path) {
+  try {
+    return JSON.parse(fs.readFileSync(path, 'utf8'))
+  } catch (e) {
+    return {}
+  }
+}
+
+export const build = () => {
+  run('npm run build')
+  const url = new URL('https://example.com')
+  return decodeURIComponent(url.pathname)
+}
+
+class Loader {
+  id() {
+    return BigInt(globalThis.crypto.randomUUID().length)
+  }
+
+  load(text) {
+    return yaml.load(text)
+  }
+}
+
+export function parsesWithLocalJson(text) {
+  const JSON = { parse: (value) => value }
+  return JSON.parse(text)
+}
+
+export function readsWithParameter(fs) {
+  return fs.readFileSync('config.json')
+}
@@ -0,0 +1,111 @@
+// @ts-nocheck
+class NotFoundError extends Error {}
+class ValidationError extends Error {}
+class WrappedError extends Error {}
+class HttpError extends Error {}
+class NotFoundHttpError extends HttpError {}
+class Plain {}
+
+function find() {
+  throw new NotFoundError('missing')
+}
+
+export function handlesNotFound() {
+  try {
+    if (Math.random() > 0.5) {
+      throw new NotFoundError('missing')
+    }
+    throw new ValidationError('invalid')
+  } catch (e) {
+    if (e instanceof NotFoundError) return
+    throw e
+  }
+}
+
+export function rethrowsOnlyValidation() {
+  try {
+    find()
+    throw new ValidationError('invalid')
+  } catch (error) {
+    if (error instanceof ValidationError) {
+      throw error
+    }
+    console.log(error)
+  }
+}
+
+export function rethrowsOthers() {
+  try {
+    find()
+    throw new ValidationError('invalid')
+  } catch (err) {
+    if (!(err instanceof NotFoundError || err instanceof ValidationError)) {
+      console.log(err)
+    } else {
+      throw err
+    }
+  }
+}
+
+export function wraps() {
+  try {
+    find()
+  } catch (e) {
+    throw new WrappedError('failed', { cause: e })
+  }
+}
+
+export function swallows() {
+  try {
+    find()
+  } catch {
+    console.log('ignored')
+  }
+}
+
+export function nested() {
+  try {
+    try {
+      find()
+    } catch (inner) {
+      throw inner
+    }
+  } catch (outer) {
+    if (outer instanceof NotFoundError) {
+      return
+    }
+    throw outer
+  }
+}
+
+export function handlesSubclasses() {
+  try {
+    throw new NotFoundHttpError('missing')
+  } catch (e) {
+    if (e instanceof HttpError) return
+    throw e
+  }
+}
+
+export function handlesEveryError() {
+  try {
+    find()
+    throw new TypeError('invalid')
+  } catch (e) {
+    if (e instanceof Error) {
+      console.log(e)
+      return
+    }
+    throw e
+  }
+}
+
+export function rethrowsNonErrors() {
+  try {
+    find()
+    throw new Plain()
+  } catch (e) {
+    if (e instanceof Error) return
+    throw e
+  }
+}
@@ -0,0 +1,45 @@
+// @ts-nocheck
+export class Parser {
+  #validate() {
+    throw new TypeError('invalid input')
+  }
+
+  *[Symbol.iterator]() {
+    throw new Error('not iterable')
+  }
+
+  'content-type'() {
+    throw new Error('no content type')
+  }
+
+  get value() {
+    throw new RangeError('no value yet')
+  }
+
+  set value(next) {
+    throw new RangeError('value is read only')
+  }
+
+  static create() {
+    throw new Error('use the constructor')
+  }
+
+  handler = () => {
+    throw new Error('unhandled')
+  }
+
+  static #instances = function () {
+    throw new Error('no instances')
+  }
+
+  static {
+    throw new Error('cannot load')
+  }
+
+  parse() {
+    this.#validate()
+    for (const token of this[Symbol.iterator]()) {
+      console.log(token)
+    }
+  }
+}
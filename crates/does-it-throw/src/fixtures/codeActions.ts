@@ -0,0 +1,38 @@
+class ValidationError extends Error {}
+
+/**
+ * Parses a user id
+ */
+function parseId(input: string) {
+    if (!input) throw new ValidationError('missing id')
+    return Number(input)
+}
+
+function loadUser(id: number) {
+    throw new Error(`no user ${id}`)
+}
+
+async function saveUser(id: number) {
+    if (!id) {
+        throw new ValidationError('missing id')
+    }
+}
+
+export function main(input: string) {
+    const id = parseId(input)
+    saveUser(id)
+    return loadUser(id)
+}
+
+export function isValid(input: string) {
+    if (parseId(input) > 0) {
+        return true
+    }
+    return false
+}
+
+export function describe(input: string) {
+    const id: number = parseId(input);
+    const label = `user ${loadUser(id)}`;
+    return id;
+}
@@ -0,0 +1,5 @@
+module.exports = function assert(condition) {
+  if (!condition) {
+    throw new Error('assertion failed')
+  }
+}
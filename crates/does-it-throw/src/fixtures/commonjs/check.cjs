@@ -0,0 +1,5 @@
+module.exports = (value) => {
+  if (!value) {
+    throw new Error('check failed')
+  }
+}
@@ -0,0 +1,8 @@
+module.exports = {
+  load(path) {
+    throw new Error(`missing config: ${path}`)
+  },
+  validate: (config) => {
+    throw new Error('invalid config')
+  }
+}
@@ -0,0 +1,13 @@
+function parseDate(input) {
+  throw new Error(`invalid date: ${input}`)
+}
+
+exports.parseDate = parseDate
+
+exports.formatDate = function (date) {
+  throw new TypeError('not a date')
+}
+
+exports.today = function () {
+  return new Date()
+}
@@ -0,0 +1,9 @@
+exports.parseId = (input) => {
+  throw new Error(`invalid id: ${input}`)
+}
+
+module.exports.parseName = (input) => {
+  throw new TypeError(`invalid name: ${input}`)
+}
+
+exports.formatId = (id) => `#${id}`
@@ -0,0 +1,23 @@
+const fs = require('node:fs')
+const dates = require('./dates')
+const { load, validate: validateConfig } = require('./config.cjs')
+const assert = require('./assert')
+const formatDate = require('./dates').formatDate
+const { parseId, parseName, formatId } = require('./ids')
+const check = require('./check.cjs')
+
+function run(input) {
+  const config = load(input)
+  validateConfig(config)
+  assert(config)
+  dates.parseDate(input)
+  dates.today()
+  formatDate(input)
+  fs.readFileSync(input)
+  check(input)
+  parseId(input)
+  parseName(input)
+  formatId(input)
+}
+
+module.exports = { run }
@@ -0,0 +1,16 @@
+// @ts-nocheck
+import Repository from './service'
+import parseInput from './parse'
+
+const Cache = class {
+  get(key) {
+    throw new Error(`cache miss for ${key}`)
+  }
+}
+
+export function run(input) {
+  parseInput(input)
+  Repository.load(input)
+  const cache = new Cache()
+  cache.get(input)
+}
@@ -0,0 +1,4 @@
+// @ts-nocheck
+export default function (input) {
+  throw new SyntaxError(`cannot parse ${input}`)
+}
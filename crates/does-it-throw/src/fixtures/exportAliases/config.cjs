@@ -0,0 +1,5 @@
+function load(path) {
+  throw new Error(`missing config: ${path}`)
+}
+
+module.exports = { load, loadConfig: load }
@@ -0,0 +1,10 @@
+import { parse, parseInput } from './parse'
+
+const { load, loadConfig } = require('./config.cjs')
+
+export function run(input: string) {
+  parse(input)
+  parseInput(input)
+  load(input)
+  loadConfig(input)
+}
@@ -0,0 +1,5 @@
+function parse(input: string) {
+  throw new Error(`cannot parse ${input}`)
+}
+
+export { parse, parse as parseInput }
@@ -0,0 +1,37 @@
+// @ts-nocheck
+class Base {
+  constructor() {
+    throw new Error('base constructor')
+  }
+
+  load() {
+    throw new Error('base load')
+  }
+
+  save() {
+    return true
+  }
+}
+
+class Child extends Base {
+  constructor() {
+    super()
+  }
+
+  save() {
+    super.load()
+    throw new Error('child save')
+  }
+}
+
+class GrandChild extends Child {}
+
+function usesInherited() {
+  const grandChild = new GrandChild()
+  grandChild.load()
+}
+
+function usesOverride() {
+  const child = new Child()
+  child.save()
+}
@@ -0,0 +1,5 @@
+import { parse } from './parse'
+
+export function main(input: string) {
+  return parse(input)
+}
@@ -0,0 +1,6 @@
+export function parse(input: string) {
+  if (!input) {
+    throw new Error('empty input')
+  }
+  return input
+}
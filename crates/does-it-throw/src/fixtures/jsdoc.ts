@@ -0,0 +1,79 @@
+// @ts-nocheck
+/**
+ * Parses a config file.
+ * @throws {SyntaxError} when the file is not valid JSON
+ * @throws {RangeError} when the port is out of range
+ */
+export function documented(port) {
+  if (port > 65535) {
+    throw new RangeError('port out of range')
+  }
+  throw new TypeError('not a config')
+}
+
+/**
+ * @throws {TypeError | RangeError} on invalid input
+ */
+function union(value) {
+  if (typeof value !== 'number') {
+    throw new TypeError('not a number')
+  }
+  throw new RangeError('negative')
+}
+
+/**
+ * No throws documented here.
+ */
+const arrow = () => {
+  throw new Error('arrow')
+}
+
+/**
+ * @throws {ValidationError} never anymore
+ */
+export const noLongerThrows = () => {
+  return 1
+}
+
+/**
+ * TSDoc style link.
+ * @throws {@link errors.NotFound} when the user does not exist
+ */
+export default function (id) {
+  throw new errors.NotFound(id)
+}
+
+/**
+ * @throws when anything goes wrong
+ */
+function untyped() {
+  throw new Error('untyped')
+}
+
+// not a doc comment, so not checked
+function notDocumented() {
+  throw new Error('not documented')
+}
+
+class Service {
+  /**
+   * @exception {NotFoundError}
+   */
+  load() {
+    throw new NotFoundError()
+  }
+
+  /**
+   * @throws {Error}
+   */
+  save() {
+    return 1
+  }
+}
+
+/**
+ * @throws {SyntaxError} when the text is not JSON
+ */
+function parseJson(text: string) {
+  return JSON.parse(text)
+}
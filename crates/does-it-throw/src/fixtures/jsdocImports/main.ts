@@ -0,0 +1,15 @@
+import { findUser } from './users'
+
+/**
+ * @throws {NotFoundError} when there is no user
+ */
+export function loadUser(id: string) {
+  return findUser(id)
+}
+
+/**
+ * @throws {TypeError} never
+ */
+export function loadProfile(id: string) {
+  return findUser(id)
+}
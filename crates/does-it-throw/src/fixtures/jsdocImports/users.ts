@@ -0,0 +1,5 @@
+export class NotFoundError extends Error {}
+
+export function findUser(id: string) {
+  throw new NotFoundError(id)
+}
@@ -0,0 +1,9 @@
+export function fetchUser(id: string) {
+  throw new Error(`user ${id} not found`)
+}
+
+export const users = {
+  remove(id: string) {
+    throw new Error(`cannot remove ${id}`)
+  },
+}
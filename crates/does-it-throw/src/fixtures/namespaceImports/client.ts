@@ -0,0 +1,6 @@
+export default {
+  send(message: string) {
+    throw new Error(`cannot send ${message}`)
+  },
+  close() {},
+}
@@ -0,0 +1,7 @@
+const logger = {
+  log(message: string) {
+    throw new Error(message)
+  },
+}
+
+export default logger
@@ -0,0 +1,13 @@
+import * as api from './api'
+import client from './client'
+import logger from './logger'
+import retry from './retry'
+
+export function run(id: string) {
+  api.fetchUser(id)
+  api.users.remove(id)
+  client.send(id)
+  client.close()
+  logger.log(id)
+  retry(3)
+}
@@ -0,0 +1,3 @@
+export default (attempts: number) => {
+  throw new Error(`gave up after ${attempts} attempts`)
+}
@@ -0,0 +1,12 @@
+import { findUser } from './users'
+import * as users from './users'
+
+export function show(id: string) {
+  findUser(id)
+}
+
+export function showAll(ids: string[]) {
+  for (const id of ids) {
+    users.findUser(id)
+  }
+}
@@ -0,0 +1,10 @@
+export function findUser(id: string) {
+  if (!id) {
+    throw new Error('missing id')
+  }
+  return { id }
+}
+
+export function requireUser(id: string) {
+  return findUser(id)
+}
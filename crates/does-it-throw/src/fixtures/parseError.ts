@@ -0,0 +1,12 @@
+// @ts-nocheck
+export function throwsBeforeError() {
+  throw new Error('still found')
+}
+
+function halfTyped() {
+  const value = someCall(
+}
+
+export const throwsAfterError = () => {
+  throw new Error('also found')
+}
@@ -0,0 +1 @@
+export const link = () => 'components'
@@ -0,0 +1 @@
+export const primaryButton = () => 'ui'
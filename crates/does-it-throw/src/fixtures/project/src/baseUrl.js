@@ -0,0 +1,3 @@
+export function throwsBaseUrl() {
+  throw new Error('baseUrl')
+}
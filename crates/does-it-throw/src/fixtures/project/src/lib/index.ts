@@ -0,0 +1,9 @@
+// @ts-nocheck
+export const throwsFromIndex = () => {
+  throw new Error('index')
+}
+
+// not exported, so calls to the `throwsRelative` imported from `./relative` must not reach it
+function throwsRelative() {
+  throw new Error('local')
+}
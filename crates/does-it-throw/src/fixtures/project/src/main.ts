@@ -0,0 +1,23 @@
+// @ts-nocheck
+import { throwsRelative } from './relative'
+import { throwsFromIndex } from './lib'
+import { throwsAlias } from '@utils/alias'
+import { throwsBaseUrl } from 'src/baseUrl'
+import React from 'react'
+
+export function callsRelative() {
+  throwsRelative()
+}
+
+export function callsIndex() {
+  throwsFromIndex()
+}
+
+export const callsAlias = () => {
+  throwsAlias()
+}
+
+export function callsBaseUrl() {
+  throwsBaseUrl()
+  React.createElement('div')
+}
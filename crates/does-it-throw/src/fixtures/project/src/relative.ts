@@ -0,0 +1,4 @@
+// @ts-nocheck
+export function throwsRelative() {
+  throw new Error('relative')
+}
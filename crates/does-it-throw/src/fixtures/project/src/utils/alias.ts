@@ -0,0 +1,4 @@
+// @ts-nocheck
+export function throwsAlias() {
+  throw new Error('alias')
+}
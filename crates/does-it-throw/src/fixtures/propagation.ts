@@ -0,0 +1,24 @@
+// @ts-nocheck
+function c() {
+  throw new Error('deep')
+}
+
+function b() {
+  c()
+}
+
+function a() {
+  b()
+}
+
+function caught() {
+  try {
+    b()
+  } catch (e) {
+    console.log(e)
+  }
+}
+
+const callsA = () => {
+  a()
+}
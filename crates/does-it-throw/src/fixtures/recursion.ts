@@ -0,0 +1,18 @@
+// @ts-nocheck
+function countdown(n) {
+  if (n < 0) {
+    throw new RangeError('negative')
+  }
+  countdown(n - 1)
+}
+
+function isEven(n) {
+  if (n < 0) {
+    throw new RangeError('negative')
+  }
+  return n === 0 || isOdd(n - 1)
+}
+
+function isOdd(n) {
+  return n !== 0 && isEven(n - 1)
+}
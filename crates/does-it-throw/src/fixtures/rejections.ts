@@ -0,0 +1,68 @@
+// @ts-nocheck
+export async function asyncThrow() {
+  throw new Error('async')
+}
+
+export async function asyncCaught() {
+  try {
+    throw new Error('caught')
+  } catch (e) {
+    console.log(e)
+  }
+}
+
+export function promiseReject() {
+  return Promise.reject(new TypeError('rejected'))
+}
+
+export function executor() {
+  return new Promise((resolve, reject) => {
+    if (Math.random() > 0.5) {
+      reject(new RangeError('executor'))
+    }
+    resolve(1)
+  })
+}
+
+export function executorThrow() {
+  return new Promise(function (resolve) {
+    throw new Error('executor throw')
+  })
+}
+
+export async function callsAsync() {
+  asyncThrow()
+  await asyncThrow()
+  asyncThrow().catch(console.error)
+  asyncThrow().then(console.log)
+  asyncThrow().then(console.log, console.error)
+  void asyncThrow()
+  promiseReject()
+  asyncCaught()
+  const pending = executor()
+  return pending
+}
+
+class Service {
+  async load() {
+    throw new Error('load')
+  }
+
+  start() {
+    this.load()
+  }
+}
+
+export function startService() {
+  const svc = new Service()
+  svc.load()
+}
+
+export function shadowsAsync(asyncThrow) {
+  asyncThrow()
+}
+
+export function shadowsReject() {
+  const promiseReject = () => Promise.resolve(1)
+  promiseReject()
+}
@@ -0,0 +1,54 @@
+// @ts-nocheck
+import { load } from './loader'
+
+class Repo {
+  static save(record) {
+    throw new Error('repo save failed')
+  }
+}
+
+function parse(input) {
+  throw new Error('parse failed')
+}
+
+function shadowedByLocal() {
+  const parse = (input) => input
+  parse('a')
+}
+
+function shadowedByNestedDeclaration() {
+  function parse(input) {
+    return input
+  }
+  return () => parse('b')
+}
+
+function parameterShadowsImport(load) {
+  load('c')
+}
+
+function localShadowsImport() {
+  const load = () => {}
+  load('d')
+}
+
+function unrelatedParameter(obj) {
+  obj.save('e')
+}
+
+function unrelatedLocalObject() {
+  const obj = { save() {} }
+  obj.save('f')
+}
+
+function callsRepo() {
+  Repo.save('g')
+}
+
+function callsParse() {
+  parse('h')
+}
+
+function callsLoad() {
+  load('i')
+}
@@ -0,0 +1,60 @@
+// @ts-nocheck
+import { load } from './loader'
+
+function save(record) {
+  throw new Error('save failed')
+}
+
+class Repository {
+  save(record) {
+    throw new Error('repository save failed')
+  }
+
+  saveAll(records) {
+    records.forEach((record) => this.save(record))
+  }
+}
+
+function shadowedByParameter(save) {
+  save('a')
+}
+
+function shadowedByLocal() {
+  const save = () => {}
+  save('b')
+}
+
+function callsUnrelatedSave(file) {
+  file.save()
+}
+
+function callsRepository() {
+  const repository = new Repository()
+  repository.save('c')
+}
+
+function callsSave() {
+  save('d')
+}
+
+function hoistedVar() {
+  if (true) {
+    var save = () => {}
+  }
+  save('e')
+}
+
+function blockScoped() {
+  {
+    const save = () => {}
+  }
+  save('f')
+}
+
+function callsLoad() {
+  load()
+}
+
+function shadowsLoad(load) {
+  load()
+}
@@ -0,0 +1,35 @@
+// @ts-nocheck
+function parse(input) {
+  throw new Error('top-level parse')
+}
+
+function callsParse() {
+  parse('a')
+}
+
+export class Reader {
+  parse(input) {
+    return input
+  }
+
+  read(input) {
+    this.parse(input)
+  }
+}
+
+function withLocalParse() {
+  function parse(input) {
+    return input
+  }
+  parse('b')
+}
+
+export const csv = {
+  parse(input) {
+    throw new Error('csv parse')
+  },
+}
+
+function callsCsv() {
+  csv.parse('c')
+}
@@ -0,0 +1,28 @@
+// @ts-nocheck
+class ValidationError extends Error {}
+
+export function validate(input: string) {
+  if (!input) {
+    throw new ValidationError('input is required')
+  }
+  if (input.length > 10) {
+    throw new errors.NotFoundError(`not found`)
+  }
+  throw TypeError('not a number')
+}
+
+export function rethrow(error: unknown) {
+  throw error
+}
+
+export function literal() {
+  throw 'something went wrong'
+}
+
+export function factory() {
+  throw createError(404)
+}
+
+export function callsValidate() {
+  validate('')
+}
@@ -0,0 +1,57 @@
+// @ts-nocheck
+export function throwsInCatch() {
+  try {
+    work()
+  } catch (e) {
+    throw new Error('from catch')
+  }
+}
+
+export function throwsInFinally() {
+  try {
+    work()
+  } finally {
+    throw new Error('from finally')
+  }
+}
+
+export function tryWithoutCatch() {
+  try {
+    throw new Error('escapes')
+  } finally {
+    cleanup()
+  }
+}
+
+export function catchInsideTry() {
+  try {
+    try {
+      work()
+    } catch (inner) {
+      throw new Error('caught by outer')
+    }
+  } catch (outer) {
+    console.log(outer)
+  }
+}
+
+export function finallyInsideTry() {
+  try {
+    try {
+      work()
+    } finally {
+      throw new Error('caught by outer')
+    }
+  } catch {
+    console.log('ignored')
+  }
+}
+
+export function rethrows() {
+  try {
+    throw new TypeError('not a number')
+  } catch (e) {
+    console.log(e)
+    throw e
+  }
+}
@@ -3,57 +3,135 @@ extern crate swc_ecma_ast;
 extern crate swc_ecma_parser;
 extern crate swc_ecma_visit;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use swc_ecma_ast::Callee;
+use swc_common::sync::Lrc;
+use swc_ecma_ast::{Callee, Ident, ImportDecl, ImportSpecifier, ModuleExportName, VarDeclarator};
 
+use crate::commonjs::required_bindings;
+use crate::scope_analysis::{BindingKind, ScopeAnalysis};
+use crate::symbol::{member_prop_name, SymbolId};
 use crate::throw_finder::IdentifierUsage;
 
-use self::swc_ecma_ast::{CallExpr, Expr, MemberProp};
+use self::swc_ecma_ast::{CallExpr, Expr, MemberExpr, MemberProp};
 
 use self::swc_ecma_visit::Visit;
 
 pub struct ImportUsageFinder {
-  pub imported_identifiers: Vec<String>,
   pub imported_identifier_usages: HashSet<IdentifierUsage>,
   pub current_class_name: Option<String>,
   pub current_method_name: Option<String>,
   pub function_name_stack: Vec<String>,
+  // Local bindings of imports -> the module they come from and the export path they stand for,
+  // e.g. `parseDate` -> (`./dates`, `["parse"]`) for `import { parse as parseDate } from './dates'`.
+  // Namespace imports stand for the module itself, with an empty path. Calls are matched by
+  // their local binding, and looked up in other files by the export path.
+  pub import_bindings: HashMap<String, (String, Vec<String>)>,
+  pub scope_analysis: Lrc<ScopeAnalysis>,
+}
+
+impl ImportUsageFinder {
+  // Whether the identifier refers to an import, and not to a local declaration shadowing it
+  fn refers_to_import(&self, ident: &Ident) -> bool {
+    self
+      .scope_analysis
+      .binding_at(ident.span)
+      .is_some_and(|binding| binding.kind == BindingKind::Import)
+  }
+
+  // The export reached by calling `path` on the import bound to `local`, e.g. `utils.parse`
+  fn imported_symbol(&self, local: &str, path: &[String]) -> SymbolId {
+    match self.import_bindings.get(local) {
+      Some((source, export_path)) => {
+        let mut export_path = export_path.clone();
+        export_path.extend(path.iter().cloned());
+        // calling a required module itself, e.g. `const parse = require('./parse'); parse()`,
+        // calls what it assigned to `module.exports`
+        if export_path.is_empty() {
+          export_path.push("default".to_string());
+        }
+        SymbolId::import(source, &export_path)
+      }
+      None => SymbolId::default(),
+    }
+  }
+}
+
+// The import a member call is made on and the path of the member called, e.g. `api` and
+// `["users", "remove"]` for `api.users.remove()`. The path is never empty.
+fn imported_member_path(member_expr: &MemberExpr) -> Option<(&Ident, Vec<String>)> {
+  let mut path = vec![];
+  let mut member_expr = member_expr;
+  loop {
+    path.insert(0, member_prop_name(&member_expr.prop)?);
+    match &*member_expr.obj {
+      Expr::Member(obj) => member_expr = obj,
+      Expr::Ident(obj) => return Some((obj, path)),
+      _ => return None,
+    }
+  }
 }
 
 impl Visit for ImportUsageFinder {
+  fn visit_import_decl(&mut self, import: &ImportDecl) {
+    let source = import.src.value.to_string();
+    for specifier in &import.specifiers {
+      let (local, export_path) = match specifier {
+        ImportSpecifier::Default(default_spec) => {
+          (&default_spec.local, vec!["default".to_string()])
+        }
+        ImportSpecifier::Namespace(namespace_spec) => (&namespace_spec.local, vec![]),
+        ImportSpecifier::Named(named_spec) => {
+          let imported_name = match &named_spec.imported {
+            Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+            Some(ModuleExportName::Str(str_)) => str_.value.to_string(),
+            None => named_spec.local.sym.to_string(),
+          };
+          (&named_spec.local, vec![imported_name])
+        }
+      };
+      self
+        .import_bindings
+        .insert(local.sym.to_string(), (source.clone(), export_path));
+    }
+  }
+
+  fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+    if let Some((source, bindings)) = required_bindings(declarator) {
+      for binding in bindings {
+        self.import_bindings.insert(
+          binding.local.sym.to_string(),
+          (source.clone(), binding.export_path),
+        );
+      }
+    }
+    swc_ecma_visit::visit_var_declarator(self, declarator);
+  }
+
   fn visit_call_expr(&mut self, call: &CallExpr) {
     if let Callee::Expr(expr) = &call.callee {
       match &**expr {
         Expr::Member(member_expr) => {
-          if let Expr::Ident(object_ident) = &*member_expr.obj {
-            self.current_class_name = Some(object_ident.sym.to_string());
-          }
-
           if let MemberProp::Ident(method_ident) = &member_expr.prop {
             self.current_method_name = Some(method_ident.sym.to_string());
           }
 
-          if let (Some(current_class_name), Some(current_method_name)) = (
-            self.current_class_name.clone(),
-            self.current_method_name.clone(),
-          ) {
-            if self.imported_identifiers.contains(&current_class_name) {
-              let usage_context = current_method_name.clone();
-              let id = format!(
-                "{}-{}",
-                self
-                  .current_class_name
-                  .clone()
-                  .unwrap_or_else(|| "NOT_SET".to_string()),
-                usage_context
-              );
-              // Create and store the identifier usage information
+          // `api.fetchUser()` on a namespace or default import, or `api.users.remove()` on an
+          // object exported by the module: the member is looked up by its path in the module
+          if let Some((import_ident, path)) = imported_member_path(member_expr) {
+            let local = import_ident.sym.to_string();
+            if self.refers_to_import(import_ident) && self.import_bindings.contains_key(&local) {
+              // `api.users-remove` for `api.users.remove()`
+              let mut container = vec![local.clone()];
+              container.extend(path[..path.len() - 1].iter().cloned());
+              let usage_context = path[path.len() - 1].clone();
+              let id = format!("{}-{}", container.join("."), usage_context);
               let usage_map = IdentifierUsage::new(
                 call.span,
-                current_class_name.clone(),
-                usage_context.clone(),
-                id.clone(),
+                local.clone(),
+                usage_context,
+                id,
+                self.imported_symbol(&local, &path),
               );
               self.imported_identifier_usages.insert(usage_map);
             }
@@ -79,7 +157,9 @@ impl Visit for ImportUsageFinder {
 
         Expr::Ident(ident) => {
           let called_function_name = ident.sym.to_string();
-          if self.imported_identifiers.contains(&called_function_name) {
+          if self.refers_to_import(ident)
+            && self.import_bindings.contains_key(&called_function_name)
+          {
             let usage_context = self
               .function_name_stack
               .last()
@@ -98,6 +178,7 @@ impl Visit for ImportUsageFinder {
               called_function_name.clone(),
               usage_context.clone(),
               id.clone(),
+              self.imported_symbol(&called_function_name, &[]),
             );
             self.imported_identifier_usages.insert(usage_map);
           }
@@ -0,0 +1,316 @@
+extern crate serde;
+extern crate swc_common;
+
+use std::collections::HashSet;
+
+use crate::builtins::CallToThrowingBuiltin;
+use crate::call_finder::CallToThrowMap;
+use crate::project::ImportedThrow;
+use crate::throw_finder::{error_classes, ThrowMap, ThrownValue};
+
+use self::serde::{Deserialize, Serialize};
+use self::swc_common::comments::{Comment, CommentKind, Comments};
+use self::swc_common::{BytePos, Span};
+
+// Tags that document what a function throws. `@throw` and `@exception` are JSDoc synonyms of `@throws`.
+const THROWS_TAGS: [&str; 3] = ["@throws", "@throw", "@exception"];
+
+const LINK_TAGS: [&str; 3] = ["@link", "@linkcode", "@linkplain"];
+
+// A `@throws {ErrorType} description` tag of a JSDoc or TSDoc comment
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ThrowsTag {
+  // from the tag name to the end of its line
+  pub span: Span,
+  // e.g. `["TypeError", "RangeError"]` for `{TypeError | RangeError}` (or TSDoc's `{@link TypeError}`),
+  // empty when the tag has no type
+  pub error_classes: Vec<String>,
+  pub description: Option<String>,
+}
+
+// `errors.NotFound` and `NotFound` name the same class as far as we can tell without type information
+fn same_error_class(documented: &str, thrown: &str) -> bool {
+  documented == thrown || documented.rsplit('.').next() == thrown.rsplit('.').next()
+}
+
+impl ThrowsTag {
+  // An untyped tag, or one for `Error`, documents anything the function throws
+  pub fn documents(&self, error_class: &str) -> bool {
+    self.error_classes.is_empty()
+      || self
+        .error_classes
+        .iter()
+        .any(|documented| documented == "Error" || same_error_class(documented, error_class))
+  }
+
+  // Whether the tag describes something of `thrown_classes`, where `None` is a value of unknown
+  // class, which could be anything a tag documents
+  fn describes_any(&self, thrown_classes: &[Option<String>]) -> bool {
+    thrown_classes
+      .iter()
+      .any(|thrown_class| match thrown_class {
+        Some(thrown_class) => self.documents(thrown_class),
+        None => true,
+      })
+  }
+}
+
+// The classes of `thrown_values`, `None` for the values of unknown class
+fn thrown_classes(thrown_values: &[ThrownValue]) -> Vec<Option<String>> {
+  thrown_values
+    .iter()
+    .flat_map(|thrown_value| {
+      let value_classes = error_classes(std::slice::from_ref(thrown_value));
+      if value_classes.is_empty() {
+        vec![None]
+      } else {
+        value_classes.into_iter().map(Some).collect()
+      }
+    })
+    .collect()
+}
+
+// `/** ... */` block comments, but not `/* ... */` or `/**/`
+fn is_doc_comment(comment: &Comment) -> bool {
+  comment.kind == CommentKind::Block && comment.text.starts_with('*') && comment.text.len() > 1
+}
+
+// The type of a tag, e.g. `TypeError | RangeError`, and the rest of the line after it
+fn split_tag_type(rest: &str) -> (Option<&str>, &str) {
+  if !rest.starts_with('{') {
+    return (None, rest);
+  }
+  let mut depth = 0;
+  for (index, character) in rest.char_indices() {
+    match character {
+      '{' => depth += 1,
+      '}' => {
+        depth -= 1;
+        if depth == 0 {
+          return (Some(&rest[1..index]), &rest[index + 1..]);
+        }
+      }
+      _ => {}
+    }
+  }
+  // unterminated type, e.g. `@throws {TypeError`
+  (Some(&rest[1..]), "")
+}
+
+fn parse_error_classes(tag_type: &str) -> Vec<String> {
+  let tag_type = tag_type.trim();
+  if let Some(link_tag) = LINK_TAGS
+    .iter()
+    .rev()
+    .find(|link_tag| tag_type.starts_with(*link_tag))
+  {
+    // `{@link NotFoundError}` or `{@link NotFoundError | not found}`
+    return tag_type[link_tag.len()..]
+      .split(|character: char| character.is_whitespace() || character == '|')
+      .find(|name| !name.is_empty())
+      .map(|name| vec![name.to_string()])
+      .unwrap_or_default();
+  }
+  tag_type
+    .split('|')
+    .map(|error_class| {
+      error_class
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim()
+    })
+    .filter(|error_class| !error_class.is_empty() && *error_class != "*")
+    .map(|error_class| error_class.to_string())
+    .collect()
+}
+
+// The `@throws` tags of a doc comment, in order
+pub fn parse_throws_tags(comment: &Comment) -> Vec<ThrowsTag> {
+  let mut throws_tags = vec![];
+  // `comment.text` starts after the opening `/*`
+  let text_start = comment.span.lo + BytePos(2);
+  let mut line_offset = 0;
+  for line in comment.text.split('\n') {
+    let content = line.trim_start().trim_start_matches('*').trim_start();
+    let content_offset = line_offset + line.len() - content.len();
+    line_offset += line.len() + 1;
+
+    let tag_name = THROWS_TAGS.iter().find(|tag_name| {
+      content.starts_with(*tag_name)
+        && content[tag_name.len()..]
+          .chars()
+          .next()
+          .is_none_or(char::is_whitespace)
+    });
+    let tag_name = match tag_name {
+      Some(tag_name) => tag_name,
+      None => continue,
+    };
+
+    let tag = content.trim_end();
+    let (tag_type, description) = split_tag_type(tag[tag_name.len()..].trim_start());
+    let description = description.trim().trim_start_matches('-').trim();
+    let lo = text_start + BytePos(content_offset as u32);
+    throws_tags.push(ThrowsTag {
+      span: Span::new(lo, lo + BytePos(tag.len() as u32), Default::default()),
+      error_classes: tag_type.map(parse_error_classes).unwrap_or_default(),
+      description: if description.is_empty() {
+        None
+      } else {
+        Some(description.to_string())
+      },
+    });
+  }
+  throws_tags
+}
+
+// The `@throws` tags of the doc comments leading any of `positions`, `None` when there is no doc comment.
+// A declaration can have its doc comment in front of an `export` or `const` keyword rather than the
+// function itself, so callers pass each position a doc comment may be attached to.
+pub fn doc_comment_throws_tags(
+  comments: &dyn Comments,
+  positions: &[BytePos],
+) -> Option<Vec<ThrowsTag>> {
+  let doc_comments: Vec<Comment> = positions
+    .iter()
+    .filter_map(|position| comments.get_leading(*position))
+    .flatten()
+    .filter(is_doc_comment)
+    .collect();
+  if doc_comments.is_empty() {
+    return None;
+  }
+  Some(doc_comments.iter().flat_map(parse_throws_tags).collect())
+}
+
+// A throw statement of a documented function that none of its `@throws` tags describe
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UndocumentedThrow {
+  pub throw_span: Span,
+  pub error_classes: Vec<String>,
+  pub function_or_method_name: String,
+  pub class_name: Option<String>,
+  pub id: String,
+}
+
+// A `@throws` tag that describes nothing the function throws anymore
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StaleThrowsTag {
+  pub throws_tag: ThrowsTag,
+  // the documented function
+  pub function_span: Span,
+  pub function_or_method_name: String,
+  pub class_name: Option<String>,
+  pub id: String,
+}
+
+// Compares the `@throws` tags of documented functions with what they are found to throw.
+// Only functions with a doc comment are checked, so undocumented code is not flagged wholesale.
+// Calls made inside a function, to throwing functions of the module or to throwing builtins,
+// count towards what it throws, whether or not they propagate.
+pub fn check_throws_tags(
+  documented_functions: &HashSet<ThrowMap>,
+  calls_to_throws: &HashSet<CallToThrowMap>,
+  calls_to_throwing_builtins: &[CallToThrowingBuiltin],
+) -> (Vec<UndocumentedThrow>, Vec<StaleThrowsTag>) {
+  let mut undocumented_throws = vec![];
+  let mut stale_throws_tags = vec![];
+  for function in documented_functions {
+    let throws_tags = match &function.throws_tags {
+      Some(throws_tags) => throws_tags,
+      None => continue,
+    };
+
+    for throw_span in &function.throw_spans {
+      let thrown_values: Vec<_> = function
+        .thrown_values
+        .iter()
+        .filter(|thrown_value| thrown_value.throw_span == *throw_span)
+        .cloned()
+        .collect();
+      let thrown_classes = error_classes(&thrown_values);
+      let is_documented = if thrown_classes.is_empty() {
+        !throws_tags.is_empty()
+      } else {
+        thrown_classes
+          .iter()
+          .all(|error_class| throws_tags.iter().any(|tag| tag.documents(error_class)))
+      };
+      if !is_documented {
+        undocumented_throws.push(UndocumentedThrow {
+          throw_span: *throw_span,
+          error_classes: thrown_classes,
+          function_or_method_name: function.function_or_method_name.clone(),
+          class_name: function.class_name.clone(),
+          id: function.id.clone(),
+        });
+      }
+    }
+
+    let mut thrown_values = function.thrown_values.clone();
+    for call in calls_to_throws {
+      if function.throw_statement.contains(call.call_span) {
+        thrown_values.extend(call.throw_map.thrown_values.iter().cloned());
+      }
+    }
+    let mut thrown_classes = thrown_classes(&thrown_values);
+    // throw statements whose values are not known still throw something
+    if !function.throw_spans.is_empty() && thrown_values.is_empty() {
+      thrown_classes.push(None);
+    }
+    for builtin_call in calls_to_throwing_builtins {
+      if function.throw_statement.contains(builtin_call.call_span) {
+        if builtin_call.builtin.error_classes.is_empty() {
+          thrown_classes.push(None);
+        }
+        thrown_classes.extend(builtin_call.builtin.error_classes.iter().cloned().map(Some));
+      }
+    }
+    for throws_tag in throws_tags {
+      if !throws_tag.describes_any(&thrown_classes) {
+        stale_throws_tags.push(StaleThrowsTag {
+          throws_tag: throws_tag.clone(),
+          function_span: function.throw_statement,
+          function_or_method_name: function.function_or_method_name.clone(),
+          class_name: function.class_name.clone(),
+          id: function.id.clone(),
+        });
+      }
+    }
+  }
+  // the same function can be recorded more than once, e.g. a class method and its function
+  undocumented_throws.sort_by_key(|throw| (throw.throw_span.lo, throw.throw_span.hi));
+  undocumented_throws.dedup_by_key(|throw| throw.throw_span);
+  stale_throws_tags.sort_by_key(|tag| (tag.throws_tag.span.lo, tag.throws_tag.span.hi));
+  stale_throws_tags.dedup_by_key(|tag| tag.throws_tag.span);
+  (undocumented_throws, stale_throws_tags)
+}
+
+// Drops the stale tags that document what the imported functions called by their function throw,
+// which the analysis of a single file does not know
+pub fn check_imported_throws(
+  stale_throws_tags: &mut Vec<StaleThrowsTag>,
+  imported_throws: &[ImportedThrow],
+) {
+  stale_throws_tags.retain(|stale_tag| {
+    let thrown_classes: Vec<Option<String>> = imported_throws
+      .iter()
+      .filter(|imported_throw| {
+        stale_tag
+          .function_span
+          .contains(imported_throw.usage.usage_span)
+      })
+      .flat_map(|imported_throw| {
+        let thrown_classes = thrown_classes(&imported_throw.throw_map.thrown_values);
+        // the function is known to throw, even when what it throws is not
+        if thrown_classes.is_empty() {
+          vec![None]
+        } else {
+          thrown_classes
+        }
+      })
+      .collect();
+    !stale_tag.throws_tag.describes_any(&thrown_classes)
+  });
+}
@@ -1,123 +1,523 @@
+pub mod builtins;
+pub mod cache;
 pub mod call_finder;
+pub mod class_hierarchy;
+pub mod code_actions;
+pub mod commonjs;
 pub mod import_usage_finder;
+pub mod jsdoc;
+pub mod project;
+pub mod query;
+pub mod rejection_finder;
+pub mod sarif;
+pub mod scope_analysis;
+pub mod symbol;
 pub mod throw_finder;
+use builtins::{
+  catalog_with, CallToThrowingBuiltin, ThrowingBuiltin, FS_ACCESS_CATEGORY, JSON_PARSE_CATEGORY,
+};
 use call_finder::{CallFinder, CallToThrowMap};
+use class_hierarchy::{ClassHierarchy, ThrowingOverride};
 use import_usage_finder::ImportUsageFinder;
+use jsdoc::{check_throws_tags, StaleThrowsTag, UndocumentedThrow};
+use rejection_finder::{FloatingPromise, FloatingPromiseFinder, Rejection, RejectionFinder};
+use scope_analysis::ScopeAnalysis;
 use swc_common::comments::SingleThreadedComments;
-use throw_finder::{IdentifierUsage, ThrowAnalyzer, ThrowMap, ThrowFinderSettings};
+use symbol::{ModuleSymbols, ReExport, SymbolId};
+use throw_finder::{
+  IdentifierUsage, ThrowAnalyzer, ThrowFinderSettings, ThrowMap, ThrowingCall, ThrownValue,
+};
+extern crate serde;
 extern crate swc_common;
 extern crate swc_ecma_ast;
 extern crate swc_ecma_parser;
 extern crate swc_ecma_visit;
 
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
 
 use std::vec;
 
-use self::swc_common::{sync::Lrc, SourceMap};
-use self::swc_ecma_ast::EsVersion;
+use self::serde::{Deserialize, Serialize};
+use self::swc_common::{sync::Lrc, BytePos, FileName, SourceFile, SourceMap, Span, Spanned};
+use self::swc_ecma_ast::{EsVersion, Module};
 use self::swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
 use self::swc_ecma_visit::Visit;
 
-#[derive(Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct AnalysisResult {
   pub functions_with_throws: HashSet<ThrowMap>,
   pub calls_to_throws: HashSet<CallToThrowMap>,
+  // Uncaught calls to the standard library and Node APIs known to throw, see `builtins`
+  pub calls_to_throwing_builtins: Vec<CallToThrowingBuiltin>,
+  // Ids of the functions making the `JSON.parse` and `fs` calls of `calls_to_throwing_builtins`
   pub json_parse_calls: Vec<String>,
   pub fs_access_calls: Vec<String>,
   pub import_sources: HashSet<String>,
   pub imported_identifiers: Vec<String>,
   pub imported_identifier_usages: HashSet<IdentifierUsage>,
+  pub parse_errors: Vec<ParseError>,
+  pub rejections: Vec<Rejection>,
+  pub floating_promises: Vec<FloatingPromise>,
+  // Throws of functions with a doc comment that no `@throws` tag describes
+  pub undocumented_throws: Vec<UndocumentedThrow>,
+  // `@throws` tags that describe nothing their function throws
+  pub stale_throws_tags: Vec<StaleThrowsTag>,
+  // Throwing methods that override a method of a base class that does not throw
+  pub throwing_overrides: Vec<ThrowingOverride>,
+  // Exports of other modules re-exported by this one, followed when linking imports across files
+  pub re_exports: Vec<ReExport>,
 }
 
-struct CombinedAnalyzers<'throwfinder_settings>  {
+struct CombinedAnalyzers<'throwfinder_settings> {
   throw_analyzer: ThrowAnalyzer<'throwfinder_settings>,
   call_finder: CallFinder,
   import_usage_finder: ImportUsageFinder,
+  rejection_finder: RejectionFinder<'throwfinder_settings>,
+  floating_promise_finder: FloatingPromiseFinder,
 }
 
-impl <'throwfinder_settings> From<CombinedAnalyzers<'throwfinder_settings>> for AnalysisResult {
+impl<'throwfinder_settings> From<CombinedAnalyzers<'throwfinder_settings>> for AnalysisResult {
   fn from(analyzers: CombinedAnalyzers) -> Self {
     Self {
       functions_with_throws: analyzers.throw_analyzer.functions_with_throws,
       calls_to_throws: analyzers.call_finder.calls,
-      json_parse_calls: analyzers.throw_analyzer.json_parse_calls,
-      fs_access_calls: analyzers.throw_analyzer.fs_access_calls,
+      json_parse_calls: builtin_call_ids(&analyzers.call_finder.builtin_calls, JSON_PARSE_CATEGORY),
+      fs_access_calls: builtin_call_ids(&analyzers.call_finder.builtin_calls, FS_ACCESS_CATEGORY),
+      calls_to_throwing_builtins: analyzers.call_finder.builtin_calls,
       import_sources: analyzers.throw_analyzer.import_sources,
       imported_identifiers: analyzers.throw_analyzer.imported_identifiers,
       imported_identifier_usages: analyzers.import_usage_finder.imported_identifier_usages,
+      parse_errors: vec![],
+      rejections: analyzers.rejection_finder.rejections,
+      floating_promises: analyzers.floating_promise_finder.floating_promises,
+      undocumented_throws: vec![],
+      stale_throws_tags: vec![],
+      throwing_overrides: vec![],
+      re_exports: vec![],
     }
   }
 }
 
+fn builtin_call_ids(builtin_calls: &[CallToThrowingBuiltin], category: &str) -> Vec<String> {
+  builtin_calls
+    .iter()
+    .filter(|builtin_call| builtin_call.builtin.category.as_deref() == Some(category))
+    .map(|builtin_call| builtin_call.id.clone())
+    .collect()
+}
+
 pub struct UserSettings {
   pub include_try_statement_throws: bool,
   pub ignore_statements: Vec<String>,
+  pub recover_from_parse_errors: bool,
+  // How many levels of callers are marked as throwing when they make an uncaught call
+  // to a throwing function (`a -> b -> c(throws)` needs 2). 0 disables propagation.
+  pub max_propagation_depth: usize,
+  // Added to the built-in catalog of APIs known to throw, replacing entries with the same callee
+  pub throwing_builtins: Vec<ThrowingBuiltin>,
 }
 
-pub fn analyze_code(
-  content: &str,
-  cm: Lrc<SourceMap>,
-  user_settings: &UserSettings,
-) -> (AnalysisResult, Lrc<SourceMap>) {
-  let fm = cm.new_source_file(swc_common::FileName::Anon, content.into());
-  let comments = Lrc::new(SingleThreadedComments::default());
-  let lexer = Lexer::new(
-    Syntax::Typescript(swc_ecma_parser::TsConfig {
-      tsx: true,
-      decorators: true,
-      dts: false,
-      no_early_errors: false,
-      disallow_ambiguous_jsx_like: false,
-    }),
-    EsVersion::latest(),
-    StringInput::from(&*fm),
-    Some(&comments),
-  );
+impl Default for UserSettings {
+  fn default() -> Self {
+    Self {
+      include_try_statement_throws: false,
+      ignore_statements: vec![
+        "@it-throws".to_string(),
+        "@does-it-throw-ignore".to_string(),
+      ],
+      recover_from_parse_errors: false,
+      max_propagation_depth: 0,
+      throwing_builtins: vec![],
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseError {
+  pub span: Span,
+  pub message: String,
+}
+
+impl From<swc_ecma_parser::error::Error> for ParseError {
+  fn from(error: swc_ecma_parser::error::Error) -> Self {
+    Self {
+      span: error.span(),
+      message: error.kind().msg().to_string(),
+    }
+  }
+}
+
+// Returned when the module could not be parsed at all (or could not be recovered
+// when `recover_from_parse_errors` is set). Carries every error SWC reported, in order.
+#[derive(Debug)]
+pub struct AnalysisError {
+  pub errors: Vec<ParseError>,
+}
+
+impl fmt::Display for AnalysisError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.errors.first() {
+      Some(error) => write!(f, "failed to parse module: {}", error.message),
+      None => write!(f, "failed to parse module"),
+    }
+  }
+}
+
+impl std::error::Error for AnalysisError {}
+
+struct ParsedModule {
+  module: Module,
+  comments: Lrc<SingleThreadedComments>,
+  errors: Vec<ParseError>,
+}
+
+// Upper bound on how many lines we blank out before giving up on a module.
+const MAX_RECOVERY_ATTEMPTS: usize = 16;
+
+// Replaces the line containing `offset` with spaces so that the byte length (and
+// therefore every span) of the source stays the same. If that line is blank (e.g. an
+// unexpected EOF) or only closes an earlier construct (`}`, `)`, `]`), the offending
+// code is most likely on the closest non-blank line above it, so that one is used.
+// Returns the byte range of the code that was blanked, without surrounding whitespace.
+fn blank_line_at(source: &mut String, offset: usize) -> Option<(usize, usize)> {
+  let bytes = source.as_bytes();
+  let mut offset = offset.min(bytes.len());
+  loop {
+    let line_start = bytes[..offset]
+      .iter()
+      .rposition(|b| *b == b'\n')
+      .map_or(0, |pos| pos + 1);
+    let line_end = bytes[offset..]
+      .iter()
+      .position(|b| *b == b'\n')
+      .map_or(bytes.len(), |pos| offset + pos);
+    let line = &bytes[line_start..line_end];
+    let first_char = line.iter().position(|b| !b.is_ascii_whitespace());
+    let closes_construct = matches!(
+      first_char.map(|index| line[index]),
+      Some(b'}') | Some(b')') | Some(b']')
+    );
+    if let Some(first_char) = first_char.filter(|_| !closes_construct || line_start == 0) {
+      let last_char = line.iter().rposition(|b| !b.is_ascii_whitespace())?;
+      let blanked = line
+        .iter()
+        .map(|b| if *b == b'\r' { b'\r' } else { b' ' })
+        .collect::<Vec<u8>>();
+      let mut new_bytes = bytes.to_vec();
+      new_bytes[line_start..line_end].copy_from_slice(&blanked);
+      // whole lines are replaced, so char boundaries are preserved
+      *source = String::from_utf8(new_bytes).unwrap_or_default();
+      return Some((line_start + first_char, line_start + last_char + 1));
+    }
+    if line_start == 0 {
+      return None;
+    }
+    offset = line_start - 1;
+  }
+}
+
+// Parses the `source` of a file starting at `start_pos`, blanking out the lines of syntax errors
+// when `recover_from_parse_errors` is set
+fn parse_module(
+  source: &str,
+  start_pos: BytePos,
+  recover_from_parse_errors: bool,
+) -> Result<ParsedModule, AnalysisError> {
+  let end_pos = start_pos + BytePos(source.len() as u32);
+  let mut source = source.to_string();
+  let mut recovered_errors: Vec<ParseError> = vec![];
+  let mut attempt = 0;
+
+  loop {
+    let comments = Lrc::new(SingleThreadedComments::default());
+    let lexer = Lexer::new(
+      Syntax::Typescript(swc_ecma_parser::TsConfig {
+        tsx: true,
+        decorators: true,
+        dts: false,
+        no_early_errors: false,
+        disallow_ambiguous_jsx_like: false,
+      }),
+      EsVersion::latest(),
+      StringInput::new(&source, start_pos, end_pos),
+      Some(&comments),
+    );
+
+    let mut parser = Parser::new_from(lexer);
+    let parsed = parser.parse_module();
+    let mut errors: Vec<ParseError> = parser
+      .take_errors()
+      .into_iter()
+      .map(ParseError::from)
+      .collect();
+    let fatal_error = match parsed {
+      Ok(module) => {
+        recovered_errors.append(&mut errors);
+        return Ok(ParsedModule {
+          module,
+          comments,
+          errors: recovered_errors,
+        });
+      }
+      Err(fatal_error) => ParseError::from(fatal_error),
+    };
 
-  let mut parser = Parser::new_from(lexer);
-  let module = parser.parse_module().expect("Failed to parse module");
+    let blanked = if recover_from_parse_errors && attempt < MAX_RECOVERY_ATTEMPTS {
+      blank_line_at(&mut source, (fatal_error.span.lo - start_pos).0 as usize)
+    } else {
+      None
+    };
+    let Some((lo, hi)) = blanked else {
+      recovered_errors.append(&mut errors);
+      recovered_errors.push(fatal_error);
+      return Err(AnalysisError {
+        errors: recovered_errors,
+      });
+    };
+    // the blanked code is not analyzed, which callers need to know: it may have held valid
+    // statements sharing the line with the error
+    let skipped_code = ParseError {
+      span: Span::new(
+        start_pos + BytePos(lo as u32),
+        start_pos + BytePos(hi as u32),
+        Default::default(),
+      ),
+      message: format!("Skipped to recover from: {}", fatal_error.message),
+    };
+    recovered_errors.push(fatal_error);
+    recovered_errors.push(skipped_code);
+    attempt += 1;
+  }
+}
+
+fn collect_throws<'throwfinder_settings>(
+  module: &Module,
+  comments: Lrc<SingleThreadedComments>,
+  symbols: Lrc<ModuleSymbols>,
+  class_hierarchy: &'throwfinder_settings ClassHierarchy,
+  user_settings: &'throwfinder_settings UserSettings,
+  throwing_calls: &'throwfinder_settings HashMap<Span, ThrowingCall>,
+  propagate_throwing_calls: bool,
+) -> ThrowAnalyzer<'throwfinder_settings> {
   let mut throw_collector = ThrowAnalyzer {
-    comments: comments.clone(),
+    comments,
     functions_with_throws: HashSet::new(),
-    json_parse_calls: vec![],
-    fs_access_calls: vec![],
+    documented_functions: HashSet::new(),
+    doc_comment_positions: HashMap::new(),
     import_sources: HashSet::new(),
     imported_identifiers: Vec::new(),
     function_name_stack: vec![],
     current_class_name: None,
     current_method_name: None,
     throwfinder_settings: ThrowFinderSettings {
-      ignore_statements: &user_settings.ignore_statements.clone(),
-      include_try_statements: &user_settings.include_try_statement_throws.clone(),
-    }
+      ignore_statements: &user_settings.ignore_statements,
+      include_try_statements: &user_settings.include_try_statement_throws,
+      throwing_calls,
+      propagate_throwing_calls,
+      class_hierarchy,
+    },
+    symbols,
   };
-  throw_collector.visit_module(&module);
+  throw_collector.visit_module(module);
+  throw_collector
+}
+
+fn collect_calls(
+  module: &Module,
+  functions_with_throws: HashSet<ThrowMap>,
+  builtins: &HashMap<String, ThrowingBuiltin>,
+  symbols: Lrc<ModuleSymbols>,
+  scope_analysis: Lrc<ScopeAnalysis>,
+  class_hierarchy: Lrc<ClassHierarchy>,
+) -> CallFinder {
   let mut call_collector = CallFinder {
     processed_calls: HashSet::new(),
-    functions_with_throws: throw_collector.functions_with_throws.clone(),
+    functions_with_throws,
     calls: HashSet::new(),
     current_class_name: None,
     instantiations: HashMap::new(),
     function_name_stack: vec![],
     object_property_stack: vec![],
+    builtins: builtins.clone(),
+    builtin_aliases: HashMap::new(),
+    builtin_calls: vec![],
+    try_block_depth: 0,
+    symbols,
+    scope_analysis,
+    class_hierarchy,
+  };
+  call_collector.visit_module(module);
+  call_collector
+}
+
+pub fn analyze_code(
+  content: &str,
+  cm: Lrc<SourceMap>,
+  user_settings: &UserSettings,
+) -> Result<(AnalysisResult, Lrc<SourceMap>), AnalysisError> {
+  let fm = cm.new_source_file(FileName::Anon, content.into());
+  analyze_source_file(&fm, cm, user_settings)
+}
+
+// Like `analyze_code`, for the content of the file at `path`, which becomes part of
+// the `SymbolId` of every function found, so that they can be told apart across files.
+pub fn analyze_code_at(
+  path: &Path,
+  content: &str,
+  cm: Lrc<SourceMap>,
+  user_settings: &UserSettings,
+) -> Result<(AnalysisResult, Lrc<SourceMap>), AnalysisError> {
+  let fm = cm.new_source_file(FileName::Real(path.to_path_buf()), content.into());
+  analyze_source_file(&fm, cm, user_settings)
+}
+
+fn analyze_source_file(
+  fm: &SourceFile,
+  cm: Lrc<SourceMap>,
+  user_settings: &UserSettings,
+) -> Result<(AnalysisResult, Lrc<SourceMap>), AnalysisError> {
+  let ParsedModule {
+    module,
+    comments,
+    errors: parse_errors,
+  } = parse_module(
+    &fm.src,
+    fm.start_pos,
+    user_settings.recover_from_parse_errors,
+  )?;
+  let file = match &fm.name {
+    FileName::Real(path) => Some(path.clone()),
+    _ => None,
   };
-  call_collector.visit_module(&module);
+  let symbols = Lrc::new(ModuleSymbols::new(&module, file));
+  let scope_analysis = Lrc::new(ScopeAnalysis::new(&module));
+  let class_hierarchy = Lrc::new(ClassHierarchy::new(&module, &symbols, &scope_analysis));
+  // Fixed-point pass over the module's call graph: every uncaught call to a throwing function
+  // marks its caller as throwing too, which may in turn reveal new calls to throwing functions.
+  // Each round re-runs both analyzers with the calls found so far, until the throwing functions
+  // and what they throw stop changing or `max_propagation_depth` rounds have run. Propagation
+  // paths are left out of the comparison, they only describe how the errors get there.
+  // At least one extra round always runs, without propagation when it is disabled, so that
+  // catch clauses know what the calls in their try block may throw.
+  let builtins = catalog_with(&user_settings.throwing_builtins);
+  let mut throwing_calls: HashMap<Span, ThrowingCall> = HashMap::new();
+  let mut throwing_functions: HashMap<SymbolId, Vec<ThrownValue>> = HashMap::new();
+  let mut depth = 0;
+  let (throw_collector, mut call_collector) = loop {
+    let throw_collector = collect_throws(
+      &module,
+      comments.clone(),
+      symbols.clone(),
+      &class_hierarchy,
+      user_settings,
+      &throwing_calls,
+      depth <= user_settings.max_propagation_depth,
+    );
+    let call_collector = collect_calls(
+      &module,
+      throw_collector.functions_with_throws.clone(),
+      &builtins,
+      symbols.clone(),
+      scope_analysis.clone(),
+      class_hierarchy.clone(),
+    );
+
+    let next_throwing_functions: HashMap<SymbolId, Vec<ThrownValue>> = throw_collector
+      .functions_with_throws
+      .iter()
+      .map(|throw_map| (throw_map.symbol.clone(), throw_map.thrown_values.clone()))
+      .collect();
+    let next_throwing_calls: HashMap<Span, ThrowingCall> = call_collector
+      .calls
+      .iter()
+      .map(|call| {
+        (
+          call.call_span,
+          ThrowingCall {
+            propagation_path: call.propagation_path.clone(),
+            thrown_values: call.throw_map.thrown_values.clone(),
+          },
+        )
+      })
+      .collect();
+    let reached_max_depth = depth >= user_settings.max_propagation_depth.max(1);
+    if reached_max_depth || next_throwing_functions == throwing_functions {
+      break (throw_collector, call_collector);
+    }
+    throwing_functions = next_throwing_functions;
+    throwing_calls = next_throwing_calls;
+    depth += 1;
+  };
+
+  if !user_settings.include_try_statement_throws {
+    call_collector
+      .builtin_calls
+      .retain(|builtin_call| !builtin_call.in_try_block);
+  }
 
   let mut import_usages_collector = ImportUsageFinder {
-    imported_identifiers: throw_collector.imported_identifiers.clone(),
     imported_identifier_usages: HashSet::new(),
     current_class_name: None,
     current_method_name: None,
     function_name_stack: vec![],
+    import_bindings: HashMap::new(),
+    scope_analysis: scope_analysis.clone(),
   };
   import_usages_collector.visit_module(&module);
 
+  let mut rejections_collector = RejectionFinder::new(
+    comments.clone(),
+    &user_settings.ignore_statements,
+    symbols.clone(),
+  );
+  rejections_collector.visit_module(&module);
+
+  // async functions that throw through their calls reject too
+  let mut rejecting_functions = rejections_collector.rejecting_functions();
+  rejecting_functions.extend(
+    throw_collector
+      .functions_with_throws
+      .iter()
+      .filter(|throw_map| {
+        rejections_collector
+          .async_functions
+          .contains(&throw_map.symbol)
+      })
+      .map(|throw_map| (throw_map.symbol.clone(), throw_map.id.clone())),
+  );
+  let mut floating_promises_collector = FloatingPromiseFinder::new(
+    rejecting_functions,
+    symbols.clone(),
+    scope_analysis.clone(),
+    class_hierarchy.clone(),
+  );
+  floating_promises_collector.visit_module(&module);
+
+  let (undocumented_throws, stale_throws_tags) = check_throws_tags(
+    &throw_collector.documented_functions,
+    &call_collector.calls,
+    &call_collector.builtin_calls,
+  );
+
+  let throwing_overrides =
+    class_hierarchy.throwing_overrides(&symbols, &throw_collector.functions_with_throws);
+
   let combined_analyzers = CombinedAnalyzers {
     throw_analyzer: throw_collector,
     call_finder: call_collector,
     import_usage_finder: import_usages_collector,
+    rejection_finder: rejections_collector,
+    floating_promise_finder: floating_promises_collector,
   };
 
-  (combined_analyzers.into(), cm)
+  let mut result: AnalysisResult = combined_analyzers.into();
+  result.parse_errors = parse_errors;
+  result.undocumented_throws = undocumented_throws;
+  result.stale_throws_tags = stale_throws_tags;
+  result.throwing_overrides = throwing_overrides;
+  result.re_exports = symbols.re_exports().to_vec();
+
+  Ok((result, cm))
 }
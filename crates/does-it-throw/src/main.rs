@@ -1,76 +1,645 @@
+extern crate clap;
 extern crate does_it_throw;
+extern crate globset;
+extern crate ignore;
+extern crate serde_json;
 extern crate swc_common;
-use std::fs;
-
-use self::swc_common::{sync::Lrc, SourceMap};
-use does_it_throw::{analyze_code, UserSettings};
-
-pub fn main() {
-  let sample_code = fs::read_to_string("crates/does-it-throw/src/fixtures/sample.ts")
-    .expect("Something went wrong reading the file");
-  let cm: Lrc<SourceMap> = Default::default();
-  let user_settings = UserSettings {
-    include_try_statement_throws: false,
-    ignore_statements: vec!["@it-throws".to_string()],
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use self::swc_common::Span;
+use clap::{Parser, ValueEnum};
+use does_it_throw::builtins::ThrowingBuiltin;
+use does_it_throw::cache::AnalysisCache;
+use does_it_throw::project::{
+  analyze_project_files_cached, default_threads, is_source_file, normalize_path, ConfigError,
+  FileAnalysis, ProjectAnalysis,
+};
+use does_it_throw::rejection_finder::RejectionKind;
+use does_it_throw::sarif::{file_sarif_findings, sarif_results, Region, SarifLevel, SarifLog};
+use does_it_throw::throw_finder::{error_classes, ThrownValue};
+use does_it_throw::UserSettings;
+use globset::Glob;
+use ignore::WalkBuilder;
+
+/// Finds throw statements, and calls to functions that may throw, in JavaScript and TypeScript code
+#[derive(Parser, Debug)]
+#[command(name = "does-it-throw", version)]
+struct Cli {
+  /// Files, directories or glob patterns to analyze
+  #[arg(default_value = ".")]
+  paths: Vec<String>,
+
+  /// Project root, used to resolve `tsconfig.json` paths and imported files
+  #[arg(long, default_value = ".")]
+  root: PathBuf,
+
+  /// Include throw statements inside try statements
+  #[arg(long)]
+  include_try_statement_throws: bool,
+
+  /// Ignore throw statements with comments above that contain this text (repeatable)
+  #[arg(long = "ignore-statement", value_name = "TEXT", default_values_t = ["@it-throws".to_string(), "@does-it-throw-ignore".to_string()])]
+  ignore_statements: Vec<String>,
+
+  /// Keep analyzing the rest of a file when it has syntax errors
+  #[arg(long)]
+  recover_from_parse_errors: bool,
+
+  /// Mark callers of throwing functions as throwing, up to this many levels (0 disables)
+  #[arg(long, value_name = "DEPTH", default_value_t = 0)]
+  max_propagation_depth: usize,
+
+  /// Report calls to this API as throwing, in addition to the built-in catalog (repeatable).
+  /// Error classes can follow an `=`, e.g. `yaml.parse=YAMLException` or `new Temporal.PlainDate=RangeError`
+  #[arg(long = "throwing-builtin", value_name = "CALLEE[=CLASSES]", value_parser = parse_throwing_builtin)]
+  throwing_builtins: Vec<ThrowingBuiltin>,
+
+  /// Exit with a non-zero status when more than this many uncaught throws are found
+  #[arg(long, value_name = "COUNT")]
+  max_uncaught_throws: Option<usize>,
+
+  /// Do not honor `.gitignore` and other ignore files when walking directories
+  #[arg(long)]
+  no_ignore: bool,
+
+  /// Analyze files on this many threads (defaults to one per core)
+  #[arg(long, value_name = "COUNT")]
+  threads: Option<usize>,
+
+  /// Load the analyses of the previous run from this file, and save this run's to it.
+  /// Files that did not change since are not analyzed again.
+  #[arg(long, value_name = "PATH")]
+  cache: Option<PathBuf>,
+
+  /// How to print the findings
+  #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+  format: OutputFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+  /// Each finding with the source line it is on, then a summary
+  Human,
+  /// A SARIF 2.1.0 log, for code-scanning dashboards
+  Sarif,
+}
+
+impl Cli {
+  fn user_settings(&self) -> UserSettings {
+    UserSettings {
+      include_try_statement_throws: self.include_try_statement_throws,
+      ignore_statements: self.ignore_statements.clone(),
+      recover_from_parse_errors: self.recover_from_parse_errors,
+      max_propagation_depth: self.max_propagation_depth,
+      throwing_builtins: self.throwing_builtins.clone(),
+    }
+  }
+}
+
+fn parse_throwing_builtin(value: &str) -> Result<ThrowingBuiltin, String> {
+  let (callee, error_classes) = match value.split_once('=') {
+    Some((callee, error_classes)) => (
+      callee,
+      error_classes
+        .split(',')
+        .map(str::trim)
+        .filter(|error_class| !error_class.is_empty())
+        .map(str::to_string)
+        .collect(),
+    ),
+    None => (value, vec![]),
   };
-  let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
-  for import in result.import_sources.into_iter() {
-    println!("Imported {}", import);
-  }
-  for fun in result.functions_with_throws.clone().into_iter() {
-    let start = _cm.lookup_char_pos(fun.throw_statement.lo());
-    let end = _cm.lookup_char_pos(fun.throw_statement.hi());
-    println!(
-      "Function throws: {}, className {}",
-      fun.function_or_method_name,
-      fun.class_name.unwrap_or_else(|| "NOT_SET".to_string())
-    );
-    println!(
-      "From line {} column {} to line {} column {}",
-      start.line, start.col_display, end.line, end.col_display
-    );
-    for span in &fun.throw_spans {
-      let start = _cm.lookup_char_pos(span.lo());
-      let end = _cm.lookup_char_pos(span.hi());
-      println!(
-        "  Throw from line {} column {} to line {} column {}",
-        start.line, start.col_display, end.line, end.col_display
+  if callee.trim().is_empty() {
+    return Err("expected a callee such as `yaml.parse` or `new URL`".to_string());
+  }
+  Ok(ThrowingBuiltin::new(callee, error_classes))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FindingKind {
+  ParseError,
+  ThrowStatement,
+  CallToThrow,
+  CallToImportedThrow,
+  PromiseRejection,
+  FloatingPromise,
+  UndocumentedThrow,
+  StaleThrowsTag,
+  CallToThrowingBuiltin,
+  ThrowingOverride,
+}
+
+struct Finding {
+  kind: FindingKind,
+  span: Span,
+  message: String,
+}
+
+impl Finding {
+  // Parse errors, `@throws` tag mismatches and throwing overrides are reported but are not
+  // throws of their own
+  fn is_uncaught_throw(&self) -> bool {
+    !matches!(
+      self.kind,
+      FindingKind::ParseError
+        | FindingKind::UndocumentedThrow
+        | FindingKind::StaleThrowsTag
+        | FindingKind::ThrowingOverride
+    )
+  }
+}
+
+fn is_glob(path: &str) -> bool {
+  path.contains(['*', '?', '[', '{'])
+}
+
+// Walks `directory` with the same rules as `git` (plus `node_modules`), unless `no_ignore` is set
+fn walk_source_files(directory: &Path, no_ignore: bool) -> Vec<PathBuf> {
+  WalkBuilder::new(directory)
+    .standard_filters(!no_ignore)
+    .require_git(false)
+    .filter_entry(|entry| entry.file_name() != "node_modules")
+    .build()
+    .filter_map(Result::ok)
+    .filter(|entry| {
+      entry
+        .file_type()
+        .is_some_and(|file_type| file_type.is_file())
+    })
+    .map(|entry| entry.into_path())
+    .filter(|path| is_source_file(path))
+    .collect()
+}
+
+fn collect_files(paths: &[String], no_ignore: bool) -> Result<Vec<PathBuf>, String> {
+  let mut files = BTreeSet::new();
+  for path in paths {
+    let as_path = Path::new(path);
+    if as_path.is_file() {
+      files.insert(normalize_path(as_path));
+    } else if as_path.is_dir() {
+      files.extend(
+        walk_source_files(as_path, no_ignore)
+          .iter()
+          .map(|file| normalize_path(file)),
       );
+    } else if is_glob(path) {
+      let matcher = Glob::new(path)
+        .map_err(|error| format!("invalid glob `{}`: {}", path, error))?
+        .compile_matcher();
+      // walk from the longest directory prefix without glob characters
+      let base: PathBuf = as_path
+        .components()
+        .take_while(|component| !is_glob(&component.as_os_str().to_string_lossy()))
+        .collect();
+      let base = if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+      } else {
+        base
+      };
+      files.extend(
+        walk_source_files(&base, no_ignore)
+          .into_iter()
+          .filter(|file| {
+            matcher.is_match(file) || matcher.is_match(file.strip_prefix("./").unwrap_or(file))
+          })
+          .map(|file| normalize_path(&file)),
+      );
+    } else {
+      return Err(format!("no such file or directory: {}", path));
     }
   }
+  Ok(files.into_iter().collect())
+}
 
-  for throw_id in result.functions_with_throws.into_iter() {
-    println!("throw id: {}", throw_id.id);
-  }
+// e.g. " `ValidationError`, `NotFoundError`", or nothing when no error class is known
+fn describe_error_classes(error_classes: &[String]) -> String {
+  error_classes
+    .iter()
+    .map(|error_class| format!(" `{}`", error_class))
+    .collect::<Vec<String>>()
+    .join(",")
+}
 
-  println!("------- Calls to throws --------");
-  for call in result.calls_to_throws.into_iter() {
-    let start = _cm.lookup_char_pos(call.call_span.lo());
-    let end = _cm.lookup_char_pos(call.call_span.hi());
-    println!("Call throws: {}", call.id);
-    println!(
-      "From line {} column {} to line {} column {}",
-      start.line, start.col_display, end.line, end.col_display
+fn findings_for_file(analysis: &FileAnalysis, current_dir: &Path) -> Vec<Finding> {
+  let mut findings = vec![];
+  for parse_error in &analysis.result.parse_errors {
+    findings.push(Finding {
+      kind: FindingKind::ParseError,
+      span: parse_error.span,
+      message: format!("syntax error: {}", parse_error.message),
+    });
+  }
+  for throw_map in &analysis.result.functions_with_throws {
+    for throw_span in &throw_map.throw_spans {
+      let thrown_values: Vec<ThrownValue> = throw_map
+        .thrown_values
+        .iter()
+        .filter(|thrown_value| thrown_value.throw_span == *throw_span)
+        .cloned()
+        .collect();
+      let error_classes = describe_error_classes(&error_classes(&thrown_values));
+      findings.push(Finding {
+        kind: FindingKind::ThrowStatement,
+        span: *throw_span,
+        message: format!(
+          "uncaught {}{} in `{}`",
+          if throw_map.is_rethrow(*throw_span) {
+            "rethrow of caught error"
+          } else {
+            "throw"
+          },
+          if error_classes.is_empty() {
+            error_classes
+          } else {
+            format!(" of{}", error_classes)
+          },
+          throw_map.function_or_method_name
+        ),
+      });
+    }
+  }
+  for call in &analysis.result.calls_to_throws {
+    findings.push(Finding {
+      kind: FindingKind::CallToThrow,
+      span: call.call_span,
+      message: format!(
+        "call to `{}`, which may throw{}",
+        call.throw_map.function_or_method_name,
+        describe_error_classes(&call.error_classes())
+      ),
+    });
+  }
+  for builtin_call in &analysis.result.calls_to_throwing_builtins {
+    findings.push(Finding {
+      kind: FindingKind::CallToThrowingBuiltin,
+      span: builtin_call.call_span,
+      message: format!(
+        "call to `{}`, which may throw{}",
+        builtin_call.builtin.callee,
+        describe_error_classes(&builtin_call.builtin.error_classes)
+      ),
+    });
+  }
+  for imported_throw in &analysis.imported_throws {
+    findings.push(Finding {
+      kind: FindingKind::CallToImportedThrow,
+      span: imported_throw.usage.usage_span,
+      message: format!(
+        "call to `{}`, imported from {}, which may throw{}",
+        imported_throw.throw_map.function_or_method_name,
+        imported_throw
+          .source_file
+          .strip_prefix(current_dir)
+          .unwrap_or(&imported_throw.source_file)
+          .display(),
+        describe_error_classes(&imported_throw.throw_map.error_classes())
+      ),
+    });
+  }
+  // throws in async functions are already reported as throw statements
+  for rejection in analysis
+    .result
+    .rejections
+    .iter()
+    .filter(|rejection| rejection.kind != RejectionKind::AsyncThrow)
+  {
+    let error_classes = describe_error_classes(
+      &rejection
+        .rejected_value
+        .iter()
+        .filter_map(|value| value.error_class.clone())
+        .collect::<Vec<String>>(),
     );
+    findings.push(Finding {
+      kind: FindingKind::PromiseRejection,
+      span: rejection.span,
+      message: format!(
+        "promise rejection{} in `{}`",
+        if error_classes.is_empty() {
+          error_classes
+        } else {
+          format!(" of{}", error_classes)
+        },
+        rejection.function_or_method_name
+      ),
+    });
+  }
+  for floating_promise in &analysis.result.floating_promises {
+    findings.push(Finding {
+      kind: FindingKind::FloatingPromise,
+      span: floating_promise.span,
+      message: format!(
+        "promise returned by `{}` may reject but is neither awaited nor handled",
+        floating_promise.callee_id
+      ),
+    });
+  }
+  for undocumented_throw in &analysis.result.undocumented_throws {
+    findings.push(Finding {
+      kind: FindingKind::UndocumentedThrow,
+      span: undocumented_throw.throw_span,
+      message: format!(
+        "throw{} in `{}` is not documented by a @throws tag",
+        describe_error_classes(&undocumented_throw.error_classes),
+        undocumented_throw.function_or_method_name
+      ),
+    });
   }
+  for stale_throws_tag in &analysis.result.stale_throws_tags {
+    findings.push(Finding {
+      kind: FindingKind::StaleThrowsTag,
+      span: stale_throws_tag.throws_tag.span,
+      message: format!(
+        "@throws tag{} of `{}` matches no throw",
+        describe_error_classes(&stale_throws_tag.throws_tag.error_classes),
+        stale_throws_tag.function_or_method_name
+      ),
+    });
+  }
+  for throwing_override in &analysis.result.throwing_overrides {
+    findings.push(Finding {
+      kind: FindingKind::ThrowingOverride,
+      span: throwing_override.span,
+      message: format!(
+        "`{}` may throw while `{}`, which it overrides, does not",
+        throwing_override.throw_map.symbol, throwing_override.base
+      ),
+    });
+  }
+  findings.sort_by_key(|finding| (finding.span.lo, finding.span.hi, finding.kind));
+  findings
+}
 
-  println!("-------- Imported identifiers usages --------");
-  for identifier_usage in result.imported_identifier_usages.into_iter() {
-    let start = _cm.lookup_char_pos(identifier_usage.usage_span.lo());
-    let end = _cm.lookup_char_pos(identifier_usage.usage_span.hi());
-    let identifier_name = &identifier_usage.id;
-    println!(
-      "{} From line {} column {} to line {} column {}",
-      identifier_name, start.line, start.col_display, end.line, end.col_display
-    );
+// The findings of `files` as a SARIF log, with paths relative to `current_dir`. Syntax errors
+// are left out, SARIF has no rule for them.
+fn sarif_log(project: &ProjectAnalysis, files: &[PathBuf], current_dir: &Path) -> SarifLog {
+  let mut results = vec![];
+  for file in files {
+    let Some(analysis) = project.file(file) else {
+      continue;
+    };
+    let display_path = analysis
+      .path
+      .strip_prefix(current_dir)
+      .unwrap_or(&analysis.path);
+    let uri = display_path.to_string_lossy().replace('\\', "/");
+    results.extend(sarif_results(
+      file_sarif_findings(analysis),
+      &uri,
+      |span| Region::in_file(analysis, span),
+      |_| SarifLevel::Warning,
+    ));
+  }
+  SarifLog::new(results)
+}
+
+// Renders a finding the way compilers do: a location header, then the source line with the span underlined
+fn render_finding(analysis: &FileAnalysis, display_path: &Path, finding: &Finding) -> String {
+  let start = analysis.location(finding.span.lo);
+  let end = analysis.location(finding.span.hi);
+  let severity = if finding.kind == FindingKind::ParseError {
+    "error"
+  } else {
+    "warning"
+  };
+  let mut rendered = format!(
+    "{}: {}\n  --> {}:{}:{}\n",
+    severity,
+    finding.message,
+    display_path.display(),
+    start.line,
+    start.column + 1
+  );
+  if let Some(line) = analysis.line(start.line) {
+    let gutter = " ".repeat(start.line.to_string().len());
+    let line_length = line.chars().count();
+    let underline_end = if end.line == start.line {
+      end.column
+    } else {
+      line_length
+    };
+    let underline_length = underline_end.saturating_sub(start.column).max(1);
+    rendered.push_str(&format!("{} |\n", gutter));
+    rendered.push_str(&format!("{} | {}\n", start.line, line));
+    rendered.push_str(&format!(
+      "{} | {}{}\n",
+      gutter,
+      " ".repeat(start.column),
+      "^".repeat(underline_length)
+    ));
+  }
+  rendered
+}
+
+fn render_config_error(config_error: &ConfigError, current_dir: &Path) -> String {
+  format!(
+    "warning: could not parse config, path aliases are not resolved: {}\n  --> {}:{}:{}\n",
+    config_error.message,
+    config_error
+      .path
+      .strip_prefix(current_dir)
+      .unwrap_or(&config_error.path)
+      .display(),
+    config_error.location.line,
+    config_error.location.column + 1
+  )
+}
+
+pub fn main() -> ExitCode {
+  let cli = Cli::parse();
+  let files = match collect_files(&cli.paths, cli.no_ignore) {
+    Ok(files) => files,
+    Err(error) => {
+      eprintln!("error: {}", error);
+      return ExitCode::from(2);
+    }
+  };
+
+  let user_settings = cli.user_settings();
+  let threads = cli.threads.unwrap_or_else(default_threads);
+  let mut cache = match &cli.cache {
+    Some(cache_path) => match AnalysisCache::load(cache_path) {
+      Ok(cache) => cache,
+      Err(error) => {
+        eprintln!("error: could not read {}: {}", cache_path.display(), error);
+        return ExitCode::from(2);
+      }
+    },
+    None => AnalysisCache::new(),
+  };
+  let project =
+    match analyze_project_files_cached(&cli.root, &files, &user_settings, &mut cache, threads) {
+      Ok(project) => project,
+      Err(error) => {
+        eprintln!("error: {}", error);
+        return ExitCode::from(2);
+      }
+    };
+  if let Some(cache_path) = &cli.cache {
+    // the findings do not depend on it, so a cache that cannot be saved is only a warning
+    if let Err(error) = cache.save(cache_path) {
+      eprintln!(
+        "warning: could not save {}: {}",
+        cache_path.display(),
+        error
+      );
+    }
+  }
+
+  let current_dir = std::env::current_dir().unwrap_or_default();
+  for config_error in &project.config_errors {
+    eprintln!("{}", render_config_error(config_error, &current_dir));
+  }
+  let mut uncaught_throws = 0;
+  let mut files_with_throws = 0;
+  for file in &files {
+    let Some(analysis) = project.file(file) else {
+      eprintln!("error: could not read {}", file.display());
+      continue;
+    };
+    let display_path = analysis
+      .path
+      .strip_prefix(&current_dir)
+      .unwrap_or(&analysis.path);
+    let findings = findings_for_file(analysis, &current_dir);
+    let throws_in_file = findings
+      .iter()
+      .filter(|finding| finding.is_uncaught_throw())
+      .count();
+    if throws_in_file > 0 {
+      files_with_throws += 1;
+    }
+    uncaught_throws += throws_in_file;
+    if cli.format == OutputFormat::Human {
+      for finding in &findings {
+        println!("{}", render_finding(analysis, display_path, finding));
+      }
+    }
+  }
+
+  match cli.format {
+    OutputFormat::Human => println!(
+      "Found {} uncaught throw{} in {} of {} file{}",
+      uncaught_throws,
+      if uncaught_throws == 1 { "" } else { "s" },
+      files_with_throws,
+      files.len(),
+      if files.len() == 1 { "" } else { "s" }
+    ),
+    OutputFormat::Sarif => {
+      let log = sarif_log(&project, &files, &current_dir);
+      println!("{}", serde_json::to_string_pretty(&log).unwrap());
+    }
+  }
+
+  match cli.max_uncaught_throws {
+    Some(max_uncaught_throws) if uncaught_throws > max_uncaught_throws => {
+      eprintln!(
+        "error: {} uncaught throws exceed the maximum of {}",
+        uncaught_throws, max_uncaught_throws
+      );
+      ExitCode::FAILURE
+    }
+    _ => ExitCode::SUCCESS,
   }
 }
 
 #[cfg(test)]
 mod integration_tests {
   use std::env;
+  use std::fs;
+  use std::path::Path;
 
   use super::*;
+  use does_it_throw::analyze_code;
+  use does_it_throw::cache::{content_hash, AnalysisCache, FileSummary, ThrowingFunction};
+  use does_it_throw::code_actions::{code_actions_at, CodeAction, CodeActionKind};
+  use does_it_throw::project::{
+    analyze_project, analyze_project_files, analyze_project_files_cached, analyze_sources,
+    find_source_files, Location, ModuleResolver,
+  };
+  use does_it_throw::query::{
+    byte_pos_at, calls_to_function_at, exported_throw_sites, file_throw_info_at,
+    file_throw_sites_at, project_calls_to_function_at, throw_info_at, throwing_function_at,
+    ThrowSite,
+  };
+  use does_it_throw::rejection_finder::RejectionKind;
+  use does_it_throw::symbol::{ReExport, ReExportKind};
+  use does_it_throw::throw_finder::{MemberKind, ThrowRegion, ThrownValueKind};
+  use swc_common::{sync::Lrc, SourceMap, SourceMapper};
+
+  #[test]
+  fn test_cli_collects_files_from_directories_and_globs() {
+    let fixtures = env::var("CARGO_MANIFEST_DIR").unwrap() + "/src/fixtures/project";
+    let from_directory = collect_files(&[format!("{}/src", fixtures)], false).unwrap();
+    let names: Vec<_> = from_directory
+      .iter()
+      .map(|file| {
+        file
+          .strip_prefix(&fixtures)
+          .unwrap()
+          .to_string_lossy()
+          .into_owned()
+      })
+      .collect();
+    assert_eq!(
+      names,
+      vec![
+        "src/baseUrl.js",
+        "src/lib/index.ts",
+        "src/main.ts",
+        "src/relative.ts",
+        "src/utils/alias.ts"
+      ]
+    );
+
+    let from_glob = collect_files(&[format!("{}/**/*.js", fixtures)], false).unwrap();
+    assert_eq!(
+      from_glob,
+      vec![normalize_path(Path::new(&format!(
+        "{}/src/baseUrl.js",
+        fixtures
+      )))]
+    );
+
+    assert!(collect_files(&[format!("{}/missing.ts", fixtures)], false).is_err());
+  }
+
+  #[test]
+  fn test_cli_counts_uncaught_throws() {
+    let root = env::var("CARGO_MANIFEST_DIR").unwrap() + "/src/fixtures/project";
+    let main_file = normalize_path(Path::new(&format!("{}/src/main.ts", root)));
+    let cli = Cli::parse_from(["does-it-throw", "--max-uncaught-throws", "2"]);
+    let project = analyze_project_files(
+      Path::new(&root),
+      std::slice::from_ref(&main_file),
+      &cli.user_settings(),
+    )
+    .unwrap();
+    let analysis = project.file(&main_file).unwrap();
+    let findings = findings_for_file(analysis, Path::new(&root));
+
+    assert_eq!(
+      findings
+        .iter()
+        .filter(|finding| finding.is_uncaught_throw())
+        .count(),
+      4
+    );
+    assert!(findings
+      .iter()
+      .all(|finding| finding.kind == FindingKind::CallToImportedThrow));
+
+    let rendered = render_finding(analysis, Path::new("src/main.ts"), &findings[0]);
+    assert!(rendered.contains("--> src/main.ts:9:3"));
+    assert!(rendered.contains("9 |   throwsRelative()"));
+    assert!(rendered.contains("  |   ^^^^^^^^^^^^^^^^"));
+    assert!(rendered.contains("imported from src/relative.ts, which may throw `Error`"));
+  }
+
   #[test]
   fn test_ts_class() {
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
@@ -81,8 +650,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 4);
@@ -96,7 +666,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     assert!(function_names_contains(
@@ -116,7 +686,7 @@ mod integration_tests {
       .iter()
       .map(|c| c.id.clone())
       .collect();
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     assert!(calls_to_throws_contains(
@@ -143,8 +713,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 4);
@@ -158,7 +729,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     assert!(function_names_contains(
@@ -178,7 +749,7 @@ mod integration_tests {
       .iter()
       .map(|c| c.id.clone())
       .collect();
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     assert!(calls_to_throws_contains(
@@ -205,8 +776,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 4);
@@ -220,7 +792,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     [
@@ -239,7 +811,7 @@ mod integration_tests {
       .map(|c| c.id.clone())
       .collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     [
@@ -262,8 +834,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 4);
@@ -277,7 +850,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     [
@@ -296,7 +869,7 @@ mod integration_tests {
       .map(|c| c.id.clone())
       .collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     [
@@ -320,8 +893,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 3);
@@ -335,7 +909,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     [
@@ -353,7 +927,7 @@ mod integration_tests {
       .map(|c| c.id.clone())
       .collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     [
@@ -377,8 +951,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 3);
@@ -392,7 +967,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     [
@@ -410,7 +985,7 @@ mod integration_tests {
       .map(|c| c.id.clone())
       .collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     [
@@ -433,8 +1008,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 5);
@@ -448,7 +1024,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     [
@@ -467,7 +1043,7 @@ mod integration_tests {
       .iter()
       .map(|c| c.id.clone())
       .collect();
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     [
@@ -496,8 +1072,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 4);
@@ -511,7 +1088,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
 
@@ -526,7 +1103,7 @@ mod integration_tests {
       .map(|c| c.id.clone())
       .collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     [
@@ -549,8 +1126,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 4);
@@ -564,7 +1142,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
 
@@ -579,7 +1157,7 @@ mod integration_tests {
       .map(|c| c.id.clone())
       .collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     [
@@ -602,21 +1180,44 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 0);
     assert_eq!(result.calls_to_throws.len(), 0);
-    assert_eq!(result.imported_identifier_usages.len(), 5);
+    assert_eq!(result.imported_identifier_usages.len(), 9);
     assert_eq!(result.import_sources.len(), 5);
 
+    // aliased imports are matched by their local binding, and looked up by the exported name
+    let mut aliased_usages: Vec<(String, String)> = result
+      .imported_identifier_usages
+      .iter()
+      .filter(|usage| usage.id != usage.import_id())
+      .map(|usage| (usage.identifier_name.clone(), usage.import_id()))
+      .collect();
+    aliased_usages.sort();
+    assert_eq!(
+      aliased_usages,
+      vec![
+        ("SomeThrow2".to_string(), "NOT_SET-SomeThrow".to_string()),
+        (
+          "SomethingElse2".to_string(),
+          "NOT_SET-SomethingElse".to_string()
+        ),
+        ("Test".to_string(), "NOT_SET-testing".to_string()),
+        ("Test2".to_string(), "NOT_SET-testing".to_string()),
+        ("Testing".to_string(), "NOT_SET-default".to_string()),
+      ]
+    );
+
     let imported_identifier_usages = result
       .imported_identifier_usages
       .into_iter()
       .map(|i| i.id)
       .collect::<Vec<String>>();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     [
@@ -630,7 +1231,7 @@ mod integration_tests {
     .for_each(|f| assert!(function_names_contains(&imported_identifier_usages, f)));
 
     let import_sources = result.import_sources.into_iter().collect::<Vec<String>>();
-    fn import_sources_contains(import_sources: &Vec<String>, import_source: &str) -> bool {
+    fn import_sources_contains(import_sources: &[String], import_source: &str) -> bool {
       import_sources.iter().any(|f| f == import_source)
     }
     [
@@ -654,8 +1255,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 2);
@@ -669,7 +1271,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
     ["_contextFromWorkflow", "_contextFromWorkflow"]
@@ -683,7 +1285,7 @@ mod integration_tests {
       .map(|c| c.id.clone())
       .collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
     ["SomeClass-someCallToThrow", "SomeClass-someCallToThrow"]
@@ -701,8 +1303,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 2);
@@ -716,7 +1319,7 @@ mod integration_tests {
       .iter()
       .map(|f| f.function_or_method_name.clone())
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
 
@@ -731,7 +1334,7 @@ mod integration_tests {
       .map(|c| c.id.clone())
       .collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
 
@@ -740,7 +1343,7 @@ mod integration_tests {
       .for_each(|f| assert!(calls_to_throws_contains(&calls_to_throws, f)));
 
     let import_sources = result.import_sources.into_iter().collect::<Vec<String>>();
-    fn import_sources_contains(import_sources: &Vec<String>, import_source: &str) -> bool {
+    fn import_sources_contains(import_sources: &[String], import_source: &str) -> bool {
       import_sources.iter().any(|f| f == import_source)
     }
     ["./something"]
@@ -752,10 +1355,7 @@ mod integration_tests {
       .into_iter()
       .map(|i| i.id)
       .collect::<Vec<String>>();
-    fn import_identifiers_contains(
-      import_identifiers: &Vec<String>,
-      import_identifier: &str,
-    ) -> bool {
+    fn import_identifiers_contains(import_identifiers: &[String], import_identifier: &str) -> bool {
       import_identifiers.iter().any(|f| f == import_identifier)
     }
     ["someObjectLiteral-objectLiteralThrow", "NOT_SET-SomeThrow"]
@@ -774,8 +1374,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: true,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 8);
@@ -790,7 +1391,7 @@ mod integration_tests {
       .into_iter()
       .map(|f| f.function_or_method_name)
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
 
@@ -810,7 +1411,7 @@ mod integration_tests {
     // calls to throws
     let calls_to_throws: Vec<String> = result.calls_to_throws.into_iter().map(|c| c.id).collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
 
@@ -841,8 +1442,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     assert_eq!(result.functions_with_throws.len(), 1);
     assert_eq!(result.calls_to_throws.len(), 0);
@@ -858,8 +1460,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: true,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     // general result assertions
     assert_eq!(result.functions_with_throws.len(), 4);
@@ -883,8 +1486,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: false,
       ignore_statements: vec!["@it-throws".to_string()],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     assert_eq!(result.functions_with_throws.len(), 2);
     assert_eq!(result.calls_to_throws.len(), 0);
@@ -895,7 +1499,7 @@ mod integration_tests {
       .into_iter()
       .map(|f| f.function_or_method_name)
       .collect();
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
 
@@ -915,8 +1519,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: true,
       ignore_statements: vec![],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     assert_eq!(result.functions_with_throws.len(), 11);
     assert_eq!(result.calls_to_throws.len(), 15);
@@ -933,8 +1538,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: true,
       ignore_statements: vec![],
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     assert_eq!(result.functions_with_throws.len(), 6);
     assert_eq!(result.calls_to_throws.len(), 7);
@@ -955,8 +1561,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: true,
       ignore_statements,
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     assert_eq!(result.functions_with_throws.len(), 0);
     assert_eq!(result.calls_to_throws.len(), 0);
@@ -977,8 +1584,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: true,
       ignore_statements,
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     assert_eq!(result.functions_with_throws.len(), 0);
     assert_eq!(result.calls_to_throws.len(), 0);
@@ -999,8 +1607,9 @@ mod integration_tests {
     let user_settings = UserSettings {
       include_try_statement_throws: true,
       ignore_statements,
+      ..Default::default()
     };
-    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings);
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
 
     assert_eq!(result.functions_with_throws.len(), 2);
     assert_eq!(result.calls_to_throws.len(), 7);
@@ -1012,7 +1621,7 @@ mod integration_tests {
       .map(|f| f.function_or_method_name)
       .collect();
 
-    fn function_names_contains(function_names: &Vec<String>, function_name: &str) -> bool {
+    fn function_names_contains(function_names: &[String], function_name: &str) -> bool {
       function_names.iter().any(|f| f == function_name)
     }
 
@@ -1022,7 +1631,7 @@ mod integration_tests {
 
     let calls_to_throws: Vec<String> = result.calls_to_throws.into_iter().map(|c| c.id).collect();
 
-    fn calls_to_throws_contains(calls_to_throws: &Vec<String>, call_to_throw: &str) -> bool {
+    fn calls_to_throws_contains(calls_to_throws: &[String], call_to_throw: &str) -> bool {
       calls_to_throws.iter().any(|c| c == call_to_throw)
     }
 
@@ -1038,4 +1647,1956 @@ mod integration_tests {
     .iter()
     .for_each(|f| assert!(calls_to_throws_contains(&calls_to_throws, f)));
   }
+
+  #[test]
+  fn test_parse_error_is_returned_instead_of_panicking() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/parseError.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings {
+      recover_from_parse_errors: false,
+      ..Default::default()
+    };
+    let error = analyze_code(&sample_code, cm.clone(), &user_settings)
+      .err()
+      .expect("parsing should fail");
+
+    assert_eq!(error.errors.len(), 1);
+    let start = cm.lookup_char_pos(error.errors[0].span.lo());
+    assert_eq!(start.line, 8);
+    assert!(!error.errors[0].message.is_empty());
+  }
+
+  #[test]
+  fn test_parse_error_recovery() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/parseError.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings {
+      recover_from_parse_errors: true,
+      ..Default::default()
+    };
+    let (result, cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    assert!(!result.parse_errors.is_empty());
+    let start = cm.lookup_char_pos(result.parse_errors[0].span.lo());
+    assert_eq!(start.line, 8);
+    // the code left out to recover is reported too
+    let skipped = &result.parse_errors[1];
+    assert_eq!(
+      cm.span_to_snippet(skipped.span).unwrap(),
+      "const value = someCall("
+    );
+    assert!(skipped.message.starts_with("Skipped to recover from: "));
+
+    let function_names: Vec<String> = result
+      .functions_with_throws
+      .into_iter()
+      .map(|f| f.function_or_method_name)
+      .collect();
+    assert_eq!(function_names.len(), 2);
+    assert!(function_names.contains(&"throwsBeforeError".to_string()));
+    assert!(function_names.contains(&"throwsAfterError".to_string()));
+  }
+
+  #[test]
+  fn test_propagation_disabled_by_default() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/propagation.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings::default();
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    assert_eq!(result.functions_with_throws.len(), 1);
+    assert_eq!(result.calls_to_throws.len(), 1);
+  }
+
+  #[test]
+  fn test_propagation_through_call_graph() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/propagation.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings {
+      max_propagation_depth: 2,
+      ..Default::default()
+    };
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    let mut function_names: Vec<String> = result
+      .functions_with_throws
+      .iter()
+      .map(|f| f.function_or_method_name.clone())
+      .collect();
+    function_names.sort();
+    assert_eq!(function_names, vec!["a", "b", "c"]);
+
+    let a = result
+      .functions_with_throws
+      .iter()
+      .find(|f| f.function_or_method_name == "a")
+      .unwrap();
+    assert!(a.throw_spans.is_empty());
+    assert_eq!(a.propagation_path, vec!["NOT_SET-b", "NOT_SET-c"]);
+
+    let mut call_ids: Vec<String> = result
+      .calls_to_throws
+      .iter()
+      .map(|c| c.id.clone())
+      .collect();
+    call_ids.sort();
+    assert_eq!(
+      call_ids,
+      vec!["NOT_SET-a", "NOT_SET-b", "NOT_SET-callsA", "NOT_SET-caught"]
+    );
+    let call_in_callers_a = result
+      .calls_to_throws
+      .iter()
+      .find(|c| c.id == "NOT_SET-callsA")
+      .unwrap();
+    assert_eq!(
+      call_in_callers_a.propagation_path,
+      vec!["NOT_SET-a", "NOT_SET-b", "NOT_SET-c"]
+    );
+  }
+
+  #[test]
+  fn test_propagation_reaches_fixed_point() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/propagation.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings {
+      max_propagation_depth: 100,
+      ..Default::default()
+    };
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    let mut function_names: Vec<String> = result
+      .functions_with_throws
+      .into_iter()
+      .map(|f| f.function_or_method_name)
+      .collect();
+    function_names.sort();
+    // `caught` only calls `b` inside a try block, so it does not throw
+    assert_eq!(function_names, vec!["a", "b", "c", "callsA"]);
+  }
+
+  #[test]
+  fn test_propagation_through_recursive_functions() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/recursion.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings {
+      max_propagation_depth: 100,
+      ..Default::default()
+    };
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    let mut propagation_paths: Vec<(String, Vec<String>)> = result
+      .functions_with_throws
+      .iter()
+      .map(|f| {
+        (
+          f.function_or_method_name.clone(),
+          f.propagation_path.clone(),
+        )
+      })
+      .collect();
+    propagation_paths.sort();
+    assert_eq!(
+      propagation_paths,
+      vec![
+        (
+          "countdown".to_string(),
+          vec!["NOT_SET-countdown".to_string()]
+        ),
+        (
+          "isEven".to_string(),
+          vec!["NOT_SET-isOdd".to_string(), "NOT_SET-isEven".to_string()]
+        ),
+        ("isOdd".to_string(), vec!["NOT_SET-isEven".to_string()]),
+      ]
+    );
+
+    let mut call_paths: Vec<(String, Vec<String>)> = result
+      .calls_to_throws
+      .iter()
+      .map(|c| (c.id.clone(), c.propagation_path.clone()))
+      .collect();
+    call_paths.sort();
+    assert_eq!(
+      call_paths,
+      vec![
+        (
+          "NOT_SET-countdown".to_string(),
+          vec!["NOT_SET-countdown".to_string()]
+        ),
+        (
+          "NOT_SET-isEven".to_string(),
+          vec!["NOT_SET-isOdd".to_string(), "NOT_SET-isEven".to_string()]
+        ),
+        (
+          "NOT_SET-isOdd".to_string(),
+          vec!["NOT_SET-isEven".to_string(), "NOT_SET-isOdd".to_string()]
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_project_reports_invalid_config() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = format!("{}/src/fixtures/invalidConfig", manifest_dir);
+    let user_settings = UserSettings::default();
+    let project = analyze_project(Path::new(&root), &user_settings).unwrap();
+
+    assert_eq!(project.config_errors.len(), 1);
+    let config_error = &project.config_errors[0];
+    assert_eq!(config_error.path, Path::new(&root).join("tsconfig.json"));
+    assert_eq!(config_error.location.line, 4);
+    assert_eq!(
+      render_config_error(config_error, Path::new(&root)),
+      "warning: could not parse config, path aliases are not resolved: expected `,` or `}`\n  --> tsconfig.json:4:5\n"
+    );
+    // relative imports still resolve
+    let main = project.file(&Path::new(&root).join("src/main.ts")).unwrap();
+    assert_eq!(main.resolved_imports.len(), 1);
+  }
+
+  #[test]
+  fn test_project_resolves_and_links_imports() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = format!("{}/src/fixtures/project", manifest_dir);
+    let user_settings = UserSettings::default();
+    let project = analyze_project(Path::new(&root), &user_settings).unwrap();
+
+    let file_names: Vec<String> = project
+      .files
+      .keys()
+      .map(|path| {
+        path
+          .strip_prefix(&project.root)
+          .unwrap()
+          .to_string_lossy()
+          .to_string()
+      })
+      .collect();
+    assert_eq!(
+      file_names,
+      vec![
+        "src/baseUrl.js",
+        "src/lib/index.ts",
+        "src/main.ts",
+        "src/relative.ts",
+        "src/utils/alias.ts",
+      ]
+    );
+
+    let main = project.file(&Path::new(&root).join("src/main.ts")).unwrap();
+    let resolved: Vec<(String, String)> = main
+      .resolved_imports
+      .iter()
+      .map(|(specifier, path)| {
+        (
+          specifier.clone(),
+          path
+            .strip_prefix(&project.root)
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      resolved,
+      vec![
+        ("./lib".to_string(), "src/lib/index.ts".to_string()),
+        ("./relative".to_string(), "src/relative.ts".to_string()),
+        ("@utils/alias".to_string(), "src/utils/alias.ts".to_string()),
+        ("src/baseUrl".to_string(), "src/baseUrl.js".to_string()),
+      ]
+    );
+
+    let imported_throws: Vec<(String, usize)> = main
+      .imported_throws
+      .iter()
+      .map(|imported_throw| {
+        (
+          imported_throw.throw_map.function_or_method_name.clone(),
+          main.location(imported_throw.usage.usage_span.lo).line,
+        )
+      })
+      .collect();
+    assert_eq!(
+      imported_throws,
+      vec![
+        ("throwsRelative".to_string(), 9),
+        ("throwsFromIndex".to_string(), 13),
+        ("throwsAlias".to_string(), 17),
+        ("throwsBaseUrl".to_string(), 21),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_path_patterns_prefer_the_most_specific_match() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/pathPatterns");
+    let resolver = ModuleResolver::from_root(&root);
+    let importing_file = root.join("src/main.ts");
+    let resolve = |specifier: &str| {
+      resolver
+        .resolve(specifier, &importing_file)
+        .map(|path| path.strip_prefix(&root).unwrap().to_path_buf())
+    };
+
+    // `*` and `@/*` come first in `paths`, and `@/*` would also resolve these
+    assert_eq!(
+      resolve("@/components/link"),
+      Some(PathBuf::from("src/ui/link.ts"))
+    );
+    assert_eq!(
+      resolve("@/components/button"),
+      Some(PathBuf::from("src/ui/primaryButton.ts"))
+    );
+    assert_eq!(resolve("@/format"), Some(PathBuf::from("src/format.ts")));
+    assert_eq!(resolve("lodash"), None);
+  }
+  #[test]
+  fn test_symbol_ids_tell_apart_functions_with_the_same_name() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/symbols.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let (result, cm) = analyze_code(&sample_code, cm, &UserSettings::default()).unwrap();
+
+    let mut throwing_symbols: Vec<String> = result
+      .functions_with_throws
+      .iter()
+      .map(|throw_map| throw_map.symbol.to_string())
+      .collect();
+    throwing_symbols.sort();
+    assert_eq!(throwing_symbols, vec!["csv.parse", "parse"]);
+
+    let csv_parse = result
+      .functions_with_throws
+      .iter()
+      .find(|throw_map| throw_map.symbol.to_string() == "csv.parse")
+      .unwrap();
+    assert_eq!(
+      csv_parse.symbol.export_path(),
+      Some(vec!["csv".to_string(), "parse".to_string()])
+    );
+
+    let mut calls: Vec<(String, String, String)> = result
+      .calls_to_throws
+      .iter()
+      .map(|call| {
+        (
+          call.symbol.to_string(),
+          call.throw_map.symbol.to_string(),
+          cm.span_to_snippet(call.call_span).unwrap(),
+        )
+      })
+      .collect();
+    calls.sort();
+    assert_eq!(
+      calls,
+      vec![
+        (
+          "callsCsv".to_string(),
+          "csv.parse".to_string(),
+          "csv.parse('c')".to_string()
+        ),
+        (
+          "callsParse".to_string(),
+          "parse".to_string(),
+          "parse('a')".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_calls_bind_to_their_declaration() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/shadowing.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let (result, cm) = analyze_code(&sample_code, cm, &UserSettings::default()).unwrap();
+
+    let mut calls: Vec<(String, String)> = result
+      .calls_to_throws
+      .iter()
+      .map(|call| {
+        (
+          cm.span_to_snippet(call.call_span).unwrap(),
+          call.throw_map.symbol.to_string(),
+        )
+      })
+      .collect();
+    calls.sort();
+    assert_eq!(
+      calls,
+      vec![
+        (
+          "repository.save('c')".to_string(),
+          "Repository.save".to_string()
+        ),
+        ("save('d')".to_string(), "save".to_string()),
+        ("save('f')".to_string(), "save".to_string()),
+        (
+          "this.save(record)".to_string(),
+          "Repository.save".to_string()
+        ),
+      ]
+    );
+
+    let import_usages: Vec<String> = result
+      .imported_identifier_usages
+      .iter()
+      .map(|usage| cm.span_to_snippet(usage.usage_span).unwrap())
+      .collect();
+    assert_eq!(import_usages, vec!["load()".to_string()]);
+    let usage = result.imported_identifier_usages.iter().next().unwrap();
+    assert_eq!(usage.symbol.export_path(), Some(vec!["load".to_string()]));
+  }
+
+  #[test]
+  fn test_scope_resolution() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/scopeResolution.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let (result, cm) = analyze_code(&sample_code, cm, &UserSettings::default()).unwrap();
+
+    // locals and nested declarations shadow `parse`, and `obj.save()` is not `Repo.save()`
+    let mut calls: Vec<(String, String)> = result
+      .calls_to_throws
+      .iter()
+      .map(|call| {
+        (
+          cm.span_to_snippet(call.call_span).unwrap(),
+          call.throw_map.symbol.to_string(),
+        )
+      })
+      .collect();
+    calls.sort();
+    assert_eq!(
+      calls,
+      vec![
+        ("Repo.save('g')".to_string(), "Repo.save".to_string()),
+        ("parse('h')".to_string(), "parse".to_string()),
+      ]
+    );
+
+    // parameters and locals named `load` shadow the import
+    let import_usages: Vec<String> = result
+      .imported_identifier_usages
+      .iter()
+      .map(|usage| cm.span_to_snippet(usage.usage_span).unwrap())
+      .collect();
+    assert_eq!(import_usages, vec!["load('i')".to_string()]);
+  }
+
+  #[test]
+  fn test_class_hierarchy() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/inheritance.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let (result, cm) = analyze_code(&sample_code, cm, &UserSettings::default()).unwrap();
+
+    let mut calls: Vec<(String, String)> = result
+      .calls_to_throws
+      .iter()
+      .map(|call| {
+        (
+          cm.span_to_snippet(call.call_span).unwrap(),
+          call.throw_map.symbol.to_string(),
+        )
+      })
+      .collect();
+    calls.sort();
+    assert_eq!(
+      calls,
+      vec![
+        ("child.save()".to_string(), "Child.save".to_string()),
+        ("grandChild.load()".to_string(), "Base.load".to_string()),
+        ("super()".to_string(), "Base.constructor".to_string()),
+        ("super.load()".to_string(), "Base.load".to_string()),
+      ]
+    );
+
+    let throwing_overrides: Vec<(String, String, String)> = result
+      .throwing_overrides
+      .iter()
+      .map(|throwing_override| {
+        (
+          cm.span_to_snippet(throwing_override.span).unwrap(),
+          throwing_override.throw_map.symbol.to_string(),
+          throwing_override.base.to_string(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      throwing_overrides,
+      vec![(
+        "save".to_string(),
+        "Child.save".to_string(),
+        "Base.save".to_string()
+      )]
+    );
+  }
+
+  #[test]
+  fn test_class_members() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/classMembers.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let (result, cm) = analyze_code(&sample_code, cm, &UserSettings::default()).unwrap();
+
+    let mut members: Vec<(String, MemberKind, bool, bool)> = result
+      .functions_with_throws
+      .iter()
+      .filter_map(|throw_map| {
+        let class_member = throw_map.class_member?;
+        Some((
+          throw_map.symbol.to_string(),
+          class_member.kind,
+          class_member.is_static,
+          class_member.is_private,
+        ))
+      })
+      .collect();
+    members.sort();
+    assert_eq!(
+      members,
+      vec![
+        (
+          "Parser.#instances".to_string(),
+          MemberKind::Property,
+          true,
+          true
+        ),
+        (
+          "Parser.#validate".to_string(),
+          MemberKind::Method,
+          false,
+          true
+        ),
+        (
+          "Parser.<static>".to_string(),
+          MemberKind::StaticBlock,
+          true,
+          false
+        ),
+        (
+          "Parser.[Symbol.iterator]".to_string(),
+          MemberKind::Method,
+          false,
+          false
+        ),
+        (
+          "Parser.content-type".to_string(),
+          MemberKind::Method,
+          false,
+          false
+        ),
+        ("Parser.create".to_string(), MemberKind::Method, true, false),
+        (
+          "Parser.handler".to_string(),
+          MemberKind::Property,
+          false,
+          false
+        ),
+        ("Parser.value".to_string(), MemberKind::Getter, false, false),
+        ("Parser.value".to_string(), MemberKind::Setter, false, false),
+      ]
+    );
+
+    let mut calls: Vec<(String, String)> = result
+      .calls_to_throws
+      .iter()
+      .map(|call| {
+        (
+          cm.span_to_snippet(call.call_span).unwrap(),
+          call.throw_map.symbol.to_string(),
+        )
+      })
+      .collect();
+    calls.sort();
+    assert_eq!(
+      calls,
+      vec![
+        (
+          "this.#validate()".to_string(),
+          "Parser.#validate".to_string()
+        ),
+        (
+          "this[Symbol.iterator]()".to_string(),
+          "Parser.[Symbol.iterator]".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_class_expressions_and_default_exports() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = format!("{}/src/fixtures/defaultExports", manifest_dir);
+    let project = analyze_project(Path::new(&root), &UserSettings::default()).unwrap();
+
+    let ids_in = |file: &str| {
+      let mut ids: Vec<(String, String)> = project
+        .file(&Path::new(&root).join(file))
+        .unwrap()
+        .result
+        .functions_with_throws
+        .iter()
+        .map(|throw_map| (throw_map.id.clone(), throw_map.import_id()))
+        .collect();
+      ids.sort();
+      ids.dedup();
+      ids
+    };
+    assert_eq!(
+      ids_in("service.ts"),
+      vec![("default-load".to_string(), "default-load".to_string())]
+    );
+    assert_eq!(
+      ids_in("parse.ts"),
+      vec![("NOT_SET-default".to_string(), "NOT_SET-default".to_string())]
+    );
+    assert_eq!(
+      ids_in("main.ts"),
+      vec![("Cache-get".to_string(), "Cache-get".to_string())]
+    );
+
+    let main = project.file(&Path::new(&root).join("main.ts")).unwrap();
+    let calls: Vec<String> = main
+      .result
+      .calls_to_throws
+      .iter()
+      .map(|call| call.throw_map.id.clone())
+      .collect();
+    assert_eq!(calls, vec!["Cache-get"]);
+
+    let mut imported_throws: Vec<(String, String, String)> = main
+      .imported_throws
+      .iter()
+      .map(|imported_throw| {
+        (
+          imported_throw.usage.id.clone(),
+          imported_throw.usage.import_id(),
+          imported_throw.throw_map.import_id(),
+        )
+      })
+      .collect();
+    imported_throws.sort();
+    assert_eq!(
+      imported_throws,
+      vec![
+        (
+          "NOT_SET-parseInput".to_string(),
+          "NOT_SET-default".to_string(),
+          "NOT_SET-default".to_string()
+        ),
+        (
+          "Repository-load".to_string(),
+          "default-load".to_string(),
+          "default-load".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_commonjs_modules() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = format!("{}/src/fixtures/commonjs", manifest_dir);
+    let project = analyze_project(Path::new(&root), &UserSettings::default()).unwrap();
+
+    let import_ids_in = |file: &str| {
+      let mut import_ids: Vec<String> = project
+        .file(&Path::new(&root).join(file))
+        .unwrap()
+        .result
+        .functions_with_throws
+        .iter()
+        .map(|throw_map| throw_map.import_id())
+        .collect();
+      import_ids.sort();
+      import_ids.dedup();
+      import_ids
+    };
+    assert_eq!(
+      import_ids_in("dates.js"),
+      vec!["NOT_SET-formatDate", "NOT_SET-parseDate"]
+    );
+    assert_eq!(
+      import_ids_in("config.cjs"),
+      vec!["NOT_SET-load", "NOT_SET-validate"]
+    );
+    assert_eq!(import_ids_in("assert.js"), vec!["NOT_SET-default"]);
+    // arrow functions assigned to `exports.x`, `module.exports.x` and `module.exports`
+    assert_eq!(
+      import_ids_in("ids.js"),
+      vec!["NOT_SET-parseId", "NOT_SET-parseName"]
+    );
+    assert_eq!(import_ids_in("check.cjs"), vec!["NOT_SET-default"]);
+
+    let main = project.file(&Path::new(&root).join("main.cjs")).unwrap();
+    let mut import_sources: Vec<&str> = main
+      .result
+      .import_sources
+      .iter()
+      .map(|source| source.as_str())
+      .collect();
+    import_sources.sort();
+    assert_eq!(
+      import_sources,
+      vec![
+        "./assert",
+        "./check.cjs",
+        "./config.cjs",
+        "./dates",
+        "./ids",
+        "node:fs"
+      ]
+    );
+    let mut imported_identifiers = main.result.imported_identifiers.clone();
+    imported_identifiers.sort();
+    assert_eq!(
+      imported_identifiers,
+      vec![
+        "assert",
+        "check",
+        "dates",
+        "formatDate",
+        "formatId",
+        "fs",
+        "load",
+        "parseId",
+        "parseName",
+        "validate"
+      ]
+    );
+
+    let mut imported_throws: Vec<(String, String)> = main
+      .imported_throws
+      .iter()
+      .map(|imported_throw| {
+        (
+          imported_throw.usage.identifier_name.clone(),
+          imported_throw.throw_map.import_id(),
+        )
+      })
+      .collect();
+    imported_throws.sort();
+    assert_eq!(
+      imported_throws,
+      vec![
+        ("assert".to_string(), "NOT_SET-default".to_string()),
+        ("check".to_string(), "NOT_SET-default".to_string()),
+        ("dates".to_string(), "NOT_SET-parseDate".to_string()),
+        ("formatDate".to_string(), "NOT_SET-formatDate".to_string()),
+        ("load".to_string(), "NOT_SET-load".to_string()),
+        ("parseId".to_string(), "NOT_SET-parseId".to_string()),
+        ("parseName".to_string(), "NOT_SET-parseName".to_string()),
+        ("validateConfig".to_string(), "NOT_SET-validate".to_string()),
+      ]
+    );
+
+    let builtin_callees: Vec<&str> = main
+      .result
+      .calls_to_throwing_builtins
+      .iter()
+      .map(|builtin_call| builtin_call.builtin.callee.as_str())
+      .collect();
+    assert_eq!(builtin_callees, vec!["fs.readFileSync"]);
+  }
+
+  #[test]
+  fn test_re_exports_through_barrel_files() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/barrels");
+    // only the importing file is listed, the modules behind the barrels are found through it
+    let project =
+      analyze_project_files(&root, &[root.join("main.ts")], &UserSettings::default()).unwrap();
+
+    let index = project.file(&root.join("index.ts")).unwrap();
+    assert_eq!(
+      index.result.re_exports,
+      vec![
+        ReExport {
+          source: "./dates".to_string(),
+          kind: ReExportKind::All,
+        },
+        ReExport {
+          source: "./config".to_string(),
+          kind: ReExportKind::Namespace("config".to_string()),
+        },
+        ReExport {
+          source: "./dates/parse".to_string(),
+          kind: ReExportKind::Named {
+            orig: "default".to_string(),
+            exported: "parseTime".to_string(),
+          },
+        },
+        ReExport {
+          source: "./index".to_string(),
+          kind: ReExportKind::All,
+        },
+      ]
+    );
+    assert!(index.result.import_sources.contains("./dates"));
+
+    let main = project.file(&root.join("main.ts")).unwrap();
+    let imported_throws: Vec<(String, String, String)> = main
+      .imported_throws
+      .iter()
+      .map(|imported_throw| {
+        (
+          imported_throw.usage.id.clone(),
+          imported_throw
+            .source_file
+            .strip_prefix(&root)
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+          imported_throw.throw_map.function_or_method_name.clone(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      imported_throws,
+      vec![
+        (
+          "NOT_SET-parseDate".to_string(),
+          "dates/parse.ts".to_string(),
+          "parseDate".to_string()
+        ),
+        (
+          "NOT_SET-format".to_string(),
+          "dates/format.ts".to_string(),
+          "formatDate".to_string()
+        ),
+        (
+          "NOT_SET-parseTime".to_string(),
+          "dates/parse.ts".to_string(),
+          "parseTime".to_string()
+        ),
+        (
+          "config-loadConfig".to_string(),
+          "config.ts".to_string(),
+          "loadConfig".to_string()
+        ),
+        (
+          "lib-parseDate".to_string(),
+          "dates/parse.ts".to_string(),
+          "parseDate".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_aliased_imports_link_to_the_exported_function() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/aliasedImports");
+    let project = analyze_project(&root, &UserSettings::default()).unwrap();
+
+    // calls are matched by the local binding and linked by the exported name, so the local
+    // `parse`, an alias of the non-throwing `format`, is not taken for the exported `parse`
+    let main = project.file(&root.join("main.ts")).unwrap();
+    let mut imported_throws: Vec<(String, String, String)> = main
+      .imported_throws
+      .iter()
+      .map(|imported_throw| {
+        (
+          main.snippet(imported_throw.usage.usage_span).to_string(),
+          imported_throw.usage.import_id(),
+          imported_throw.throw_map.id.clone(),
+        )
+      })
+      .collect();
+    imported_throws.sort();
+    assert_eq!(
+      imported_throws,
+      vec![
+        (
+          "loadAll()".to_string(),
+          "NOT_SET-default".to_string(),
+          "NOT_SET-load".to_string()
+        ),
+        (
+          "parseInput(input)".to_string(),
+          "NOT_SET-parse".to_string(),
+          "NOT_SET-parse".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_bindings_exported_under_several_names() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/exportAliases");
+    let project = analyze_project(&root, &UserSettings::default()).unwrap();
+
+    // `export { parse, parse as parseInput }` and `module.exports = { load, loadConfig: load }`
+    // export the same function under both names
+    let main = project.file(&root.join("main.ts")).unwrap();
+    let mut imported_throws: Vec<(&str, String)> = main
+      .imported_throws
+      .iter()
+      .map(|imported_throw| {
+        (
+          main.snippet(imported_throw.usage.usage_span),
+          imported_throw.throw_map.id.clone(),
+        )
+      })
+      .collect();
+    imported_throws.sort();
+    assert_eq!(
+      imported_throws,
+      vec![
+        ("load(input)", "NOT_SET-load".to_string()),
+        ("loadConfig(input)", "NOT_SET-load".to_string()),
+        ("parse(input)", "NOT_SET-parse".to_string()),
+        ("parseInput(input)", "NOT_SET-parse".to_string()),
+      ]
+    );
+  }
+  #[test]
+  fn test_namespace_and_default_import_member_calls() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/namespaceImports");
+    let project = analyze_project(&root, &UserSettings::default()).unwrap();
+
+    let main = project.file(&root.join("main.ts")).unwrap();
+    let imported_throws: Vec<(String, String, String)> = main
+      .imported_throws
+      .iter()
+      .map(|imported_throw| {
+        (
+          imported_throw.usage.id.clone(),
+          imported_throw.usage.import_id(),
+          imported_throw.throw_map.id.clone(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      imported_throws,
+      vec![
+        (
+          "api-fetchUser".to_string(),
+          "NOT_SET-fetchUser".to_string(),
+          "NOT_SET-fetchUser".to_string()
+        ),
+        (
+          "api.users-remove".to_string(),
+          "users-remove".to_string(),
+          "users-remove".to_string()
+        ),
+        (
+          "client-send".to_string(),
+          "default-send".to_string(),
+          "default-send".to_string()
+        ),
+        (
+          "logger-log".to_string(),
+          "default-log".to_string(),
+          "logger-log".to_string()
+        ),
+        (
+          "NOT_SET-retry".to_string(),
+          "NOT_SET-default".to_string(),
+          "NOT_SET-default".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_throw_info_at() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/namespaceImports");
+    let project = analyze_project(&root, &UserSettings::default()).unwrap();
+    let main = project.file(&root.join("main.ts")).unwrap();
+    let api = project.file(&root.join("api.ts")).unwrap();
+
+    // `api.users.remove(id)`
+    let pos = main
+      .byte_pos(Location {
+        line: 8,
+        column: 12,
+      })
+      .unwrap();
+    let throw_info = file_throw_info_at(main, pos).unwrap();
+    assert_eq!(main.snippet(throw_info.call_span), "api.users.remove(id)");
+    assert_eq!(throw_info.callee, "users.remove");
+    assert_eq!(throw_info.error_classes, vec!["Error"]);
+    assert_eq!(throw_info.propagation_path, vec!["users-remove"]);
+    assert_eq!(throw_info.throw_sites.len(), 1);
+    let throw_site = &throw_info.throw_sites[0];
+    assert_eq!(throw_site.file.as_deref(), Some(api.path.as_path()));
+    assert_eq!(api.location(throw_site.span.lo).line, 7);
+
+    // `client.close()` does not throw
+    let pos = main
+      .byte_pos(Location {
+        line: 10,
+        column: 4,
+      })
+      .unwrap();
+    assert!(file_throw_info_at(main, pos).is_none());
+    // nor does anything outside of a call
+    let pos = main.byte_pos(Location { line: 6, column: 0 }).unwrap();
+    assert!(throw_info_at(&main.result, pos).is_none());
+  }
+
+  #[test]
+  fn test_code_actions_at() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/codeActions.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let (result, cm) = analyze_code(&sample_code, cm, &UserSettings::default()).unwrap();
+    let start_pos = cm.files()[0].start_pos;
+
+    let code_actions_at = |line: usize, column: usize| {
+      let pos = byte_pos_at(&sample_code, start_pos, line, column).unwrap();
+      code_actions_at(&result, &sample_code, start_pos, pos, "@it-throws")
+    };
+    let apply = |code_action: &CodeAction| {
+      let mut code = sample_code.clone();
+      for edit in code_action.edits.iter().rev() {
+        let lo = (edit.span.lo - start_pos).0 as usize;
+        let hi = (edit.span.hi - start_pos).0 as usize;
+        code.replace_range(lo..hi, &edit.new_text);
+      }
+      code
+    };
+
+    // `const id = parseId(input)`, whose binding is moved out of the try block since it is used
+    // after it
+    let code_actions = code_actions_at(22, 15);
+    assert_eq!(code_actions.len(), 1);
+    assert_eq!(code_actions[0].kind, CodeActionKind::WrapInTryCatch);
+    assert_eq!(
+      code_actions[0].title,
+      "Wrap the call to `parseId` in try/catch"
+    );
+    assert!(apply(&code_actions[0]).contains(
+      "    let id\n    try {\n        id = parseId(input)\n    } catch (error) {\n    }\n    saveUser(id)"
+    ));
+    assert!(apply(&code_actions_at(35, 23)[0]).contains(
+      "    let id: number;\n    try {\n        id = parseId(input);\n    } catch (error) {\n    }\n"
+    ));
+
+    // a declaration used nowhere else is wrapped as a whole
+    assert!(apply(&code_actions_at(36, 27)[0]).contains(
+      "    try {\n        const label = `user ${loadUser(id)}`;\n    } catch (error) {\n    }\n    return id;"
+    ));
+
+    // wrapping the head of an `if` would leave its body out of the try block
+    let pos = byte_pos_at(&sample_code, start_pos, 28, 8).unwrap();
+    assert!(throw_info_at(&result, pos).is_some());
+    assert!(code_actions_at(28, 8).is_empty());
+
+    // `saveUser(id)` is a floating promise
+    let code_actions = code_actions_at(23, 4);
+    let kinds: Vec<CodeActionKind> = code_actions.iter().map(|action| action.kind).collect();
+    assert_eq!(
+      kinds,
+      vec![CodeActionKind::WrapInTryCatch, CodeActionKind::AddCatch]
+    );
+    assert!(apply(&code_actions[1]).contains("    saveUser(id).catch((error) => {})\n"));
+
+    // a throw sharing its line with an `if` gets a block comment, and the function's doc comment
+    // a tag for what it throws
+    let code_actions = code_actions_at(7, 20);
+    let kinds: Vec<CodeActionKind> = code_actions.iter().map(|action| action.kind).collect();
+    assert_eq!(
+      kinds,
+      vec![
+        CodeActionKind::AddIgnoreComment,
+        CodeActionKind::AddThrowsTag
+      ]
+    );
+    assert!(apply(&code_actions[0])
+      .contains("    if (!input) /* @it-throws */ throw new ValidationError('missing id')"));
+    assert_eq!(
+      code_actions[1].title,
+      "Document the throw with `@throws {ValidationError}`"
+    );
+    assert!(apply(&code_actions[1])
+      .contains("/**\n * Parses a user id\n * @throws {ValidationError}\n */\nfunction parseId"));
+
+    // a throw on its own line gets a comment line, and an undocumented function a doc comment
+    let code_actions = code_actions_at(12, 4);
+    assert!(
+      apply(&code_actions[0]).contains("    // @it-throws\n    throw new Error(`no user ${id}`)")
+    );
+    assert!(apply(&code_actions[1])
+      .contains("}\n\n/**\n * @throws {Error}\n */\nfunction loadUser(id: number) {"));
+
+    // nothing to do outside of throwing calls and throw statements
+    assert!(code_actions_at(9, 0).is_empty());
+  }
+
+  #[test]
+  fn test_navigation_queries() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/navigation");
+    let project = analyze_project(&root, &UserSettings::default()).unwrap();
+    let main = project.file(&root.join("main.ts")).unwrap();
+    let users = project.file(&root.join("users.ts")).unwrap();
+
+    // from `findUser(id)` in `main.ts` to the throw statement in `users.ts`
+    let pos = main.byte_pos(Location { line: 5, column: 2 }).unwrap();
+    let throw_sites = file_throw_sites_at(main, pos);
+    assert_eq!(throw_sites.len(), 1);
+    assert_eq!(throw_sites[0].file.as_deref(), Some(users.path.as_path()));
+    assert_eq!(
+      users.snippet(throw_sites[0].span),
+      "throw new Error('missing id')"
+    );
+    assert_eq!(
+      exported_throw_sites(&users.result, &["findUser".to_string()]),
+      vec![ThrowSite {
+        file: None,
+        ..throw_sites[0].clone()
+      }]
+    );
+
+    // from `findUser` to its calls, in `users.ts` and through both imports of `main.ts`
+    let pos = users
+      .byte_pos(Location {
+        line: 1,
+        column: 16,
+      })
+      .unwrap();
+    assert_eq!(
+      throwing_function_at(&users.result, pos)
+        .unwrap()
+        .function_or_method_name,
+      "findUser"
+    );
+    let call_sites = project_calls_to_function_at(&project, &users.path, pos);
+    let call_sites: Vec<(Option<&Path>, usize, &str)> = call_sites
+      .iter()
+      .map(|call_site| match &call_site.file {
+        None => (
+          None,
+          users.location(call_site.span.lo).line,
+          users.snippet(call_site.span),
+        ),
+        Some(path) => (
+          Some(path.as_path()),
+          main.location(call_site.span.lo).line,
+          main.snippet(call_site.span),
+        ),
+      })
+      .collect();
+    assert_eq!(
+      call_sites,
+      vec![
+        (None, 9, "findUser(id)"),
+        (Some(main.path.as_path()), 5, "findUser(id)"),
+        (Some(main.path.as_path()), 10, "users.findUser(id)"),
+      ]
+    );
+    assert_eq!(calls_to_function_at(&users.result, pos).len(), 1);
+
+    // `requireUser` does not throw itself, so there is nothing to navigate from
+    let pos = users.byte_pos(Location { line: 9, column: 2 }).unwrap();
+    assert!(project_calls_to_function_at(&project, &users.path, pos).is_empty());
+  }
+
+  #[test]
+  fn test_analysis_cache() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/navigation");
+    let main_path = root.join("main.ts");
+    let users_path = root.join("users.ts");
+    let users_source = fs::read_to_string(&users_path).unwrap();
+    let user_settings = UserSettings::default();
+
+    let entry_files = vec![main_path.clone()];
+    let mut cache = AnalysisCache::new();
+    let project =
+      analyze_project_files_cached(&root, &entry_files, &user_settings, &mut cache, 1).unwrap();
+    assert_eq!(project.files.len(), 2);
+    assert_eq!(
+      cache.dependents(&users_path),
+      BTreeSet::from([main_path.clone()])
+    );
+    let summary = cache.summary(&users_path).unwrap();
+    assert_eq!(summary.content_hash, content_hash(&users_source));
+    assert_eq!(
+      summary.throwing_functions,
+      vec![ThrowingFunction {
+        id: "NOT_SET-findUser".to_string(),
+        export_paths: vec![vec!["findUser".to_string()]],
+        error_classes: vec!["Error".to_string()],
+      }]
+    );
+    assert_eq!(
+      cache.summary(&main_path).unwrap().imports,
+      BTreeSet::from(["./users".to_string()])
+    );
+
+    // unchanged content is not analyzed again
+    let update = cache.update(&users_path, users_source.clone(), &user_settings);
+    assert!(!update.analyzed);
+    // a change that keeps the exports does not invalidate `main.ts`
+    let commented = format!("// users\n{}", users_source);
+    let update = cache.update(&users_path, commented, &user_settings);
+    assert!(update.analyzed);
+    assert!(update.invalidated.is_empty());
+    // `findUser` no longer throwing does
+    let not_throwing = users_source.replace("throw new Error('missing id')", "return null");
+    let update = cache.update(&users_path, not_throwing, &user_settings);
+    assert_eq!(update.invalidated, BTreeSet::from([main_path.clone()]));
+    assert!(cache
+      .summary(&users_path)
+      .unwrap()
+      .throwing_functions
+      .is_empty());
+
+    // other settings start over
+    let update = cache.update(
+      &users_path,
+      users_source.clone(),
+      &UserSettings {
+        max_propagation_depth: 1,
+        ..Default::default()
+      },
+    );
+    assert!(update.analyzed);
+    assert!(cache.summary(&main_path).is_none());
+
+    // summaries and analysis results are saved
+    let mut cache = AnalysisCache::new();
+    let project =
+      analyze_project_files_cached(&root, &entry_files, &user_settings, &mut cache, 1).unwrap();
+    let cache_path = env::temp_dir()
+      .join(format!("does-it-throw-{}", std::process::id()))
+      .join("cache.json");
+    cache.save(&cache_path).unwrap();
+    let mut loaded = AnalysisCache::load(&cache_path).unwrap();
+    fs::remove_dir_all(cache_path.parent().unwrap()).unwrap();
+    assert_eq!(loaded.summary(&users_path), cache.summary(&users_path));
+    assert_eq!(
+      loaded.dependents(&users_path),
+      BTreeSet::from([main_path.clone()])
+    );
+    // the next session starts warm: unchanged files get their saved result back without being
+    // analyzed again
+    let main_source = fs::read_to_string(&main_path).unwrap();
+    let updates = loaded.analyze_all(
+      vec![
+        (main_path.clone(), main_source),
+        (users_path.clone(), users_source.clone()),
+      ],
+      &user_settings,
+      1,
+    );
+    assert!(updates.iter().all(|update| !update.analyzed));
+    let restored = loaded.analysis(&users_path).unwrap();
+    assert_eq!(restored.source, users_source);
+    assert_eq!(
+      FileSummary::new(content_hash(&restored.source), &restored.result),
+      *cache.summary(&users_path).unwrap()
+    );
+    // and the project links imports from the saved results like from fresh ones
+    let warm_project =
+      analyze_project_files_cached(&root, &entry_files, &user_settings, &mut loaded, 1).unwrap();
+    let imported_throws = |project: &ProjectAnalysis| -> Vec<Span> {
+      project.files[&main_path]
+        .imported_throws
+        .iter()
+        .map(|imported_throw| imported_throw.usage.usage_span)
+        .collect()
+    };
+    assert!(!imported_throws(&warm_project).is_empty());
+    assert_eq!(imported_throws(&warm_project), imported_throws(&project));
+    // a change to the exports invalidates the dependents of the previous session
+    let mut loaded = AnalysisCache::from_json(&cache.to_json());
+    let not_throwing = users_source.replace("throw new Error('missing id')", "return null");
+    let update = loaded.update(&users_path, not_throwing, &user_settings);
+    assert_eq!(update.invalidated, BTreeSet::from([main_path.clone()]));
+    // a missing cache is a cold start
+    assert!(AnalysisCache::load(&cache_path)
+      .unwrap()
+      .summary(&users_path)
+      .is_none());
+  }
+
+  #[test]
+  fn test_parallel_analysis() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let fixtures = Path::new(&manifest_dir).join("src/fixtures");
+    let user_settings = UserSettings::default();
+    let summaries = |analyses: &[FileAnalysis]| -> Vec<(PathBuf, FileSummary, usize)> {
+      analyses
+        .iter()
+        .map(|analysis| {
+          (
+            analysis.path.clone(),
+            FileSummary::new(content_hash(&analysis.source), &analysis.result),
+            analysis.result.calls_to_throws.len(),
+          )
+        })
+        .collect()
+    };
+
+    // each worker parses with its own source map, the results come back in input order
+    let sources: Vec<(PathBuf, String)> = find_source_files(&fixtures)
+      .unwrap()
+      .into_iter()
+      .map(|path| {
+        let source = fs::read_to_string(&path).unwrap();
+        (path, source)
+      })
+      .collect();
+    let sequential = analyze_sources(sources.clone(), &user_settings, 1);
+    let parallel = analyze_sources(sources.clone(), &user_settings, 4);
+    assert_eq!(parallel.len(), sources.len());
+    assert_eq!(summaries(&parallel), summaries(&sequential));
+
+    // imports are followed through the barrel files the same way on any number of threads
+    let root = fixtures.join("barrels");
+    let entry_files = vec![root.join("main.ts")];
+    let imported_throws = |threads: usize| -> Vec<(PathBuf, Vec<(Span, PathBuf)>)> {
+      let project = analyze_project_files_cached(
+        &root,
+        &entry_files,
+        &user_settings,
+        &mut AnalysisCache::new(),
+        threads,
+      )
+      .unwrap();
+      project
+        .files
+        .values()
+        .map(|analysis| {
+          (
+            analysis.path.clone(),
+            analysis
+              .imported_throws
+              .iter()
+              .map(|imported_throw| {
+                (
+                  imported_throw.usage.usage_span,
+                  imported_throw.source_file.clone(),
+                )
+              })
+              .collect(),
+          )
+        })
+        .collect()
+    };
+    let sequential = imported_throws(1);
+    assert_eq!(sequential.len(), 6);
+    assert!(sequential
+      .iter()
+      .any(|(_, imported_throws)| !imported_throws.is_empty()));
+    assert_eq!(imported_throws(8), sequential);
+  }
+
+  #[test]
+  fn test_thrown_values() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/thrownValues.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings {
+      max_propagation_depth: 1,
+      ..Default::default()
+    };
+    let (result, cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    let function = |name: &str| {
+      result
+        .functions_with_throws
+        .iter()
+        .find(|f| f.function_or_method_name == name)
+        .unwrap()
+    };
+
+    let validate = function("validate");
+    let thrown: Vec<(ThrownValueKind, Option<String>, Option<String>)> = validate
+      .thrown_values
+      .iter()
+      .map(|value| {
+        (
+          value.kind.clone(),
+          value.error_class.clone(),
+          value.message.clone(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      thrown,
+      vec![
+        (
+          ThrownValueKind::Constructor,
+          Some("ValidationError".to_string()),
+          Some("input is required".to_string())
+        ),
+        (
+          ThrownValueKind::Constructor,
+          Some("errors.NotFoundError".to_string()),
+          Some("not found".to_string())
+        ),
+        (
+          ThrownValueKind::Call,
+          Some("TypeError".to_string()),
+          Some("not a number".to_string())
+        ),
+      ]
+    );
+    assert_eq!(
+      cm.span_to_snippet(validate.thrown_values[0].expression_span)
+        .unwrap(),
+      "new ValidationError('input is required')"
+    );
+    assert_eq!(
+      validate.error_classes(),
+      vec!["ValidationError", "errors.NotFoundError", "TypeError"]
+    );
+
+    let rethrow = &function("rethrow").thrown_values[0];
+    assert_eq!(
+      rethrow.kind,
+      ThrownValueKind::Identifier("error".to_string())
+    );
+    assert_eq!(rethrow.error_class, None);
+
+    let literal = &function("literal").thrown_values[0];
+    assert_eq!(literal.kind, ThrownValueKind::Literal);
+    assert_eq!(literal.message, Some("something went wrong".to_string()));
+
+    let factory = &function("factory").thrown_values[0];
+    assert_eq!(factory.kind, ThrownValueKind::Call);
+    assert_eq!(factory.error_class, None);
+
+    // values thrown by a called function are carried to its callers
+    let call_to_validate = result
+      .calls_to_throws
+      .iter()
+      .find(|c| c.id == "NOT_SET-callsValidate")
+      .unwrap();
+    assert_eq!(call_to_validate.error_classes(), validate.error_classes());
+    assert_eq!(
+      function("callsValidate").error_classes(),
+      validate.error_classes()
+    );
+  }
+
+  #[test]
+  fn test_catch_clauses_narrow_by_error_type() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/catchNarrowing.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings::default();
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    let function = |name: &str| {
+      result
+        .functions_with_throws
+        .iter()
+        .find(|f| f.function_or_method_name == name)
+    };
+
+    let mut function_names: Vec<String> = result
+      .functions_with_throws
+      .iter()
+      .map(|f| f.function_or_method_name.clone())
+      .collect();
+    function_names.sort();
+    assert_eq!(
+      function_names,
+      vec![
+        "find",
+        "handlesNotFound",
+        "rethrowsNonErrors",
+        "rethrowsOnlyValidation",
+        "rethrowsOthers",
+        "wraps"
+      ]
+    );
+    assert!(function("swallows").is_none());
+    // rethrows that every caught error is guarded out of are dropped
+    assert!(function("handlesSubclasses").is_none());
+    assert!(function("handlesEveryError").is_none());
+    assert!(function("nested").is_none());
+
+    // `Error` covers every error class, but not classes extending nothing
+    assert_eq!(
+      function("rethrowsNonErrors").unwrap().error_classes(),
+      vec!["Plain"]
+    );
+
+    // `if (e instanceof NotFoundError) return` handles one of the two errors
+    let handles_not_found = function("handlesNotFound").unwrap();
+    assert_eq!(handles_not_found.throw_spans.len(), 1);
+    assert_eq!(handles_not_found.error_classes(), vec!["ValidationError"]);
+    assert_eq!(
+      handles_not_found.thrown_values[0].kind,
+      ThrownValueKind::Rethrow("e".to_string())
+    );
+
+    // the caught errors include those thrown by calls in the try block
+    assert_eq!(
+      function("rethrowsOnlyValidation").unwrap().error_classes(),
+      vec!["ValidationError"]
+    );
+    assert_eq!(
+      function("rethrowsOthers").unwrap().error_classes(),
+      vec!["NotFoundError", "ValidationError"]
+    );
+
+    let wraps = function("wraps").unwrap();
+    assert_eq!(wraps.error_classes(), vec!["WrappedError"]);
+    let causes: Vec<Option<String>> = wraps.thrown_values[0]
+      .causes
+      .iter()
+      .map(|cause| cause.error_class.clone())
+      .collect();
+    assert_eq!(causes, vec![Some("NotFoundError".to_string())]);
+  }
+
+  #[test]
+  fn test_try_catch_finally_regions() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/tryCatchFinally.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings::default();
+    let (result, _cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    let mut function_names: Vec<String> = result
+      .functions_with_throws
+      .iter()
+      .map(|f| f.function_or_method_name.clone())
+      .collect();
+    function_names.sort();
+    assert_eq!(
+      function_names,
+      vec![
+        "rethrows",
+        "throwsInCatch",
+        "throwsInFinally",
+        "tryWithoutCatch"
+      ]
+    );
+
+    let function = |name: &str| {
+      result
+        .functions_with_throws
+        .iter()
+        .find(|f| f.function_or_method_name == name)
+        .unwrap()
+    };
+    let regions = |name: &str| -> Vec<ThrowRegion> {
+      function(name)
+        .thrown_values
+        .iter()
+        .map(|value| value.region)
+        .collect()
+    };
+    assert_eq!(regions("throwsInCatch"), vec![ThrowRegion::Catch]);
+    assert_eq!(regions("throwsInFinally"), vec![ThrowRegion::Finally]);
+    assert_eq!(regions("tryWithoutCatch"), vec![ThrowRegion::Try]);
+
+    let rethrows = function("rethrows");
+    assert_eq!(rethrows.throw_spans.len(), 1);
+    assert!(rethrows.is_rethrow(rethrows.throw_spans[0]));
+    assert_eq!(rethrows.error_classes(), vec!["TypeError"]);
+    assert!(function("throwsInCatch").rethrow_spans().is_empty());
+  }
+
+  #[test]
+  fn test_rejections_and_floating_promises() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/rejections.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings::default();
+    let (result, cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    let rejections: Vec<(String, RejectionKind, Option<String>)> = result
+      .rejections
+      .iter()
+      .map(|rejection| {
+        (
+          rejection.id.clone(),
+          rejection.kind.clone(),
+          rejection
+            .rejected_value
+            .as_ref()
+            .and_then(|value| value.error_class.clone()),
+        )
+      })
+      .collect();
+    assert_eq!(
+      rejections,
+      vec![
+        (
+          "NOT_SET-asyncThrow".to_string(),
+          RejectionKind::AsyncThrow,
+          Some("Error".to_string())
+        ),
+        (
+          "NOT_SET-promiseReject".to_string(),
+          RejectionKind::PromiseReject,
+          Some("TypeError".to_string())
+        ),
+        (
+          "NOT_SET-executor".to_string(),
+          RejectionKind::ExecutorReject,
+          Some("RangeError".to_string())
+        ),
+        (
+          "NOT_SET-executorThrow".to_string(),
+          RejectionKind::ExecutorReject,
+          Some("Error".to_string())
+        ),
+        (
+          "Service-load".to_string(),
+          RejectionKind::AsyncThrow,
+          Some("Error".to_string())
+        ),
+      ]
+    );
+
+    let floating_promises: Vec<(String, String)> = result
+      .floating_promises
+      .iter()
+      .map(|floating_promise| {
+        (
+          floating_promise.callee_id.clone(),
+          cm.span_to_snippet(floating_promise.span).unwrap(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      floating_promises,
+      vec![
+        ("NOT_SET-asyncThrow".to_string(), "asyncThrow()".to_string()),
+        (
+          "NOT_SET-asyncThrow".to_string(),
+          "asyncThrow().then(console.log)".to_string()
+        ),
+        (
+          "NOT_SET-promiseReject".to_string(),
+          "promiseReject()".to_string()
+        ),
+        ("Service-load".to_string(), "this.load()".to_string()),
+        ("Service-load".to_string(), "svc.load()".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_throws_tags() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/jsdoc.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings::default();
+    let (result, cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    let undocumented_throws: Vec<(String, Vec<String>, String)> = result
+      .undocumented_throws
+      .iter()
+      .map(|throw| {
+        (
+          throw.id.clone(),
+          throw.error_classes.clone(),
+          cm.span_to_snippet(throw.throw_span).unwrap(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      undocumented_throws,
+      vec![
+        (
+          "NOT_SET-documented".to_string(),
+          vec!["TypeError".to_string()],
+          "throw new TypeError('not a config')".to_string()
+        ),
+        (
+          "NOT_SET-arrow".to_string(),
+          vec!["Error".to_string()],
+          "throw new Error('arrow')".to_string()
+        ),
+      ]
+    );
+
+    // the tag of `parseJson` documents what `JSON.parse` throws
+    let stale_throws_tags: Vec<(String, Vec<String>, String)> = result
+      .stale_throws_tags
+      .iter()
+      .map(|stale_tag| {
+        (
+          stale_tag.id.clone(),
+          stale_tag.throws_tag.error_classes.clone(),
+          cm.span_to_snippet(stale_tag.throws_tag.span).unwrap(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      stale_throws_tags,
+      vec![
+        (
+          "NOT_SET-documented".to_string(),
+          vec!["SyntaxError".to_string()],
+          "@throws {SyntaxError} when the file is not valid JSON".to_string()
+        ),
+        (
+          "NOT_SET-noLongerThrows".to_string(),
+          vec!["ValidationError".to_string()],
+          "@throws {ValidationError} never anymore".to_string()
+        ),
+        (
+          "Service-save".to_string(),
+          vec!["Error".to_string()],
+          "@throws {Error}".to_string()
+        ),
+      ]
+    );
+
+    let documented = result
+      .functions_with_throws
+      .iter()
+      .find(|throw_map| throw_map.id == "NOT_SET-documented")
+      .unwrap();
+    let throws_tags = documented.throws_tags.as_ref().unwrap();
+    assert_eq!(throws_tags.len(), 2);
+    assert_eq!(
+      throws_tags[1].description,
+      Some("when the port is out of range".to_string())
+    );
+    let not_documented = result
+      .functions_with_throws
+      .iter()
+      .find(|throw_map| throw_map.id == "NOT_SET-notDocumented")
+      .unwrap();
+    assert_eq!(not_documented.throws_tags, None);
+  }
+
+  #[test]
+  fn test_throws_tags_of_imported_throws() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("src/fixtures/jsdocImports");
+    let project = analyze_project(&root, &UserSettings::default()).unwrap();
+
+    // the tag of `loadUser` documents what the imported `findUser` throws
+    let main = project.file(&root.join("main.ts")).unwrap();
+    let stale_throws_tags: Vec<(String, &str)> = main
+      .result
+      .stale_throws_tags
+      .iter()
+      .map(|stale_tag| {
+        (
+          stale_tag.id.clone(),
+          main.snippet(stale_tag.throws_tag.span),
+        )
+      })
+      .collect();
+    assert_eq!(
+      stale_throws_tags,
+      vec![(
+        "NOT_SET-loadProfile".to_string(),
+        "@throws {TypeError} never"
+      )]
+    );
+  }
+  #[test]
+  fn test_calls_to_throwing_builtins() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file_path = format!("{}/src/fixtures/builtins.ts", manifest_dir);
+    let sample_code = fs::read_to_string(file_path).expect("Something went wrong reading the file");
+    let cm: Lrc<SourceMap> = Default::default();
+    let user_settings = UserSettings {
+      throwing_builtins: vec![ThrowingBuiltin::new(
+        "js-yaml.load",
+        vec!["YAMLException".to_string()],
+      )],
+      ..Default::default()
+    };
+    let (result, cm) = analyze_code(&sample_code, cm, &user_settings).unwrap();
+
+    // a local `JSON` and a parameter named `fs` are not the builtins
+    let builtin_calls: Vec<(String, String, String)> = result
+      .calls_to_throwing_builtins
+      .iter()
+      .map(|builtin_call| {
+        (
+          builtin_call.id.clone(),
+          builtin_call.builtin.callee.clone(),
+          cm.span_to_snippet(builtin_call.call_span).unwrap(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      builtin_calls,
+      vec![
+        (
+          "NOT_SET-readConfig".to_string(),
+          "JSON.parse".to_string(),
+          "JSON.parse(fs.readFileSync(path, 'utf8'))".to_string()
+        ),
+        (
+          "NOT_SET-readConfig".to_string(),
+          "fs.readFileSync".to_string(),
+          "fs.readFileSync(path, 'utf8')".to_string()
+        ),
+        (
+          "NOT_SET-build".to_string(),
+          "child_process.execSync".to_string(),
+          "run('npm run build')".to_string()
+        ),
+        (
+          "NOT_SET-build".to_string(),
+          "new URL".to_string(),
+          "new URL('https://example.com')".to_string()
+        ),
+        (
+          "NOT_SET-build".to_string(),
+          "decodeURIComponent".to_string(),
+          "decodeURIComponent(url.pathname)".to_string()
+        ),
+        (
+          "Loader-id".to_string(),
+          "BigInt".to_string(),
+          "BigInt(globalThis.crypto.randomUUID().length)".to_string()
+        ),
+        (
+          "Loader-load".to_string(),
+          "js-yaml.load".to_string(),
+          "yaml.load(text)".to_string()
+        ),
+      ]
+    );
+    assert_eq!(
+      result.json_parse_calls,
+      vec!["NOT_SET-readConfig".to_string()]
+    );
+    assert_eq!(
+      result.fs_access_calls,
+      vec!["NOT_SET-readConfig".to_string()]
+    );
+
+    let user_settings = UserSettings {
+      include_try_statement_throws: true,
+      ..Default::default()
+    };
+    let (result, _cm) = analyze_code(&sample_code, Default::default(), &user_settings).unwrap();
+    assert_eq!(result.json_parse_calls.len(), 2);
+    assert_eq!(result.fs_access_calls.len(), 2);
+  }
+
+  #[test]
+  fn test_sarif_output() {
+    let cli = Cli::parse_from(["does-it-throw", "--format", "sarif", "src"]);
+    assert_eq!(cli.format, OutputFormat::Sarif);
+    assert_eq!(
+      Cli::parse_from(["does-it-throw"]).format,
+      OutputFormat::Human
+    );
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir);
+    let files: Vec<PathBuf> = ["rejections.ts", "builtins.ts", "jsdoc.ts", "inheritance.ts"]
+      .iter()
+      .map(|file| root.join("src/fixtures").join(file))
+      .collect();
+    let project = analyze_project_files(root, &files, &UserSettings::default()).unwrap();
+    let log = sarif_log(&project, &files, root);
+
+    // every kind of finding has a rule
+    let json = serde_json::to_value(&log).unwrap();
+    let rule_ids: Vec<&str> = json["runs"][0]["tool"]["driver"]["rules"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|rule| rule["id"].as_str().unwrap())
+      .collect();
+    let results = &log.runs[0].results;
+    let mut result_rule_ids: Vec<&str> = results
+      .iter()
+      .map(|result| result.rule_id.as_str())
+      .collect();
+    result_rule_ids.sort();
+    result_rule_ids.dedup();
+    assert_eq!(
+      result_rule_ids,
+      vec![
+        "does-it-throw/call-to-throw",
+        "does-it-throw/call-to-throwing-builtin",
+        "does-it-throw/floating-promise",
+        "does-it-throw/function-that-may-throw",
+        "does-it-throw/promise-rejection",
+        "does-it-throw/stale-throws-tag",
+        "does-it-throw/throw-statement",
+        "does-it-throw/throwing-override",
+        "does-it-throw/undocumented-throw",
+      ]
+    );
+    for result in results {
+      assert_eq!(rule_ids[result.rule_index], result.rule_id);
+      assert_eq!(result.level, "warning");
+    }
+
+    let builtin_call = results
+      .iter()
+      .find(|result| result.rule_id == "does-it-throw/call-to-throwing-builtin")
+      .unwrap();
+    let location = &builtin_call.locations[0].physical_location;
+    assert_eq!(location.artifact_location.uri, "src/fixtures/builtins.ts");
+    assert_eq!(
+      location.region,
+      Region {
+        start_line: 7,
+        start_column: 10,
+        end_line: 7,
+        end_column: 51,
+      }
+    );
+  }
+
+  #[test]
+  fn test_cli_parses_throwing_builtins() {
+    let cli = Cli::parse_from([
+      "does-it-throw",
+      "--throwing-builtin",
+      "yaml.parse=YAMLException, SyntaxError",
+      "--throwing-builtin",
+      "new  Temporal.PlainDate",
+    ]);
+    assert_eq!(
+      cli.user_settings().throwing_builtins,
+      vec![
+        ThrowingBuiltin::new(
+          "yaml.parse",
+          vec!["YAMLException".to_string(), "SyntaxError".to_string()]
+        ),
+        ThrowingBuiltin::new("new Temporal.PlainDate", vec![]),
+      ]
+    );
+  }
 }
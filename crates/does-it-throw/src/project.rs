@@ -0,0 +1,856 @@
+extern crate serde_json;
+extern crate swc_common;
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::iter::Peekable;
+use std::panic;
+use std::path::{Component, Path, PathBuf};
+use std::str::Chars;
+use std::sync::Mutex;
+use std::thread;
+
+use self::serde_json::Value;
+use self::swc_common::{sync::Lrc, BytePos, SourceMap, Span};
+
+use crate::cache::AnalysisCache;
+use crate::jsdoc::check_imported_throws;
+use crate::query::byte_pos_at;
+use crate::throw_finder::{IdentifierUsage, ThrowMap};
+use crate::{analyze_code_at, AnalysisResult, UserSettings};
+
+// ----- Project analysis -----
+// Analyzes every JavaScript/TypeScript file under a root directory once, resolves each
+// file's imports to files on disk and links the usages of imported identifiers to the
+// `ThrowMap`s of the file that exports them. Re-exports (`export * from './x'`,
+// `export { a as b } from './y'`, `export * as ns from './z'`) are followed, so that imports
+// from barrel files link to the file declaring the function.
+//
+// Resolution follows what `tsc` and most bundlers do for local code:
+// - relative specifiers (`./foo`, `../foo`), with or without an extension
+// - directory imports (`./lib` -> `./lib/index.ts`)
+// - `compilerOptions.paths` and `compilerOptions.baseUrl` from the root `tsconfig.json`
+//   (or `jsconfig.json`), including configs pulled in through a relative `extends`
+// Bare package specifiers (`react`, `node:fs`) are left unresolved.
+//
+// Files are analyzed on several threads. swc's `SourceMap` is not `Send`, so each file is parsed
+// with its own source map on the thread analyzing it, and only the `FileAnalysis`, which does not
+// reference it, is sent back. Results are collected in sorted maps, so that the output does not
+// depend on the thread scheduling.
+
+pub const SOURCE_EXTENSIONS: [&str; 8] = ["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+const SKIPPED_DIRECTORIES: [&str; 1] = ["node_modules"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+  // 1-based, like `SourceMap::lookup_char_pos`
+  pub line: usize,
+  // 0-based, counted in chars
+  pub column: usize,
+}
+
+#[derive(Clone)]
+pub struct FileAnalysis {
+  pub path: PathBuf,
+  pub source: String,
+  // Position of the first byte of `source` in the spans of `result`
+  pub start_pos: BytePos,
+  pub result: AnalysisResult,
+  // Import specifier -> file it resolves to. Unresolved specifiers are left out.
+  pub resolved_imports: BTreeMap<String, PathBuf>,
+  pub imported_throws: Vec<ImportedThrow>,
+}
+
+impl FileAnalysis {
+  pub fn location(&self, pos: BytePos) -> Location {
+    let offset = ((pos.0.saturating_sub(self.start_pos.0)) as usize).min(self.source.len());
+    let before = &self.source[..floor_char_boundary(&self.source, offset)];
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    Location {
+      line: before.matches('\n').count() + 1,
+      column: before[line_start..].chars().count(),
+    }
+  }
+
+  // The inverse of `location`, `None` past the last line
+  pub fn byte_pos(&self, location: Location) -> Option<BytePos> {
+    byte_pos_at(&self.source, self.start_pos, location.line, location.column)
+  }
+
+  pub fn snippet(&self, span: Span) -> &str {
+    let lo = floor_char_boundary(
+      &self.source,
+      (span.lo.0.saturating_sub(self.start_pos.0)) as usize,
+    );
+    let hi = floor_char_boundary(
+      &self.source,
+      (span.hi.0.saturating_sub(self.start_pos.0)) as usize,
+    );
+    &self.source[lo..hi.max(lo)]
+  }
+
+  pub fn line(&self, line: usize) -> Option<&str> {
+    self.source.lines().nth(line.checked_sub(1)?)
+  }
+}
+
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+  let mut offset = offset.min(source.len());
+  while !source.is_char_boundary(offset) {
+    offset -= 1;
+  }
+  offset
+}
+
+// A call in one file to a function that throws in the file it was imported from
+#[derive(Clone)]
+pub struct ImportedThrow {
+  pub usage: IdentifierUsage,
+  pub source_file: PathBuf,
+  pub throw_map: ThrowMap,
+}
+
+pub struct ProjectAnalysis {
+  pub root: PathBuf,
+  pub files: BTreeMap<PathBuf, FileAnalysis>,
+  // tsconfig.json/jsconfig.json files that exist but could not be parsed
+  pub config_errors: Vec<ConfigError>,
+}
+
+impl ProjectAnalysis {
+  pub fn file(&self, path: &Path) -> Option<&FileAnalysis> {
+    self.files.get(&normalize_path(&absolute_path(path)))
+  }
+}
+
+// What module resolution and project analysis need from the file system, so that they can run
+// where `std::fs` is not available, e.g. in the wasm module of the language server, whose files
+// are read by the editor (which also knows the unsaved content of open documents)
+pub trait FileSystem {
+  fn is_file(&self, path: &Path) -> bool;
+  fn is_dir(&self, path: &Path) -> bool;
+  fn read_to_string(&self, path: &Path) -> io::Result<String>;
+}
+
+pub struct DiskFileSystem;
+
+impl FileSystem for DiskFileSystem {
+  fn is_file(&self, path: &Path) -> bool {
+    path.is_file()
+  }
+
+  fn is_dir(&self, path: &Path) -> bool {
+    path.is_dir()
+  }
+
+  fn read_to_string(&self, path: &Path) -> io::Result<String> {
+    fs::read_to_string(path)
+  }
+}
+
+// A config file that exists but is not valid JSON (with comments and trailing commas), whose
+// aliases are then not resolved
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+  pub path: PathBuf,
+  pub location: Location,
+  pub message: String,
+}
+
+pub struct ModuleResolver<'fs> {
+  file_system: &'fs dyn FileSystem,
+  base_url: Option<PathBuf>,
+  // Directory `paths` targets are relative to: `baseUrl` if set, otherwise the tsconfig's directory
+  paths_base: PathBuf,
+  paths: Vec<(String, Vec<String>)>,
+  pub config_errors: Vec<ConfigError>,
+}
+
+impl ModuleResolver<'static> {
+  // Reads `tsconfig.json` (or `jsconfig.json`) from `root`. A missing or unreadable config
+  // only disables alias resolution, relative imports still resolve. Configs that cannot be
+  // parsed are listed in `config_errors`.
+  pub fn from_root(root: &Path) -> Self {
+    ModuleResolver::with_file_system(root, &DiskFileSystem)
+  }
+}
+
+impl<'fs> ModuleResolver<'fs> {
+  // Like `from_root`, looking files up in `file_system`
+  pub fn with_file_system(root: &Path, file_system: &'fs dyn FileSystem) -> Self {
+    let mut resolver = ModuleResolver {
+      file_system,
+      base_url: None,
+      paths_base: root.to_path_buf(),
+      paths: vec![],
+      config_errors: vec![],
+    };
+    for config_name in ["tsconfig.json", "jsconfig.json"] {
+      let config_path = root.join(config_name);
+      if file_system.is_file(&config_path) {
+        resolver.load_config(&config_path, 0);
+        break;
+      }
+    }
+    resolver
+  }
+
+  fn load_config(&mut self, config_path: &Path, depth: usize) {
+    // guard against `extends` cycles
+    if depth > 8 {
+      return;
+    }
+    let Ok(content) = self.file_system.read_to_string(config_path) else {
+      return;
+    };
+    let config = match serde_json::from_str::<Value>(&strip_json_comments(&content)) {
+      Ok(config) => config,
+      Err(error) => {
+        self.config_errors.push(ConfigError {
+          path: config_path.to_path_buf(),
+          location: Location {
+            line: error.line(),
+            column: error.column().saturating_sub(1),
+          },
+          // the location is already part of `location`
+          message: error
+            .to_string()
+            .trim_end_matches(&format!(
+              " at line {} column {}",
+              error.line(),
+              error.column()
+            ))
+            .to_string(),
+        });
+        return;
+      }
+    };
+    let config_dir = config_path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+    // the extended config is loaded first so that this config's options override it
+    if let Some(extends) = config.get("extends").and_then(Value::as_str) {
+      if extends.starts_with('.') {
+        let mut extended = normalize_path(&config_dir.join(extends));
+        if extended.extension().is_none() {
+          extended.set_extension("json");
+        }
+        self.load_config(&extended, depth + 1);
+      }
+    }
+
+    let Some(compiler_options) = config.get("compilerOptions") else {
+      return;
+    };
+    if let Some(base_url) = compiler_options.get("baseUrl").and_then(Value::as_str) {
+      let base_url = normalize_path(&config_dir.join(base_url));
+      self.paths_base = base_url.clone();
+      self.base_url = Some(base_url);
+    }
+    if let Some(paths) = compiler_options.get("paths").and_then(Value::as_object) {
+      if self.base_url.is_none() {
+        self.paths_base = config_dir.clone();
+      }
+      self.paths = paths
+        .iter()
+        .map(|(pattern, targets)| {
+          let targets = targets
+            .as_array()
+            .map(|targets| {
+              targets
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+            })
+            .unwrap_or_default();
+          (pattern.clone(), targets)
+        })
+        .collect();
+      // like tsc, an exact pattern wins over wildcards, and among wildcards the longest prefix
+      // before the `*` wins, whatever the order of `paths`
+      self.paths.sort_by_key(|(pattern, _)| {
+        pattern
+          .split_once('*')
+          .map(|(prefix, _)| Reverse(prefix.len()))
+      });
+    }
+  }
+
+  pub fn resolve(&self, specifier: &str, importing_file: &Path) -> Option<PathBuf> {
+    let prefer_typescript = is_typescript_file(importing_file);
+    if specifier.starts_with("./")
+      || specifier.starts_with("../")
+      || specifier == "."
+      || specifier == ".."
+    {
+      let importing_dir = importing_file.parent().unwrap_or(Path::new(""));
+      return self.resolve_path(&importing_dir.join(specifier), prefer_typescript);
+    }
+    if Path::new(specifier).is_absolute() {
+      return self.resolve_path(Path::new(specifier), prefer_typescript);
+    }
+
+    for (pattern, targets) in &self.paths {
+      let Some(wildcard) = match_path_pattern(pattern, specifier) else {
+        continue;
+      };
+      for target in targets {
+        let target = target.replacen('*', wildcard, 1);
+        if let Some(resolved) = self.resolve_path(&self.paths_base.join(target), prefer_typescript)
+        {
+          return Some(resolved);
+        }
+      }
+    }
+
+    self
+      .base_url
+      .as_ref()
+      .and_then(|base_url| self.resolve_path(&base_url.join(specifier), prefer_typescript))
+  }
+
+  fn resolve_path(&self, base: &Path, prefer_typescript: bool) -> Option<PathBuf> {
+    let base = normalize_path(base);
+    if self.file_system.is_file(&base) && is_source_file(&base) {
+      return Some(base);
+    }
+
+    let extensions: Vec<&str> = if prefer_typescript {
+      SOURCE_EXTENSIONS.to_vec()
+    } else {
+      let (typescript, javascript) = SOURCE_EXTENSIONS.split_at(4);
+      javascript
+        .iter()
+        .chain(typescript.iter())
+        .copied()
+        .collect()
+    };
+
+    let file_name = base.file_name()?.to_string_lossy().to_string();
+    for extension in &extensions {
+      let candidate = base.with_file_name(format!("{}.{}", file_name, extension));
+      if self.file_system.is_file(&candidate) {
+        return Some(candidate);
+      }
+    }
+
+    // TypeScript ESM code imports `./foo.js` while the file on disk is `./foo.ts`
+    if let Some(stem) = ["js", "jsx", "mjs", "cjs"]
+      .iter()
+      .find_map(|ext| file_name.strip_suffix(&format!(".{}", ext)))
+    {
+      for extension in ["ts", "tsx", "mts", "cts"] {
+        let candidate = base.with_file_name(format!("{}.{}", stem, extension));
+        if self.file_system.is_file(&candidate) {
+          return Some(candidate);
+        }
+      }
+    }
+
+    if self.file_system.is_dir(&base) {
+      for extension in &extensions {
+        let candidate = base.join(format!("index.{}", extension));
+        if self.file_system.is_file(&candidate) {
+          return Some(candidate);
+        }
+      }
+    }
+    None
+  }
+}
+
+// Returns the part of `specifier` matched by the `*` in `pattern`, or "" for exact matches
+fn match_path_pattern<'a>(pattern: &str, specifier: &'a str) -> Option<&'a str> {
+  match pattern.split_once('*') {
+    Some((prefix, suffix)) => {
+      if specifier.len() >= prefix.len() + suffix.len()
+        && specifier.starts_with(prefix)
+        && specifier.ends_with(suffix)
+      {
+        Some(&specifier[prefix.len()..specifier.len() - suffix.len()])
+      } else {
+        None
+      }
+    }
+    None => (pattern == specifier).then_some(""),
+  }
+}
+
+fn is_typescript_file(path: &Path) -> bool {
+  matches!(
+    path.extension().and_then(|ext| ext.to_str()),
+    Some("ts" | "tsx" | "mts" | "cts")
+  )
+}
+
+pub fn is_source_file(path: &Path) -> bool {
+  path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+    && !path.to_string_lossy().ends_with(".d.ts")
+}
+
+// Removes `//` and `/* */` comments and trailing commas, which `tsconfig.json` allows but JSON does not
+pub fn strip_json_comments(content: &str) -> String {
+  let mut output = String::with_capacity(content.len());
+  let mut chars = content.chars().peekable();
+  let mut in_string = false;
+  while let Some(c) = chars.next() {
+    if in_string {
+      output.push(c);
+      if c == '\\' {
+        if let Some(escaped) = chars.next() {
+          output.push(escaped);
+        }
+      } else if c == '"' {
+        in_string = false;
+      }
+      continue;
+    }
+    match (c, chars.peek()) {
+      ('"', _) => {
+        in_string = true;
+        output.push(c);
+      }
+      ('/', Some('/')) => {
+        for c in chars.by_ref() {
+          if c == '\n' {
+            output.push('\n');
+            break;
+          }
+        }
+      }
+      ('/', Some('*')) => {
+        chars.next();
+        let mut previous = ' ';
+        for c in chars.by_ref() {
+          if previous == '*' && c == '/' {
+            break;
+          }
+          previous = c;
+        }
+      }
+      (',', _) => {
+        if !matches!(next_token(chars.clone()), Some('}') | Some(']')) {
+          output.push(c);
+        }
+      }
+      _ => output.push(c),
+    }
+  }
+  output
+}
+
+// The first char of `chars` that is neither whitespace nor part of a comment
+fn next_token(mut chars: Peekable<Chars>) -> Option<char> {
+  while let Some(c) = chars.next() {
+    match (c, chars.peek()) {
+      (c, _) if c.is_whitespace() => {}
+      ('/', Some('/')) => {
+        chars.find(|c| *c == '\n');
+      }
+      ('/', Some('*')) => {
+        chars.next();
+        let mut previous = ' ';
+        for c in chars.by_ref() {
+          if previous == '*' && c == '/' {
+            break;
+          }
+          previous = c;
+        }
+      }
+      _ => return Some(c),
+    }
+  }
+  None
+}
+
+// Lexically resolves `.` and `..` without touching the file system (unlike `canonicalize`,
+// which also resolves symlinks and fails for missing files)
+pub fn normalize_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        if !normalized.pop() {
+          normalized.push("..");
+        }
+      }
+      other => normalized.push(other.as_os_str()),
+    }
+  }
+  normalized
+}
+
+fn absolute_path(path: &Path) -> PathBuf {
+  if path.is_absolute() {
+    path.to_path_buf()
+  } else {
+    std::env::current_dir()
+      .map(|cwd| cwd.join(path))
+      .unwrap_or_else(|_| path.to_path_buf())
+  }
+}
+
+// Every JavaScript/TypeScript file under `root`, skipping `node_modules` and hidden directories.
+// Sorted so results are stable across runs.
+pub fn find_source_files(root: &Path) -> io::Result<Vec<PathBuf>> {
+  let mut files = vec![];
+  let mut directories = vec![root.to_path_buf()];
+  while let Some(directory) = directories.pop() {
+    for entry in fs::read_dir(&directory)? {
+      let entry = entry?;
+      let path = entry.path();
+      let file_name = entry.file_name().to_string_lossy().to_string();
+      let file_type = entry.file_type()?;
+      if file_type.is_dir() {
+        if !file_name.starts_with('.') && !SKIPPED_DIRECTORIES.contains(&file_name.as_str()) {
+          directories.push(path);
+        }
+      } else if file_type.is_file() && is_source_file(&path) {
+        files.push(normalize_path(&path));
+      }
+    }
+  }
+  files.sort();
+  Ok(files)
+}
+
+pub fn analyze_file(path: &Path, user_settings: &UserSettings) -> io::Result<FileAnalysis> {
+  let source = fs::read_to_string(path)?;
+  Ok(analyze_source(path, source, user_settings))
+}
+
+pub fn analyze_source(path: &Path, source: String, user_settings: &UserSettings) -> FileAnalysis {
+  let cm: Lrc<SourceMap> = Default::default();
+  let (result, cm) = match analyze_code_at(path, &source, cm.clone(), user_settings) {
+    Ok(analysis) => analysis,
+    Err(error) => (
+      AnalysisResult {
+        parse_errors: error.errors,
+        ..Default::default()
+      },
+      cm,
+    ),
+  };
+  let start_pos = cm
+    .files()
+    .first()
+    .map_or(BytePos(0), |source_file| source_file.start_pos);
+  FileAnalysis {
+    path: path.to_path_buf(),
+    source,
+    start_pos,
+    result,
+    resolved_imports: BTreeMap::new(),
+    imported_throws: vec![],
+  }
+}
+
+// The number of threads files are analyzed on by default: one per core, or 1 where the number of
+// cores is unknown or threads are not available, e.g. in wasm
+pub fn default_threads() -> usize {
+  thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+// Analyzes each (path, source) of `sources` on up to `threads` threads. The analyses are returned
+// in the order of `sources`.
+pub fn analyze_sources(
+  sources: Vec<(PathBuf, String)>,
+  user_settings: &UserSettings,
+  threads: usize,
+) -> Vec<FileAnalysis> {
+  let threads = threads.clamp(1, sources.len().max(1));
+  if threads == 1 {
+    return sources
+      .into_iter()
+      .map(|(path, source)| analyze_source(&path, source, user_settings))
+      .collect();
+  }
+
+  // workers take the next file when they are done with one, so that a few large files do not
+  // keep one worker busy while the others are idle
+  let queue = Mutex::new(sources.into_iter().enumerate());
+  let mut analyses: Vec<(usize, FileAnalysis)> = thread::scope(|scope| {
+    let workers: Vec<_> = (0..threads)
+      .map(|_| {
+        scope.spawn(|| {
+          let mut analyses = vec![];
+          loop {
+            let next = queue.lock().unwrap().next();
+            let Some((index, (path, source))) = next else {
+              break;
+            };
+            analyses.push((index, analyze_source(&path, source, user_settings)));
+          }
+          analyses
+        })
+      })
+      .collect();
+    workers
+      .into_iter()
+      .flat_map(|worker| {
+        worker
+          .join()
+          .unwrap_or_else(|payload| panic::resume_unwind(payload))
+      })
+      .collect()
+  });
+  analyses.sort_by_key(|(index, _)| *index);
+  analyses.into_iter().map(|(_, analysis)| analysis).collect()
+}
+
+// Analyzes every source file under `root`
+pub fn analyze_project(root: &Path, user_settings: &UserSettings) -> io::Result<ProjectAnalysis> {
+  let root = normalize_path(&absolute_path(root));
+  let files = find_source_files(&root)?;
+  analyze_project_files(&root, &files, user_settings)
+}
+
+// Analyzes `files`, plus any file they import that is not already part of the list, so that
+// imports can be linked. Files that cannot be read are skipped.
+pub fn analyze_project_files(
+  root: &Path,
+  files: &[PathBuf],
+  user_settings: &UserSettings,
+) -> io::Result<ProjectAnalysis> {
+  analyze_project_files_cached(
+    root,
+    files,
+    user_settings,
+    &mut AnalysisCache::new(),
+    default_threads(),
+  )
+}
+
+// Like `analyze_project_files` on up to `threads` threads, only analyzing the files that changed
+// since they were added to `cache`
+pub fn analyze_project_files_cached(
+  root: &Path,
+  files: &[PathBuf],
+  user_settings: &UserSettings,
+  cache: &mut AnalysisCache,
+  threads: usize,
+) -> io::Result<ProjectAnalysis> {
+  analyze_project_files_in(root, files, user_settings, cache, threads, &DiskFileSystem)
+}
+
+// Like `analyze_project_files_cached`, reading files and resolving imports through `file_system`
+pub fn analyze_project_files_in(
+  root: &Path,
+  files: &[PathBuf],
+  user_settings: &UserSettings,
+  cache: &mut AnalysisCache,
+  threads: usize,
+  file_system: &dyn FileSystem,
+) -> io::Result<ProjectAnalysis> {
+  let root = normalize_path(&absolute_path(root));
+  let resolver = ModuleResolver::with_file_system(&root, file_system);
+  let mut analyses: BTreeMap<PathBuf, FileAnalysis> = BTreeMap::new();
+  let mut analyze_files = |files: Vec<PathBuf>| -> Vec<FileAnalysis> {
+    let sources: Vec<(PathBuf, String)> = files
+      .into_iter()
+      .filter_map(|file| {
+        let source = file_system.read_to_string(&file).ok()?;
+        Some((file, source))
+      })
+      .collect();
+    let paths: Vec<PathBuf> = sources.iter().map(|(path, _)| path.clone()).collect();
+    cache.analyze_all(sources, user_settings, threads);
+    paths
+      .iter()
+      .filter_map(|path| cache.analysis(path).cloned())
+      .collect()
+  };
+  let resolve_imports = |analysis: &mut FileAnalysis| {
+    for import_source in &analysis.result.import_sources {
+      if let Some(resolved) = resolver.resolve(import_source, &analysis.path) {
+        analysis
+          .resolved_imports
+          .insert(import_source.clone(), resolved);
+      }
+    }
+  };
+
+  let entry_files: BTreeSet<PathBuf> = files
+    .iter()
+    .map(|file| normalize_path(&absolute_path(file)))
+    .collect();
+  let mut pending_files: BTreeSet<PathBuf> = BTreeSet::new();
+  for mut analysis in analyze_files(entry_files.into_iter().collect()) {
+    resolve_imports(&mut analysis);
+    pending_files.extend(analysis.resolved_imports.values().cloned());
+    analyses.insert(analysis.path.clone(), analysis);
+  }
+
+  // imported files are analyzed in rounds, each one analyzing the files the previous one found
+  while !pending_files.is_empty() {
+    let files: Vec<PathBuf> = std::mem::take(&mut pending_files)
+      .into_iter()
+      .filter(|file| !analyses.contains_key(file))
+      .collect();
+    for mut analysis in analyze_files(files) {
+      resolve_imports(&mut analysis);
+      // the modules behind a barrel file are needed to follow imports through it
+      for re_export in &analysis.result.re_exports {
+        if let Some(resolved) = analysis.resolved_imports.get(&re_export.source) {
+          if !analyses.contains_key(resolved) {
+            pending_files.insert(resolved.clone());
+          }
+        }
+      }
+      analyses.insert(analysis.path.clone(), analysis);
+    }
+  }
+
+  for analysis in analyses.values() {
+    cache.set_dependencies(
+      &analysis.path,
+      analysis.resolved_imports.values().cloned().collect(),
+    );
+  }
+  link_imported_throws(&mut analyses);
+
+  Ok(ProjectAnalysis {
+    root,
+    files: analyses,
+    config_errors: resolver.config_errors,
+  })
+}
+
+// The modules `export_path` of the module at `path` may be declared in, with the export path
+// it has there: the module itself, and the modules it re-exports the export from, followed
+// through any number of barrel files
+fn declaring_modules(
+  analyses: &BTreeMap<PathBuf, FileAnalysis>,
+  path: &Path,
+  export_path: Vec<String>,
+) -> Vec<(PathBuf, Vec<String>)> {
+  let mut declaring_modules: Vec<(PathBuf, Vec<String>)> = vec![];
+  let mut pending = vec![(path.to_path_buf(), export_path)];
+  while let Some((path, export_path)) = pending.pop() {
+    // re-export cycles (`a` re-exports `b`, which re-exports `a`) are only followed once
+    if declaring_modules.contains(&(path.clone(), export_path.clone())) {
+      continue;
+    }
+    if let Some(analysis) = analyses.get(&path) {
+      for re_export in &analysis.result.re_exports {
+        let (Some(re_exported_path), Some(re_exported_export_path)) = (
+          analysis.resolved_imports.get(&re_export.source),
+          re_export.follow(&export_path),
+        ) else {
+          continue;
+        };
+        pending.push((re_exported_path.clone(), re_exported_export_path));
+      }
+    }
+    declaring_modules.push((path, export_path));
+  }
+  declaring_modules
+}
+
+// Matches the usages of imported identifiers in each file against the throwing functions
+// of the files its imports resolved to.
+fn link_imported_throws(analyses: &mut BTreeMap<PathBuf, FileAnalysis>) {
+  let mut links: Vec<(PathBuf, ImportedThrow)> = vec![];
+  for analysis in analyses.values() {
+    for usage in &analysis.result.imported_identifier_usages {
+      // import usages carry the module specifier as written, resolved here
+      let Some(imported_path) = usage
+        .symbol
+        .file
+        .as_ref()
+        .and_then(|source| analysis.resolved_imports.get(&*source.to_string_lossy()))
+      else {
+        continue;
+      };
+      let Some(export_path) = usage.symbol.export_path() else {
+        continue;
+      };
+      for (declaring_path, export_path) in declaring_modules(analyses, imported_path, export_path) {
+        let Some(declaring_analysis) = analyses.get(&declaring_path) else {
+          continue;
+        };
+        for throw_map in &declaring_analysis.result.functions_with_throws {
+          if throw_map.symbol.export_paths().contains(&export_path) {
+            links.push((
+              analysis.path.clone(),
+              ImportedThrow {
+                usage: usage.clone(),
+                source_file: declaring_path.clone(),
+                throw_map: throw_map.clone(),
+              },
+            ));
+          }
+        }
+      }
+    }
+  }
+  for (path, imported_throw) in links {
+    if let Some(analysis) = analyses.get_mut(&path) {
+      analysis.imported_throws.push(imported_throw);
+    }
+  }
+  for analysis in analyses.values_mut() {
+    analysis.imported_throws.sort_by_key(|imported_throw| {
+      (
+        imported_throw.usage.usage_span.lo,
+        imported_throw.usage.usage_span.hi,
+      )
+    });
+    check_imported_throws(
+      &mut analysis.result.stale_throws_tags,
+      &analysis.imported_throws,
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_strip_json_comments() {
+    let content = r#"{
+      // line comment
+      "compilerOptions": { /* block */ "baseUrl": "./src", "paths": { "@/*": ["*"], }, },
+      "url": "http://example.com/*not-a-comment*/"
+    }"#;
+    let value: Value = serde_json::from_str(&strip_json_comments(content)).unwrap();
+    assert_eq!(value["compilerOptions"]["baseUrl"], "./src");
+    assert_eq!(value["url"], "http://example.com/*not-a-comment*/");
+
+    let content = r#"{
+      "compilerOptions": {
+        "baseUrl": ".", // line comment
+        "paths": { "@/*": ["src/*"], /* block */ },
+      }
+    }"#;
+    let value: Value = serde_json::from_str(&strip_json_comments(content)).unwrap();
+    assert_eq!(value["compilerOptions"]["paths"]["@/*"][0], "src/*");
+  }
+
+  #[test]
+  fn test_match_path_pattern() {
+    assert_eq!(
+      match_path_pattern("@utils/*", "@utils/strings"),
+      Some("strings")
+    );
+    assert_eq!(match_path_pattern("@utils/*", "@other/strings"), None);
+    assert_eq!(match_path_pattern("config", "config"), Some(""));
+    assert_eq!(match_path_pattern("*.svg", "logo.svg"), Some("logo"));
+  }
+
+  #[test]
+  fn test_normalize_path() {
+    assert_eq!(
+      normalize_path(Path::new("/a/b/./../c/d.ts")),
+      PathBuf::from("/a/c/d.ts")
+    );
+  }
+}
@@ -0,0 +1,299 @@
+extern crate swc_common;
+
+use std::path::{Path, PathBuf};
+
+use self::swc_common::{BytePos, Span};
+
+use crate::call_finder::propagation_path_through;
+use crate::project::{FileAnalysis, ProjectAnalysis};
+use crate::symbol::SymbolId;
+use crate::throw_finder::{IdentifierUsage, ThrowMap};
+use crate::AnalysisResult;
+
+// ----- Queries -----
+// Answers questions about a position of an analyzed file for editor integrations, e.g. why the
+// call under the cursor may throw: what it calls, which throw statements it can reach, what they
+// throw and the functions the error passes through on its way. Navigation goes both ways, from a
+// call to the throw statements it reaches and from a throwing function to the calls to it.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThrowInfo {
+  pub call_span: Span,
+  // the function called, e.g. `Service.load`, or the API for calls to throwing builtins
+  pub callee: String,
+  // the throw statements the call can reach. Empty for builtins.
+  pub throw_sites: Vec<ThrowSite>,
+  pub error_classes: Vec<String>,
+  // ids from the called function down to the function containing the throw statement
+  pub propagation_path: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThrowSite {
+  // the file declaring the throw, `None` for the analyzed file itself
+  pub file: Option<PathBuf>,
+  // spans are relative to the source map of the file declaring the throw
+  pub span: Span,
+  pub error_class: Option<String>,
+  pub message: Option<String>,
+}
+
+fn contains(span: Span, pos: BytePos) -> bool {
+  span.lo <= pos && pos < span.hi
+}
+
+// `Service.load` for the `load` method of `Service`
+fn qualified_name(symbol: &SymbolId) -> String {
+  let mut names = symbol.scope.clone();
+  names.push(symbol.name.clone());
+  names.join(".")
+}
+
+fn throw_sites(throw_map: &ThrowMap, file: Option<PathBuf>) -> Vec<ThrowSite> {
+  if throw_map.thrown_values.is_empty() {
+    return throw_map
+      .throw_spans
+      .iter()
+      .map(|span| ThrowSite {
+        file: file.clone(),
+        span: *span,
+        error_class: None,
+        message: None,
+      })
+      .collect();
+  }
+  throw_map
+    .thrown_values
+    .iter()
+    .map(|thrown_value| ThrowSite {
+      file: file.clone(),
+      span: thrown_value.throw_span,
+      error_class: thrown_value.error_class.clone(),
+      message: thrown_value.message.clone(),
+    })
+    .collect()
+}
+
+// The innermost of the calls at a position, e.g. `parse(input)` in `load(parse(input))`. Calls
+// sharing a span, e.g. to a method any of several classes may declare, are merged.
+fn innermost(mut throw_infos: Vec<ThrowInfo>) -> Option<ThrowInfo> {
+  throw_infos.sort_by(|a, b| {
+    let a_len = a.call_span.hi.0 - a.call_span.lo.0;
+    let b_len = b.call_span.hi.0 - b.call_span.lo.0;
+    a_len.cmp(&b_len).then_with(|| a.callee.cmp(&b.callee))
+  });
+  let mut throw_infos = throw_infos.into_iter();
+  let mut innermost = throw_infos.next()?;
+  let call_span = innermost.call_span;
+  for throw_info in throw_infos.filter(|throw_info| throw_info.call_span == call_span) {
+    for throw_site in throw_info.throw_sites {
+      if !innermost.throw_sites.contains(&throw_site) {
+        innermost.throw_sites.push(throw_site);
+      }
+    }
+    for error_class in throw_info.error_classes {
+      if !innermost.error_classes.contains(&error_class) {
+        innermost.error_classes.push(error_class);
+      }
+    }
+  }
+  Some(innermost)
+}
+
+fn local_throw_infos(result: &AnalysisResult, pos: BytePos) -> Vec<ThrowInfo> {
+  let calls = result
+    .calls_to_throws
+    .iter()
+    .filter(|call| contains(call.call_span, pos))
+    .map(|call| ThrowInfo {
+      call_span: call.call_span,
+      callee: qualified_name(&call.throw_map.symbol),
+      throw_sites: throw_sites(&call.throw_map, None),
+      error_classes: call.throw_map.error_classes(),
+      propagation_path: call.propagation_path.clone(),
+    });
+  let builtin_calls = result
+    .calls_to_throwing_builtins
+    .iter()
+    .filter(|builtin_call| contains(builtin_call.call_span, pos))
+    .map(|builtin_call| ThrowInfo {
+      call_span: builtin_call.call_span,
+      callee: builtin_call.builtin.callee.clone(),
+      throw_sites: vec![],
+      error_classes: builtin_call.builtin.error_classes.clone(),
+      propagation_path: vec![],
+    });
+  calls.chain(builtin_calls).collect()
+}
+
+// Why the call at `pos` may throw, for calls to functions of the same file and to throwing
+// builtins. `None` when there is no such call at `pos`.
+pub fn throw_info_at(result: &AnalysisResult, pos: BytePos) -> Option<ThrowInfo> {
+  innermost(local_throw_infos(result, pos))
+}
+
+// Like `throw_info_at`, including the calls to throwing functions imported from other files of
+// the project
+pub fn file_throw_info_at(file: &FileAnalysis, pos: BytePos) -> Option<ThrowInfo> {
+  let mut throw_infos = local_throw_infos(&file.result, pos);
+  throw_infos.extend(
+    file
+      .imported_throws
+      .iter()
+      .filter(|imported_throw| contains(imported_throw.usage.usage_span, pos))
+      .map(|imported_throw| {
+        let throw_map = &imported_throw.throw_map;
+        ThrowInfo {
+          call_span: imported_throw.usage.usage_span,
+          callee: qualified_name(&throw_map.symbol),
+          throw_sites: throw_sites(throw_map, Some(imported_throw.source_file.clone())),
+          error_classes: throw_map.error_classes(),
+          propagation_path: propagation_path_through(throw_map),
+        }
+      }),
+  );
+  innermost(throw_infos)
+}
+
+// The throw statements the call at `pos` can reach, empty when there is no such call
+pub fn throw_sites_at(result: &AnalysisResult, pos: BytePos) -> Vec<ThrowSite> {
+  throw_info_at(result, pos).map_or(vec![], |throw_info| throw_info.throw_sites)
+}
+
+// Like `throw_sites_at`, following calls to functions imported from other files of the project
+pub fn file_throw_sites_at(file: &FileAnalysis, pos: BytePos) -> Vec<ThrowSite> {
+  file_throw_info_at(file, pos).map_or(vec![], |throw_info| throw_info.throw_sites)
+}
+
+// The innermost call at `pos` to a function imported from another module, whether or not it throws
+pub fn imported_call_at(result: &AnalysisResult, pos: BytePos) -> Option<&IdentifierUsage> {
+  result
+    .imported_identifier_usages
+    .iter()
+    .filter(|usage| contains(usage.usage_span, pos))
+    .min_by_key(|usage| {
+      (
+        usage.usage_span.hi.0 - usage.usage_span.lo.0,
+        usage.usage_span.lo,
+      )
+    })
+}
+
+// The throw statements of the throwing function exported as `export_path`, e.g. `["users",
+// "remove"]`, for editors that resolve imports themselves and analyze the imported file
+pub fn exported_throw_sites(result: &AnalysisResult, export_path: &[String]) -> Vec<ThrowSite> {
+  let mut throw_sites: Vec<ThrowSite> = result
+    .functions_with_throws
+    .iter()
+    .filter(|throw_map| {
+      throw_map
+        .symbol
+        .export_paths()
+        .iter()
+        .any(|path| path == export_path)
+    })
+    .flat_map(|throw_map| throw_sites(throw_map, None))
+    .collect();
+  throw_sites.sort_by_key(|throw_site| (throw_site.span.lo, throw_site.span.hi));
+  throw_sites.dedup();
+  throw_sites
+}
+
+// A call to a throwing function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallSite {
+  // the file making the call, `None` for the analyzed file itself
+  pub file: Option<PathBuf>,
+  pub span: Span,
+}
+
+// The innermost throwing function around `pos`
+pub fn throwing_function_at(result: &AnalysisResult, pos: BytePos) -> Option<&ThrowMap> {
+  result
+    .functions_with_throws
+    .iter()
+    .filter(|throw_map| contains(throw_map.throw_statement, pos))
+    .min_by_key(|throw_map| {
+      (
+        throw_map.throw_statement.hi.0 - throw_map.throw_statement.lo.0,
+        throw_map.throw_statement.lo,
+      )
+    })
+}
+
+fn local_calls_to(result: &AnalysisResult, function: &ThrowMap) -> Vec<CallSite> {
+  let mut call_sites: Vec<CallSite> = result
+    .calls_to_throws
+    .iter()
+    .filter(|call| call.throw_map.symbol == function.symbol)
+    .map(|call| CallSite {
+      file: None,
+      span: call.call_span,
+    })
+    .collect();
+  call_sites.sort_by_key(|call_site| (call_site.span.lo, call_site.span.hi));
+  call_sites.dedup();
+  call_sites
+}
+
+// The calls in the same file to the throwing function around `pos`
+pub fn calls_to_function_at(result: &AnalysisResult, pos: BytePos) -> Vec<CallSite> {
+  throwing_function_at(result, pos).map_or(vec![], |function| local_calls_to(result, function))
+}
+
+// The calls anywhere in the project to the throwing function around `pos` of the file at `path`:
+// those of the file itself first, then the calls of the files importing it, by path
+pub fn project_calls_to_function_at(
+  project: &ProjectAnalysis,
+  path: &Path,
+  pos: BytePos,
+) -> Vec<CallSite> {
+  let Some(file) = project.file(path) else {
+    return vec![];
+  };
+  let Some(function) = throwing_function_at(&file.result, pos) else {
+    return vec![];
+  };
+  let mut call_sites = local_calls_to(&file.result, function);
+  for other_file in project.files.values() {
+    let mut imported_calls: Vec<CallSite> = other_file
+      .imported_throws
+      .iter()
+      .filter(|imported_throw| {
+        imported_throw.source_file == file.path
+          && imported_throw.throw_map.symbol == function.symbol
+      })
+      .map(|imported_throw| CallSite {
+        file: Some(other_file.path.clone()),
+        span: imported_throw.usage.usage_span,
+      })
+      .collect();
+    imported_calls.dedup();
+    call_sites.extend(imported_calls);
+  }
+  call_sites
+}
+
+// The position of a 1-based line and 0-based column (counted in chars) of `source`, whose first
+// byte is at `start_pos`. The inverse of `FileAnalysis::location`.
+pub fn byte_pos_at(
+  source: &str,
+  start_pos: BytePos,
+  line: usize,
+  column: usize,
+) -> Option<BytePos> {
+  let line_start = if line <= 1 {
+    0
+  } else {
+    source
+      .match_indices('\n')
+      .nth(line - 2)
+      .map(|(index, _)| index + 1)?
+  };
+  let line_text = source[line_start..].split('\n').next().unwrap_or_default();
+  let offset = line_text
+    .char_indices()
+    .nth(column)
+    .map_or(line_text.len(), |(index, _)| index);
+  Some(start_pos + BytePos((line_start + offset) as u32))
+}
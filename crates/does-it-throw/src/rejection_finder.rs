@@ -0,0 +1,489 @@
+extern crate serde;
+extern crate swc_common;
+extern crate swc_ecma_ast;
+extern crate swc_ecma_visit;
+
+use std::collections::{HashMap, HashSet};
+
+use swc_ecma_ast::{
+  ArrowExpr, CallExpr, Callee, ClassDecl, ClassMethod, Constructor, Expr, ExprStmt, FnDecl,
+  Function, MemberProp, NewExpr, Pat, Prop, PropName, PropOrSpread, ThrowStmt, TryStmt, UnaryOp,
+  VarDeclarator,
+};
+
+use self::serde::{Deserialize, Serialize};
+use self::swc_common::{comments::Comments, sync::Lrc, Span, Spanned};
+use self::swc_ecma_visit::Visit;
+
+use crate::call_finder::called_symbol;
+use crate::class_hierarchy::ClassHierarchy;
+use crate::scope_analysis::ScopeAnalysis;
+use crate::symbol::{ModuleSymbols, SymbolId};
+use crate::throw_finder::ThrownValue;
+
+// ----- RejectionFinder Visitor implementation -----
+// `ThrowFinder` only knows about `throw` statements. Asynchronous code mostly fails through
+// rejected promises instead, which `RejectionFinder` records as `Rejection`s:
+//
+// - `Promise.reject(error)`
+// - `reject(error)` (or a throw) inside the executor of `new Promise((resolve, reject) => ...)`
+// - uncaught throws inside `async` functions, which reject the returned promise
+//
+// Rejections are attributed to the enclosing function, with the same `SymbolId` and
+// `{class name or NOT_SET}-{function name}` id as its `ThrowMap`, so that `FloatingPromiseFinder`
+// can then look for calls to those functions whose promise is neither awaited nor handled. Calls
+// are resolved to the function they run like `CallFinder` resolves them.
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RejectionKind {
+  PromiseReject,
+  ExecutorReject,
+  AsyncThrow,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rejection {
+  // the `Promise.reject(...)` or `reject(...)` call, or the throw statement
+  pub span: Span,
+  pub kind: RejectionKind,
+  // `None` for `Promise.reject()` and `reject()` without arguments
+  pub rejected_value: Option<ThrownValue>,
+  pub function_or_method_name: String,
+  pub class_name: Option<String>,
+  pub id: String,
+  // the function the rejection is attributed to
+  pub symbol: SymbolId,
+}
+
+// A call to a function that may reject, used as a statement on its own, so that the
+// rejection is never handled, e.g. `save()` instead of `await save()` or `save().catch(log)`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FloatingPromise {
+  pub span: Span,
+  pub call_span: Span,
+  // id of the called function, as in `Rejection::id`
+  pub callee_id: String,
+}
+
+struct FunctionFrame {
+  span: Span,
+  name: String,
+  class_name: Option<String>,
+  is_async: bool,
+  // `new Promise` executors: a throw inside them rejects the promise
+  is_executor: bool,
+  catching_try_depth: usize,
+}
+
+pub struct RejectionFinder<'rejection_finder> {
+  comments: Lrc<dyn Comments>,
+  ignore_statements: &'rejection_finder [String],
+  symbols: Lrc<ModuleSymbols>,
+  pub rejections: Vec<Rejection>,
+  // all `async` functions, whether they reject or not
+  pub async_functions: HashSet<SymbolId>,
+  current_class_name: Option<String>,
+  function_stack: Vec<FunctionFrame>,
+  // name given to the next function visited, e.g. by the variable it is assigned to
+  pending_function_name: Option<String>,
+  // `reject` parameters of the `new Promise` executors being visited
+  reject_bindings: Vec<String>,
+}
+
+fn function_id(class_name: &Option<String>, function_name: &str) -> String {
+  format!(
+    "{}-{}",
+    class_name.clone().unwrap_or_else(|| "NOT_SET".to_string()),
+    function_name
+  )
+}
+
+fn prop_name_to_string(prop_name: &PropName) -> Option<String> {
+  match prop_name {
+    PropName::Ident(ident) => Some(ident.sym.to_string()),
+    PropName::Str(str_) => Some(str_.value.to_string()),
+    _ => None,
+  }
+}
+
+fn is_promise_reject(call: &CallExpr) -> bool {
+  if let Callee::Expr(callee) = &call.callee {
+    if let Expr::Member(member_expr) = &**callee {
+      if let (Expr::Ident(object), MemberProp::Ident(method)) =
+        (&*member_expr.obj, &member_expr.prop)
+      {
+        return &*object.sym == "Promise" && &*method.sym == "reject";
+      }
+    }
+  }
+  false
+}
+
+// `reject` in `new Promise((resolve, reject) => ...)`, if the executor is written inline
+fn executor_reject_binding(executor: &Expr) -> Option<String> {
+  let second_param = match executor {
+    Expr::Arrow(arrow_expr) => arrow_expr.params.get(1),
+    Expr::Fn(fn_expr) => fn_expr.function.params.get(1).map(|param| &param.pat),
+    _ => None,
+  }?;
+  match second_param {
+    Pat::Ident(binding) => Some(binding.id.sym.to_string()),
+    _ => None,
+  }
+}
+
+impl<'rejection_finder> RejectionFinder<'rejection_finder> {
+  pub fn new(
+    comments: Lrc<dyn Comments>,
+    ignore_statements: &'rejection_finder [String],
+    symbols: Lrc<ModuleSymbols>,
+  ) -> Self {
+    Self {
+      comments,
+      ignore_statements,
+      symbols,
+      rejections: vec![],
+      async_functions: HashSet::new(),
+      current_class_name: None,
+      function_stack: vec![],
+      pending_function_name: None,
+      reject_bindings: vec![],
+    }
+  }
+
+  // The functions that may return a rejected promise, with their ids
+  pub fn rejecting_functions(&self) -> HashMap<SymbolId, String> {
+    self
+      .rejections
+      .iter()
+      .map(|rejection| (rejection.symbol.clone(), rejection.id.clone()))
+      .collect()
+  }
+
+  fn is_ignored(&self, span: Span) -> bool {
+    self.comments.get_leading(span.lo).is_some_and(|comments| {
+      comments.iter().any(|comment| {
+        self
+          .ignore_statements
+          .iter()
+          .any(|keyword| comment.text.contains(&**keyword))
+      })
+    })
+  }
+
+  // Executors belong to the function that creates the promise
+  fn owning_frame(&self) -> Option<&FunctionFrame> {
+    self
+      .function_stack
+      .iter()
+      .rev()
+      .find(|frame| !frame.is_executor)
+  }
+
+  fn record(&mut self, span: Span, kind: RejectionKind, rejected_value: Option<ThrownValue>) {
+    if self.is_ignored(span) {
+      return;
+    }
+    let (function_or_method_name, class_name, symbol) = match self.owning_frame() {
+      Some(frame) => (
+        frame.name.clone(),
+        frame.class_name.clone(),
+        self.symbols.symbol_at(frame.span),
+      ),
+      None => (
+        "<module>".to_string(),
+        None,
+        self.symbols.enclosing_symbol(span),
+      ),
+    };
+    self.rejections.push(Rejection {
+      span,
+      kind,
+      rejected_value,
+      id: function_id(&class_name, &function_or_method_name),
+      function_or_method_name,
+      class_name,
+      symbol,
+    });
+  }
+
+  fn visit_function_like<F: FnOnce(&mut Self)>(
+    &mut self,
+    span: Span,
+    is_async: bool,
+    visit_body: F,
+  ) {
+    let name = self
+      .pending_function_name
+      .take()
+      .unwrap_or_else(|| "<anonymous>".to_string());
+    if is_async {
+      self.async_functions.insert(self.symbols.symbol_at(span));
+    }
+    self.function_stack.push(FunctionFrame {
+      span,
+      name,
+      class_name: self.current_class_name.clone(),
+      is_async,
+      is_executor: false,
+      catching_try_depth: 0,
+    });
+    visit_body(self);
+    self.function_stack.pop();
+  }
+}
+
+impl<'rejection_finder> Visit for RejectionFinder<'rejection_finder> {
+  fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+    self.pending_function_name = Some(fn_decl.ident.sym.to_string());
+    self.visit_function(&fn_decl.function);
+  }
+
+  fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+    if let (Some(ident), Some(init)) = (declarator.name.as_ident(), &declarator.init) {
+      match &**init {
+        Expr::Fn(_) | Expr::Arrow(_) => {
+          self.pending_function_name = Some(ident.sym.to_string());
+        }
+        Expr::Object(object_lit) => {
+          // methods of object literals are named like class methods, `{object}-{method}`
+          let class_name = self.current_class_name.replace(ident.sym.to_string());
+          self.visit_object_lit(object_lit);
+          self.current_class_name = class_name;
+          return;
+        }
+        _ => {}
+      }
+    }
+    swc_ecma_visit::visit_var_declarator(self, declarator);
+  }
+
+  fn visit_prop_or_spread(&mut self, prop: &PropOrSpread) {
+    if let PropOrSpread::Prop(prop) = prop {
+      match &**prop {
+        Prop::Method(method_prop) => {
+          self.pending_function_name = prop_name_to_string(&method_prop.key);
+        }
+        Prop::KeyValue(key_value_prop)
+          if matches!(&*key_value_prop.value, Expr::Fn(_) | Expr::Arrow(_)) =>
+        {
+          self.pending_function_name = prop_name_to_string(&key_value_prop.key);
+          self.visit_expr(&key_value_prop.value);
+          return;
+        }
+        _ => {}
+      }
+    }
+    swc_ecma_visit::visit_prop_or_spread(self, prop);
+  }
+
+  fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+    let class_name = self
+      .current_class_name
+      .replace(class_decl.ident.sym.to_string());
+    self.visit_class(&class_decl.class);
+    self.current_class_name = class_name;
+  }
+
+  fn visit_class_method(&mut self, class_method: &ClassMethod) {
+    self.pending_function_name = prop_name_to_string(&class_method.key);
+    self.visit_function(&class_method.function);
+  }
+
+  fn visit_constructor(&mut self, constructor: &Constructor) {
+    self.pending_function_name = Some("<constructor>".to_string());
+    self.visit_function_like(constructor.span, false, |finder| {
+      swc_ecma_visit::visit_constructor(finder, constructor)
+    });
+  }
+
+  fn visit_function(&mut self, function: &Function) {
+    self.visit_function_like(function.span, function.is_async, |finder| {
+      swc_ecma_visit::visit_function(finder, function)
+    });
+  }
+
+  fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr) {
+    self.visit_function_like(arrow_expr.span, arrow_expr.is_async, |finder| {
+      swc_ecma_visit::visit_arrow_expr(finder, arrow_expr)
+    });
+  }
+
+  fn visit_new_expr(&mut self, new_expr: &NewExpr) {
+    let executor = match (&*new_expr.callee, new_expr.args.as_ref()) {
+      (Expr::Ident(callee), Some(args)) if &*callee.sym == "Promise" => args.first(),
+      _ => None,
+    };
+    let Some(executor) = executor.filter(|executor| executor.spread.is_none()) else {
+      swc_ecma_visit::visit_new_expr(self, new_expr);
+      return;
+    };
+
+    let reject_binding = executor_reject_binding(&executor.expr);
+    if let Some(reject_binding) = &reject_binding {
+      self.reject_bindings.push(reject_binding.clone());
+    }
+    let function_stack_depth = self.function_stack.len();
+    self.pending_function_name = Some("<executor>".to_string());
+    match &*executor.expr {
+      Expr::Arrow(arrow_expr) => {
+        self.visit_function_like(arrow_expr.span, arrow_expr.is_async, |finder| {
+          finder.function_stack[function_stack_depth].is_executor = true;
+          swc_ecma_visit::visit_arrow_expr(finder, arrow_expr)
+        })
+      }
+      Expr::Fn(fn_expr) => {
+        self.visit_function_like(fn_expr.function.span, fn_expr.function.is_async, |finder| {
+          finder.function_stack[function_stack_depth].is_executor = true;
+          swc_ecma_visit::visit_function(finder, &fn_expr.function)
+        })
+      }
+      expr => {
+        self.pending_function_name = None;
+        self.visit_expr(expr);
+      }
+    }
+    if reject_binding.is_some() {
+      self.reject_bindings.pop();
+    }
+  }
+
+  fn visit_call_expr(&mut self, call: &CallExpr) {
+    let first_arg = call
+      .args
+      .first()
+      .filter(|arg| arg.spread.is_none())
+      .map(|arg| ThrownValue::from_expr(call.span, &arg.expr));
+    if is_promise_reject(call) {
+      self.record(call.span, RejectionKind::PromiseReject, first_arg);
+    } else if let Callee::Expr(callee) = &call.callee {
+      if let Expr::Ident(ident) = &**callee {
+        if self
+          .reject_bindings
+          .iter()
+          .any(|binding| *binding == *ident.sym)
+        {
+          self.record(call.span, RejectionKind::ExecutorReject, first_arg);
+        }
+      }
+    }
+    swc_ecma_visit::visit_call_expr(self, call);
+  }
+
+  fn visit_try_stmt(&mut self, try_stmt: &TryStmt) {
+    let catches = try_stmt.handler.is_some();
+    if catches {
+      if let Some(frame) = self.function_stack.last_mut() {
+        frame.catching_try_depth += 1;
+      }
+    }
+    self.visit_block_stmt(&try_stmt.block);
+    if catches {
+      if let Some(frame) = self.function_stack.last_mut() {
+        frame.catching_try_depth -= 1;
+      }
+    }
+    if let Some(handler) = &try_stmt.handler {
+      self.visit_catch_clause(handler);
+    }
+    if let Some(finalizer) = &try_stmt.finalizer {
+      self.visit_block_stmt(finalizer);
+    }
+  }
+
+  fn visit_throw_stmt(&mut self, throw_stmt: &ThrowStmt) {
+    let kind = match self.function_stack.last() {
+      Some(frame) if frame.catching_try_depth == 0 && frame.is_executor => {
+        Some(RejectionKind::ExecutorReject)
+      }
+      Some(frame) if frame.catching_try_depth == 0 && frame.is_async => {
+        Some(RejectionKind::AsyncThrow)
+      }
+      _ => None,
+    };
+    if let Some(kind) = kind {
+      self.record(
+        throw_stmt.span,
+        kind,
+        Some(ThrownValue::from_throw_stmt(throw_stmt)),
+      );
+    }
+    swc_ecma_visit::visit_throw_stmt(self, throw_stmt);
+  }
+}
+
+// ----- FloatingPromiseFinder Visitor implementation -----
+// Looks for expression statements that call a function known to reject, and that are not
+// `await`ed, `return`ed, assigned, `void`ed, or handled with `.catch(...)` or `.then(_, onRejected)`.
+
+pub struct FloatingPromiseFinder {
+  // the functions that may reject, with their ids
+  pub rejecting_functions: HashMap<SymbolId, String>,
+  pub floating_promises: Vec<FloatingPromise>,
+  symbols: Lrc<ModuleSymbols>,
+  scope_analysis: Lrc<ScopeAnalysis>,
+  class_hierarchy: Lrc<ClassHierarchy>,
+}
+
+impl FloatingPromiseFinder {
+  pub fn new(
+    rejecting_functions: HashMap<SymbolId, String>,
+    symbols: Lrc<ModuleSymbols>,
+    scope_analysis: Lrc<ScopeAnalysis>,
+    class_hierarchy: Lrc<ClassHierarchy>,
+  ) -> Self {
+    Self {
+      rejecting_functions,
+      floating_promises: vec![],
+      symbols,
+      scope_analysis,
+      class_hierarchy,
+    }
+  }
+
+  // The id of the rejecting function the promise of `expr` comes from, when it is unhandled
+  fn unhandled_rejecting_call(&self, expr: &Expr) -> Option<(String, Span)> {
+    match expr {
+      Expr::Paren(paren_expr) => self.unhandled_rejecting_call(&paren_expr.expr),
+      Expr::Unary(unary_expr) if unary_expr.op == UnaryOp::Void => None,
+      Expr::Call(call) => {
+        let Callee::Expr(callee) = &call.callee else {
+          return None;
+        };
+        if let Expr::Member(member_expr) = &**callee {
+          if let MemberProp::Ident(method) = &member_expr.prop {
+            match &*method.sym {
+              "catch" => return None,
+              "then" if call.args.len() >= 2 => return None,
+              "then" | "finally" => return self.unhandled_rejecting_call(&member_expr.obj),
+              _ => {}
+            }
+          }
+        }
+        let called_function = called_symbol(
+          &self.symbols,
+          &self.scope_analysis,
+          &self.class_hierarchy,
+          callee,
+          call.span,
+        )?;
+        let callee_id = self.rejecting_functions.get(&called_function)?;
+        Some((callee_id.clone(), call.span))
+      }
+      _ => None,
+    }
+  }
+}
+
+impl Visit for FloatingPromiseFinder {
+  fn visit_expr_stmt(&mut self, expr_stmt: &ExprStmt) {
+    if let Some((callee_id, call_span)) = self.unhandled_rejecting_call(&expr_stmt.expr) {
+      self.floating_promises.push(FloatingPromise {
+        span: expr_stmt.expr.span(),
+        call_span,
+        callee_id,
+      });
+    }
+    swc_ecma_visit::visit_expr_stmt(self, expr_stmt);
+  }
+}
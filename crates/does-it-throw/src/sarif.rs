@@ -0,0 +1,463 @@
+extern crate serde;
+extern crate swc_common;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use self::serde::Serialize;
+use self::swc_common::{SourceMap, Span};
+
+use crate::project::FileAnalysis;
+use crate::rejection_finder::RejectionKind;
+use crate::AnalysisResult;
+
+// ----- SARIF -----
+// SARIF 2.1.0 output, for code-scanning dashboards that consume static-analysis results.
+//
+// Every kind of finding has a rule with a stable id, and every result carries a
+// `partialFingerprints` entry built from the id of the function involved, so that dashboards can
+// match findings across commits even when lines move around. Syntax errors have no rule.
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+pub const FINGERPRINT_KEY: &str = "doesItThrowId/v1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SarifRule {
+  FunctionThatMayThrow,
+  ThrowStatement,
+  CallToThrow,
+  CallToImportedThrow,
+  PromiseRejection,
+  FloatingPromise,
+  CallToThrowingBuiltin,
+  UndocumentedThrow,
+  StaleThrowsTag,
+  ThrowingOverride,
+}
+
+impl SarifRule {
+  // In the order of the rules of the log, new rules go last so that rule indices stay stable
+  pub const ALL: [SarifRule; 10] = [
+    SarifRule::FunctionThatMayThrow,
+    SarifRule::ThrowStatement,
+    SarifRule::CallToThrow,
+    SarifRule::CallToImportedThrow,
+    SarifRule::PromiseRejection,
+    SarifRule::FloatingPromise,
+    SarifRule::CallToThrowingBuiltin,
+    SarifRule::UndocumentedThrow,
+    SarifRule::StaleThrowsTag,
+    SarifRule::ThrowingOverride,
+  ];
+
+  // These ids are part of the output format, do not rename them
+  pub fn id(self) -> &'static str {
+    match self {
+      SarifRule::FunctionThatMayThrow => "does-it-throw/function-that-may-throw",
+      SarifRule::ThrowStatement => "does-it-throw/throw-statement",
+      SarifRule::CallToThrow => "does-it-throw/call-to-throw",
+      SarifRule::CallToImportedThrow => "does-it-throw/call-to-imported-throw",
+      SarifRule::PromiseRejection => "does-it-throw/promise-rejection",
+      SarifRule::FloatingPromise => "does-it-throw/floating-promise",
+      SarifRule::CallToThrowingBuiltin => "does-it-throw/call-to-throwing-builtin",
+      SarifRule::UndocumentedThrow => "does-it-throw/undocumented-throw",
+      SarifRule::StaleThrowsTag => "does-it-throw/stale-throws-tag",
+      SarifRule::ThrowingOverride => "does-it-throw/throwing-override",
+    }
+  }
+
+  fn name(self) -> &'static str {
+    match self {
+      SarifRule::FunctionThatMayThrow => "FunctionThatMayThrow",
+      SarifRule::ThrowStatement => "ThrowStatement",
+      SarifRule::CallToThrow => "CallToThrow",
+      SarifRule::CallToImportedThrow => "CallToImportedThrow",
+      SarifRule::PromiseRejection => "PromiseRejection",
+      SarifRule::FloatingPromise => "FloatingPromise",
+      SarifRule::CallToThrowingBuiltin => "CallToThrowingBuiltin",
+      SarifRule::UndocumentedThrow => "UndocumentedThrow",
+      SarifRule::StaleThrowsTag => "StaleThrowsTag",
+      SarifRule::ThrowingOverride => "ThrowingOverride",
+    }
+  }
+
+  // Same wording as the LSP diagnostics
+  fn message(self) -> &'static str {
+    match self {
+      SarifRule::FunctionThatMayThrow => "Function that may throw.",
+      SarifRule::ThrowStatement => "Throw statement.",
+      SarifRule::CallToThrow => "Function call that may throw.",
+      SarifRule::CallToImportedThrow => "Function imported that may throw.",
+      SarifRule::PromiseRejection => "Promise rejection.",
+      SarifRule::FloatingPromise => "Promise that may reject is neither awaited nor handled.",
+      SarifRule::CallToThrowingBuiltin => "Call to a builtin API that may throw.",
+      SarifRule::UndocumentedThrow => "Throw not documented by a @throws tag.",
+      SarifRule::StaleThrowsTag => "@throws tag that matches no throw.",
+      SarifRule::ThrowingOverride => {
+        "Override that may throw while the method it overrides does not."
+      }
+    }
+  }
+
+  fn description(self) -> &'static str {
+    match self {
+      SarifRule::FunctionThatMayThrow => {
+        "A function or method contains a throw statement that is not caught."
+      }
+      SarifRule::ThrowStatement => "A throw statement that is not caught within its function.",
+      SarifRule::CallToThrow => "A call to a function or method, in the same file, that may throw.",
+      SarifRule::CallToImportedThrow => "A call to an imported function or method that may throw.",
+      SarifRule::PromiseRejection => {
+        "A promise rejected with `Promise.reject` or the `reject` callback of `new Promise`."
+      }
+      SarifRule::FloatingPromise => {
+        "A call to a function that may reject whose promise is neither awaited nor handled."
+      }
+      SarifRule::CallToThrowingBuiltin => {
+        "An uncaught call to a standard library or Node API that is known to throw."
+      }
+      SarifRule::UndocumentedThrow => {
+        "A throw of a documented function that none of its @throws tags describes."
+      }
+      SarifRule::StaleThrowsTag => "A @throws tag that describes nothing the function throws.",
+      SarifRule::ThrowingOverride => {
+        "A method that may throw while the method of the base class it overrides does not."
+      }
+    }
+  }
+
+  fn index(self) -> usize {
+    SarifRule::ALL
+      .iter()
+      .position(|rule| *rule == self)
+      .unwrap()
+  }
+
+  fn descriptor(self) -> ReportingDescriptor {
+    ReportingDescriptor {
+      id: self.id().to_string(),
+      name: self.name().to_string(),
+      short_description: Message {
+        text: self.message().to_string(),
+      },
+      full_description: Message {
+        text: self.description().to_string(),
+      },
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SarifLevel {
+  Error,
+  Warning,
+  Note,
+}
+
+impl SarifLevel {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      SarifLevel::Error => "error",
+      SarifLevel::Warning => "warning",
+      SarifLevel::Note => "note",
+    }
+  }
+}
+
+#[derive(Serialize, Debug)]
+pub struct SarifLog {
+  #[serde(rename = "$schema")]
+  pub schema: String,
+  pub version: String,
+  pub runs: Vec<Run>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Run {
+  pub tool: Tool,
+  pub column_kind: String,
+  pub results: Vec<SarifResult>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Tool {
+  pub driver: ToolComponent,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolComponent {
+  pub name: String,
+  pub version: String,
+  pub information_uri: String,
+  pub rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingDescriptor {
+  pub id: String,
+  pub name: String,
+  pub short_description: Message,
+  pub full_description: Message,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Message {
+  pub text: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+  pub rule_id: String,
+  pub rule_index: usize,
+  pub level: String,
+  pub message: Message,
+  pub locations: Vec<Location>,
+  pub partial_fingerprints: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+  pub physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+  pub artifact_location: ArtifactLocation,
+  pub region: Region,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ArtifactLocation {
+  pub uri: String,
+}
+
+// Lines and columns are 1-based, `end_column` is exclusive
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+  pub start_line: usize,
+  pub start_column: usize,
+  pub end_line: usize,
+  pub end_column: usize,
+}
+
+impl Region {
+  pub fn from_span(cm: &SourceMap, span: Span) -> Region {
+    let start = cm.lookup_char_pos(span.lo);
+    let end = cm.lookup_char_pos(span.hi);
+    Region {
+      start_line: start.line,
+      start_column: start.col.0 + 1,
+      end_line: end.line,
+      end_column: end.col.0 + 1,
+    }
+  }
+
+  // For the spans of a file of a project analysis
+  pub fn in_file(file: &FileAnalysis, span: Span) -> Region {
+    let start = file.location(span.lo);
+    let end = file.location(span.hi);
+    Region {
+      start_line: start.line,
+      start_column: start.column + 1,
+      end_line: end.line,
+      end_column: end.column + 1,
+    }
+  }
+}
+
+// What a result is about, before it is located in a file
+#[derive(Clone, Debug, PartialEq)]
+pub struct SarifFinding {
+  pub rule: SarifRule,
+  pub span: Span,
+  // the id results are fingerprinted by, e.g. the id of the throwing function
+  pub id: String,
+}
+
+// Every finding of `result` but the calls to imported functions
+fn local_findings(result: &AnalysisResult) -> Vec<SarifFinding> {
+  let mut findings: Vec<SarifFinding> = vec![];
+  for throw_map in &result.functions_with_throws {
+    findings.push(SarifFinding {
+      rule: SarifRule::FunctionThatMayThrow,
+      span: throw_map.throw_statement,
+      id: throw_map.id.clone(),
+    });
+    for throw_span in &throw_map.throw_spans {
+      findings.push(SarifFinding {
+        rule: SarifRule::ThrowStatement,
+        span: *throw_span,
+        id: throw_map.id.clone(),
+      });
+    }
+  }
+  for call in &result.calls_to_throws {
+    findings.push(SarifFinding {
+      rule: SarifRule::CallToThrow,
+      span: call.call_span,
+      id: call.throw_map.id.clone(),
+    });
+  }
+  // throws in async functions are already reported as throw statements
+  for rejection in result
+    .rejections
+    .iter()
+    .filter(|rejection| rejection.kind != RejectionKind::AsyncThrow)
+  {
+    findings.push(SarifFinding {
+      rule: SarifRule::PromiseRejection,
+      span: rejection.span,
+      id: rejection.id.clone(),
+    });
+  }
+  for floating_promise in &result.floating_promises {
+    findings.push(SarifFinding {
+      rule: SarifRule::FloatingPromise,
+      span: floating_promise.span,
+      id: floating_promise.callee_id.clone(),
+    });
+  }
+  for builtin_call in &result.calls_to_throwing_builtins {
+    findings.push(SarifFinding {
+      rule: SarifRule::CallToThrowingBuiltin,
+      span: builtin_call.call_span,
+      id: format!("{}:{}", builtin_call.id, builtin_call.builtin.callee),
+    });
+  }
+  for undocumented_throw in &result.undocumented_throws {
+    findings.push(SarifFinding {
+      rule: SarifRule::UndocumentedThrow,
+      span: undocumented_throw.throw_span,
+      id: undocumented_throw.id.clone(),
+    });
+  }
+  for stale_throws_tag in &result.stale_throws_tags {
+    findings.push(SarifFinding {
+      rule: SarifRule::StaleThrowsTag,
+      span: stale_throws_tag.throws_tag.span,
+      id: stale_throws_tag.id.clone(),
+    });
+  }
+  for throwing_override in &result.throwing_overrides {
+    findings.push(SarifFinding {
+      rule: SarifRule::ThrowingOverride,
+      span: throwing_override.span,
+      id: throwing_override.throw_map.id.clone(),
+    });
+  }
+  findings
+}
+
+// The findings of one analyzed file.
+// `imported_throw_ids` are the ids of imported functions that are known to throw; calls to any
+// other imported identifier are not reported (the same matching the language server does).
+pub fn sarif_findings(
+  result: &AnalysisResult,
+  imported_throw_ids: &HashSet<String>,
+) -> Vec<SarifFinding> {
+  let mut findings = local_findings(result);
+  for usage in &result.imported_identifier_usages {
+    let import_id = usage.import_id();
+    if imported_throw_ids.contains(&import_id) {
+      findings.push(SarifFinding {
+        rule: SarifRule::CallToImportedThrow,
+        span: usage.usage_span,
+        id: import_id,
+      });
+    }
+  }
+  findings
+}
+
+// The findings of a file of a project analysis, whose calls to imported functions are linked
+// to the functions they call
+pub fn file_sarif_findings(file: &FileAnalysis) -> Vec<SarifFinding> {
+  let mut findings = local_findings(&file.result);
+  for imported_throw in &file.imported_throws {
+    findings.push(SarifFinding {
+      rule: SarifRule::CallToImportedThrow,
+      span: imported_throw.usage.usage_span,
+      id: imported_throw.usage.import_id(),
+    });
+  }
+  findings
+}
+
+// Builds the SARIF results for the findings of one file at `uri`
+pub fn sarif_results(
+  mut findings: Vec<SarifFinding>,
+  uri: &str,
+  region: impl Fn(Span) -> Region,
+  level: impl Fn(SarifRule) -> SarifLevel,
+) -> Vec<SarifResult> {
+  // the analysis results are hash sets, sort so that output and occurrence numbers are deterministic
+  findings.sort_by(|a, b| {
+    (a.span.lo, a.span.hi, a.rule)
+      .cmp(&(b.span.lo, b.span.hi, b.rule))
+      .then_with(|| a.id.cmp(&b.id))
+  });
+  findings.dedup();
+
+  // fingerprints only use the rule, the id and how many times that pair was seen before,
+  // so they survive edits that move code around
+  let mut occurrences: HashMap<(SarifRule, String), usize> = HashMap::new();
+  findings
+    .into_iter()
+    .map(|finding| {
+      let occurrence = occurrences
+        .entry((finding.rule, finding.id.clone()))
+        .or_insert(0);
+      let fingerprint = format!("{}:{}:{}", finding.rule.id(), finding.id, occurrence);
+      *occurrence += 1;
+
+      SarifResult {
+        rule_id: finding.rule.id().to_string(),
+        rule_index: finding.rule.index(),
+        level: level(finding.rule).as_str().to_string(),
+        message: Message {
+          text: finding.rule.message().to_string(),
+        },
+        locations: vec![Location {
+          physical_location: PhysicalLocation {
+            artifact_location: ArtifactLocation {
+              uri: uri.to_string(),
+            },
+            region: region(finding.span),
+          },
+        }],
+        partial_fingerprints: BTreeMap::from([(FINGERPRINT_KEY.to_string(), fingerprint)]),
+      }
+    })
+    .collect()
+}
+
+impl SarifLog {
+  pub fn new(results: Vec<SarifResult>) -> SarifLog {
+    SarifLog {
+      schema: SARIF_SCHEMA.to_string(),
+      version: SARIF_VERSION.to_string(),
+      runs: vec![Run {
+        tool: Tool {
+          driver: ToolComponent {
+            name: "does-it-throw".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            information_uri: "https://github.com/michaelangeloio/does-it-throw".to_string(),
+            rules: SarifRule::ALL
+              .iter()
+              .map(|rule| rule.descriptor())
+              .collect(),
+          },
+        },
+        column_kind: "unicodeCodePoints".to_string(),
+        results,
+      }],
+    }
+  }
+}
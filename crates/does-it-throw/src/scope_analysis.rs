@@ -0,0 +1,548 @@
+extern crate swc_common;
+extern crate swc_ecma_ast;
+extern crate swc_ecma_visit;
+
+use std::collections::HashMap;
+
+use self::swc_common::Span;
+use self::swc_ecma_ast::{
+  ArrowExpr, AssignPatProp, BindingIdent, BlockStmt, BlockStmtOrExpr, CatchClause, ClassExpr,
+  Constructor, Decl, Expr, FnDecl, FnExpr, ForHead, ForInStmt, ForOfStmt, ForStmt, Function, Ident,
+  ImportDecl, ImportSpecifier, Module, ModuleDecl, ModuleItem, ParamOrTsParamProp, Pat, Prop,
+  SetterProp, Stmt, TsParamPropParam, VarDecl, VarDeclKind, VarDeclOrExpr,
+};
+use self::swc_ecma_visit::Visit;
+
+use crate::commonjs::required_bindings;
+
+// ----- Scope analysis -----
+// Binds every identifier expression of a module to the declaration it refers to, following
+// JavaScript's scoping rules: `var` and function declarations are hoisted to their function,
+// `let`, `const` and `class` are scoped to their block, and parameters and catch clause
+// bindings to their function or catch clause. Identifiers declared nowhere in the module
+// (globals) are not bound.
+//
+// This plays the role of SWC's `resolver` pass (which marks identifiers with syntax contexts)
+// without transforming the module, so that spans stay exactly as parsed.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindingKind {
+  Import,
+  Function,
+  Class,
+  Var,
+  Let,
+  Const,
+  Param,
+  CatchParam,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindingValue {
+  // the span of the function, class or object literal declared by (or assigned to) the binding
+  Declared(Span),
+  // `new Name(...)`, with the span of the `Name` identifier
+  Instance(Span),
+  Unknown,
+}
+
+#[derive(Clone, Debug)]
+pub struct Binding {
+  pub name: String,
+  pub kind: BindingKind,
+  // span of the identifier declaring the binding
+  pub span: Span,
+  pub value: BindingValue,
+}
+
+#[derive(Debug, Default)]
+pub struct ScopeAnalysis {
+  bindings: Vec<Binding>,
+  // span of an identifier expression -> index in `bindings`
+  references: HashMap<Span, usize>,
+}
+
+impl ScopeAnalysis {
+  pub fn new(module: &Module) -> Self {
+    let mut resolver = Resolver::default();
+    resolver.visit_module(module);
+    Self {
+      bindings: resolver.bindings,
+      references: resolver.references,
+    }
+  }
+
+  // The binding the identifier expression at `span` refers to, `None` for globals
+  pub fn binding_at(&self, span: Span) -> Option<&Binding> {
+    self
+      .references
+      .get(&span)
+      .map(|index| &self.bindings[*index])
+  }
+
+  // The identifier expressions referring to the binding declared by the identifier at `span`
+  pub fn references_to(&self, span: Span) -> Vec<Span> {
+    self
+      .references
+      .iter()
+      .filter(|(_, index)| self.bindings[**index].span == span)
+      .map(|(reference, _)| *reference)
+      .collect()
+  }
+
+  // The function, class or object literal the identifier expression at `span` refers to,
+  // following `const service = new Service()` to the `Service` class
+  pub fn declared_span(&self, span: Span) -> Option<Span> {
+    match self.binding_at(span)?.value {
+      BindingValue::Declared(declared_span) => Some(declared_span),
+      BindingValue::Instance(class_span) => match self.binding_at(class_span)?.value {
+        BindingValue::Declared(declared_span) => Some(declared_span),
+        _ => None,
+      },
+      BindingValue::Unknown => None,
+    }
+  }
+}
+
+fn value_of(expr: &Expr) -> BindingValue {
+  match expr {
+    Expr::Fn(fn_expr) => BindingValue::Declared(fn_expr.function.span),
+    Expr::Arrow(arrow_expr) => BindingValue::Declared(arrow_expr.span),
+    Expr::Object(object_lit) => BindingValue::Declared(object_lit.span),
+    Expr::Class(class_expr) => BindingValue::Declared(class_expr.class.span),
+    Expr::New(new_expr) => match &*new_expr.callee {
+      Expr::Ident(ident) => BindingValue::Instance(ident.span),
+      _ => BindingValue::Unknown,
+    },
+    Expr::Paren(paren_expr) => value_of(&paren_expr.expr),
+    Expr::TsAs(ts_as) => value_of(&ts_as.expr),
+    Expr::TsSatisfies(ts_satisfies) => value_of(&ts_satisfies.expr),
+    Expr::TsConstAssertion(ts_const) => value_of(&ts_const.expr),
+    Expr::TsNonNull(ts_non_null) => value_of(&ts_non_null.expr),
+    Expr::TsTypeAssertion(ts_type_assertion) => value_of(&ts_type_assertion.expr),
+    _ => BindingValue::Unknown,
+  }
+}
+
+// The identifiers a pattern binds, without descending into default values or computed keys
+#[derive(Default)]
+struct PatIdents {
+  idents: Vec<Ident>,
+}
+
+impl Visit for PatIdents {
+  fn visit_binding_ident(&mut self, binding_ident: &BindingIdent) {
+    self.idents.push(binding_ident.id.clone());
+  }
+
+  fn visit_assign_pat_prop(&mut self, assign_pat_prop: &AssignPatProp) {
+    self.idents.push(assign_pat_prop.key.clone());
+  }
+
+  fn visit_expr(&mut self, _expr: &Expr) {}
+}
+
+pub fn pat_idents(pat: &Pat) -> Vec<Ident> {
+  let mut collector = PatIdents::default();
+  collector.visit_pat(pat);
+  collector.idents
+}
+
+// `var` declarations of a function body, without entering nested functions and classes
+#[derive(Default)]
+struct VarDecls<'a> {
+  var_decls: Vec<&'a VarDecl>,
+}
+
+impl<'a> VarDecls<'a> {
+  fn collect_stmt(&mut self, stmt: &'a Stmt) {
+    match stmt {
+      Stmt::Decl(Decl::Var(var_decl)) if var_decl.kind == VarDeclKind::Var => {
+        self.var_decls.push(var_decl)
+      }
+      Stmt::Block(block_stmt) => self.collect_stmts(&block_stmt.stmts),
+      Stmt::If(if_stmt) => {
+        self.collect_stmt(&if_stmt.cons);
+        if let Some(alt) = &if_stmt.alt {
+          self.collect_stmt(alt);
+        }
+      }
+      Stmt::Labeled(labeled_stmt) => self.collect_stmt(&labeled_stmt.body),
+      Stmt::While(while_stmt) => self.collect_stmt(&while_stmt.body),
+      Stmt::DoWhile(do_while_stmt) => self.collect_stmt(&do_while_stmt.body),
+      Stmt::For(for_stmt) => {
+        if let Some(VarDeclOrExpr::VarDecl(var_decl)) = &for_stmt.init {
+          if var_decl.kind == VarDeclKind::Var {
+            self.var_decls.push(var_decl);
+          }
+        }
+        self.collect_stmt(&for_stmt.body);
+      }
+      Stmt::ForIn(ForInStmt { left, body, .. }) | Stmt::ForOf(ForOfStmt { left, body, .. }) => {
+        if let ForHead::VarDecl(var_decl) = left {
+          if var_decl.kind == VarDeclKind::Var {
+            self.var_decls.push(var_decl);
+          }
+        }
+        self.collect_stmt(body);
+      }
+      Stmt::Try(try_stmt) => {
+        self.collect_stmts(&try_stmt.block.stmts);
+        if let Some(handler) = &try_stmt.handler {
+          self.collect_stmts(&handler.body.stmts);
+        }
+        if let Some(finalizer) = &try_stmt.finalizer {
+          self.collect_stmts(&finalizer.stmts);
+        }
+      }
+      Stmt::Switch(switch_stmt) => {
+        for case in &switch_stmt.cases {
+          self.collect_stmts(&case.cons);
+        }
+      }
+      Stmt::With(with_stmt) => self.collect_stmt(&with_stmt.body),
+      _ => {}
+    }
+  }
+
+  fn collect_stmts(&mut self, stmts: &'a [Stmt]) {
+    for stmt in stmts {
+      self.collect_stmt(stmt);
+    }
+  }
+}
+
+#[derive(Default)]
+struct Resolver {
+  bindings: Vec<Binding>,
+  references: HashMap<Span, usize>,
+  // innermost last, name -> index in `bindings`
+  scopes: Vec<HashMap<String, usize>>,
+}
+
+impl Resolver {
+  fn declare(&mut self, ident: &Ident, kind: BindingKind, value: BindingValue) {
+    let index = self.bindings.len();
+    self.bindings.push(Binding {
+      name: ident.sym.to_string(),
+      kind,
+      span: ident.span,
+      value,
+    });
+    if let Some(scope) = self.scopes.last_mut() {
+      scope.insert(ident.sym.to_string(), index);
+    }
+  }
+
+  fn declare_pat(&mut self, pat: &Pat, kind: BindingKind) {
+    for ident in pat_idents(pat) {
+      self.declare(&ident, kind, BindingValue::Unknown);
+    }
+  }
+
+  fn declare_var_decl(&mut self, var_decl: &VarDecl) {
+    let kind = match var_decl.kind {
+      VarDeclKind::Var => BindingKind::Var,
+      VarDeclKind::Let => BindingKind::Let,
+      VarDeclKind::Const => BindingKind::Const,
+    };
+    for declarator in &var_decl.decls {
+      if let Some((_, bindings)) = required_bindings(declarator) {
+        for binding in bindings {
+          self.declare(&binding.local, BindingKind::Import, BindingValue::Unknown);
+        }
+        continue;
+      }
+      match (&declarator.name, &declarator.init) {
+        (Pat::Ident(binding_ident), Some(init)) => {
+          self.declare(&binding_ident.id, kind, value_of(init))
+        }
+        _ => self.declare_pat(&declarator.name, kind),
+      }
+    }
+  }
+
+  fn declare_decl(&mut self, decl: &Decl) {
+    match decl {
+      Decl::Fn(fn_decl) => self.declare(
+        &fn_decl.ident,
+        BindingKind::Function,
+        BindingValue::Declared(fn_decl.function.span),
+      ),
+      Decl::Class(class_decl) => self.declare(
+        &class_decl.ident,
+        BindingKind::Class,
+        BindingValue::Declared(class_decl.class.span),
+      ),
+      Decl::Var(var_decl) if var_decl.kind != VarDeclKind::Var => self.declare_var_decl(var_decl),
+      _ => {}
+    }
+  }
+
+  // Declarations scoped to a block: `let`, `const`, classes and functions
+  fn hoist_block<'a>(&mut self, stmts: impl Iterator<Item = &'a Stmt>) {
+    for stmt in stmts {
+      if let Stmt::Decl(decl) = stmt {
+        self.declare_decl(decl);
+      }
+    }
+  }
+
+  // Declarations of a function body, or of the top level: the block ones and every `var`
+  fn hoist_function<'a>(&mut self, stmts: impl Iterator<Item = &'a Stmt> + Clone) {
+    let mut var_decls = VarDecls::default();
+    for stmt in stmts.clone() {
+      var_decls.collect_stmt(stmt);
+    }
+    for var_decl in var_decls.var_decls {
+      self.declare_var_decl(var_decl);
+    }
+    self.hoist_block(stmts);
+  }
+
+  fn reference(&mut self, ident: &Ident) {
+    let name = ident.sym.to_string();
+    let binding = self
+      .scopes
+      .iter()
+      .rev()
+      .find_map(|scope| scope.get(&name).cloned());
+    if let Some(index) = binding {
+      self.references.insert(ident.span, index);
+    }
+  }
+
+  fn with_scope<F: FnOnce(&mut Self)>(&mut self, visit: F) {
+    self.scopes.push(HashMap::new());
+    visit(self);
+    self.scopes.pop();
+  }
+
+  fn visit_function_body(&mut self, stmts: &[Stmt]) {
+    self.hoist_function(stmts.iter());
+    for stmt in stmts {
+      self.visit_stmt(stmt);
+    }
+  }
+}
+
+impl Visit for Resolver {
+  fn visit_module(&mut self, module: &Module) {
+    self.with_scope(|resolver| {
+      let stmts = module.body.iter().filter_map(|item| match item {
+        ModuleItem::Stmt(stmt) => Some(stmt),
+        _ => None,
+      });
+      resolver.hoist_function(stmts);
+      for item in &module.body {
+        match item {
+          ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+            if let Decl::Var(var_decl) = &export_decl.decl {
+              if var_decl.kind == VarDeclKind::Var {
+                resolver.declare_var_decl(var_decl);
+              }
+            }
+            resolver.declare_decl(&export_decl.decl);
+          }
+          ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default_decl)) => {
+            match &export_default_decl.decl {
+              swc_ecma_ast::DefaultDecl::Fn(FnExpr {
+                ident: Some(ident),
+                function,
+              }) => resolver.declare(
+                ident,
+                BindingKind::Function,
+                BindingValue::Declared(function.span),
+              ),
+              swc_ecma_ast::DefaultDecl::Class(ClassExpr {
+                ident: Some(ident),
+                class,
+              }) => resolver.declare(
+                ident,
+                BindingKind::Class,
+                BindingValue::Declared(class.span),
+              ),
+              _ => {}
+            }
+          }
+          _ => {}
+        }
+      }
+      swc_ecma_visit::visit_module(resolver, module);
+    });
+  }
+
+  fn visit_import_decl(&mut self, import: &ImportDecl) {
+    for specifier in &import.specifiers {
+      let local = match specifier {
+        ImportSpecifier::Named(named_spec) => &named_spec.local,
+        ImportSpecifier::Default(default_spec) => &default_spec.local,
+        ImportSpecifier::Namespace(namespace_spec) => &namespace_spec.local,
+      };
+      self.declare(local, BindingKind::Import, BindingValue::Unknown);
+    }
+  }
+
+  fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+    // the name is declared by the scope around the declaration
+    self.visit_function(&fn_decl.function);
+  }
+
+  fn visit_fn_expr(&mut self, fn_expr: &FnExpr) {
+    match &fn_expr.ident {
+      // a named function expression can refer to itself by its name
+      Some(ident) => self.with_scope(|resolver| {
+        resolver.declare(
+          ident,
+          BindingKind::Function,
+          BindingValue::Declared(fn_expr.function.span),
+        );
+        resolver.visit_function(&fn_expr.function);
+      }),
+      None => self.visit_function(&fn_expr.function),
+    }
+  }
+
+  fn visit_function(&mut self, function: &Function) {
+    self.with_scope(|resolver| {
+      for param in &function.params {
+        resolver.declare_pat(&param.pat, BindingKind::Param);
+      }
+      for param in &function.params {
+        resolver.visit_param(param);
+      }
+      if let Some(body) = &function.body {
+        resolver.visit_function_body(&body.stmts);
+      }
+    });
+  }
+
+  fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr) {
+    self.with_scope(|resolver| {
+      for param in &arrow_expr.params {
+        resolver.declare_pat(param, BindingKind::Param);
+      }
+      for param in &arrow_expr.params {
+        resolver.visit_pat(param);
+      }
+      match &*arrow_expr.body {
+        BlockStmtOrExpr::BlockStmt(block_stmt) => resolver.visit_function_body(&block_stmt.stmts),
+        BlockStmtOrExpr::Expr(expr) => resolver.visit_expr(expr),
+      }
+    });
+  }
+
+  fn visit_constructor(&mut self, constructor: &Constructor) {
+    self.with_scope(|resolver| {
+      for param in &constructor.params {
+        match param {
+          ParamOrTsParamProp::Param(param) => resolver.declare_pat(&param.pat, BindingKind::Param),
+          ParamOrTsParamProp::TsParamProp(ts_param_prop) => match &ts_param_prop.param {
+            TsParamPropParam::Ident(binding_ident) => {
+              resolver.declare(&binding_ident.id, BindingKind::Param, BindingValue::Unknown)
+            }
+            TsParamPropParam::Assign(assign_pat) => {
+              resolver.declare_pat(&assign_pat.left, BindingKind::Param)
+            }
+          },
+        }
+      }
+      for param in &constructor.params {
+        resolver.visit_param_or_ts_param_prop(param);
+      }
+      if let Some(body) = &constructor.body {
+        resolver.visit_function_body(&body.stmts);
+      }
+    });
+  }
+
+  fn visit_class_expr(&mut self, class_expr: &ClassExpr) {
+    match &class_expr.ident {
+      // a named class expression can refer to itself by its name
+      Some(ident) => self.with_scope(|resolver| {
+        resolver.declare(
+          ident,
+          BindingKind::Class,
+          BindingValue::Declared(class_expr.class.span),
+        );
+        resolver.visit_class(&class_expr.class);
+      }),
+      None => self.visit_class(&class_expr.class),
+    }
+  }
+
+  fn visit_setter_prop(&mut self, setter_prop: &SetterProp) {
+    self.with_scope(|resolver| {
+      resolver.declare_pat(&setter_prop.param, BindingKind::Param);
+      resolver.visit_pat(&setter_prop.param);
+      if let Some(body) = &setter_prop.body {
+        resolver.visit_function_body(&body.stmts);
+      }
+    });
+  }
+
+  fn visit_block_stmt(&mut self, block_stmt: &BlockStmt) {
+    self.with_scope(|resolver| {
+      resolver.hoist_block(block_stmt.stmts.iter());
+      swc_ecma_visit::visit_block_stmt(resolver, block_stmt);
+    });
+  }
+
+  fn visit_catch_clause(&mut self, catch_clause: &CatchClause) {
+    self.with_scope(|resolver| {
+      if let Some(param) = &catch_clause.param {
+        resolver.declare_pat(param, BindingKind::CatchParam);
+        resolver.visit_pat(param);
+      }
+      resolver.visit_block_stmt(&catch_clause.body);
+    });
+  }
+
+  fn visit_for_stmt(&mut self, for_stmt: &ForStmt) {
+    self.with_scope(|resolver| {
+      if let Some(VarDeclOrExpr::VarDecl(var_decl)) = &for_stmt.init {
+        if var_decl.kind != VarDeclKind::Var {
+          resolver.declare_var_decl(var_decl);
+        }
+      }
+      swc_ecma_visit::visit_for_stmt(resolver, for_stmt);
+    });
+  }
+
+  fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) {
+    self.with_scope(|resolver| {
+      if let ForHead::VarDecl(var_decl) = &for_in_stmt.left {
+        if var_decl.kind != VarDeclKind::Var {
+          resolver.declare_var_decl(var_decl);
+        }
+      }
+      swc_ecma_visit::visit_for_in_stmt(resolver, for_in_stmt);
+    });
+  }
+
+  fn visit_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt) {
+    self.with_scope(|resolver| {
+      if let ForHead::VarDecl(var_decl) = &for_of_stmt.left {
+        if var_decl.kind != VarDeclKind::Var {
+          resolver.declare_var_decl(var_decl);
+        }
+      }
+      swc_ecma_visit::visit_for_of_stmt(resolver, for_of_stmt);
+    });
+  }
+
+  fn visit_expr(&mut self, expr: &Expr) {
+    if let Expr::Ident(ident) = expr {
+      self.reference(ident);
+    }
+    swc_ecma_visit::visit_expr(self, expr);
+  }
+
+  fn visit_prop(&mut self, prop: &Prop) {
+    // `{ foo }` refers to `foo`
+    if let Prop::Shorthand(ident) = prop {
+      self.reference(ident);
+    }
+    swc_ecma_visit::visit_prop(self, prop);
+  }
+}
@@ -0,0 +1,844 @@
+extern crate serde;
+extern crate swc_common;
+extern crate swc_ecma_ast;
+extern crate swc_ecma_visit;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use self::serde::{Deserialize, Serialize};
+use self::swc_common::Span;
+use self::swc_ecma_ast::{
+  ArrowExpr, AssignExpr, ClassDecl, ClassMethod, ClassProp, Constructor, DefaultDecl, ExportAll,
+  ExportDecl, ExportDefaultDecl, ExportDefaultExpr, ExportSpecifier, Expr, FnDecl, Function, Ident,
+  Lit, MemberProp, MethodKind, Module, ModuleExportName, NamedExport, ObjectLit, PatOrExpr,
+  PrivateMethod, PrivateName, PrivateProp, Prop, PropName, PropOrSpread, StaticBlock,
+  VarDeclarator,
+};
+use self::swc_ecma_visit::Visit;
+
+use crate::commonjs::{assigned_expr, exports_member, is_module_exports};
+
+#[derive(
+  Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum SymbolKind {
+  // function declarations and expressions, and arrow functions
+  #[default]
+  Function,
+  // class and object literal methods
+  Method,
+  Getter,
+  Setter,
+  Constructor,
+  // `static { ... }` blocks of a class, named `<static>`
+  StaticBlock,
+  Class,
+  // object literals bound to a name, whose methods are called like `object.method()`
+  Object,
+  // a binding imported from another module, before it is resolved
+  Import,
+  // code at the top level of a module, outside of any function
+  Module,
+}
+
+// Identifies a function (or the class, object or module around it) across scopes and files.
+// Two functions named `parse` only share a `SymbolId` when they are declared in the same scope
+// of the same file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SymbolId {
+  // the file declaring the symbol, `None` when the code was analyzed without a path
+  pub file: Option<PathBuf>,
+  // names of the enclosing classes, object literals and functions, outermost first
+  pub scope: Vec<String>,
+  pub name: String,
+  // the names the outermost enclosing declaration (or the symbol itself, at the top level)
+  // is exported under, e.g. `default` for `export default function`, in source order
+  pub export_names: Vec<String>,
+  pub kind: SymbolKind,
+}
+
+impl SymbolId {
+  // A usage of an import from `source`, e.g. `["utils", "parse"]` for `utils.parse()` with
+  // `utils` imported by name. `file` holds the module specifier until it is resolved.
+  pub fn import(source: &str, path: &[String]) -> Self {
+    let (name, scope) = path.split_last().map_or_else(
+      || (String::new(), vec![]),
+      |(name, scope)| (name.clone(), scope.to_vec()),
+    );
+    Self {
+      file: Some(PathBuf::from(source)),
+      export_names: path.first().cloned().into_iter().collect(),
+      scope,
+      name,
+      kind: SymbolKind::Import,
+    }
+  }
+
+  // How importing modules reach the symbol: its first export name, followed by the path from
+  // the exported declaration down to the symbol, e.g. `["Service", "load"]` for a method of an
+  // exported class. `None` for symbols that cannot be imported.
+  pub fn export_path(&self) -> Option<Vec<String>> {
+    self.export_paths().into_iter().next()
+  }
+
+  // Like `export_path`, for each name the symbol is exported under, e.g. `["parse"]` and
+  // `["other"]` for `export { parse, parse as other }`
+  pub fn export_paths(&self) -> Vec<Vec<String>> {
+    self
+      .export_names
+      .iter()
+      .map(|export_name| {
+        let mut export_path = vec![export_name.clone()];
+        if !self.scope.is_empty() {
+          export_path.extend(self.scope.iter().skip(1).cloned());
+          export_path.push(self.name.clone());
+        }
+        export_path
+      })
+      .collect()
+  }
+}
+
+impl SymbolId {
+  // The `Class-method` style id importing modules know the symbol by, built from its export
+  // path rather than local names, e.g. `NOT_SET-default` for `export default function () {}`
+  // and for the calls to it through `import parse from './parse'`
+  pub fn import_id(&self) -> Option<String> {
+    let export_path = self.export_path()?;
+    let (name, containers) = export_path.split_last()?;
+    let container = if containers.is_empty() {
+      "NOT_SET".to_string()
+    } else {
+      containers.join(".")
+    };
+    Some(format!("{}-{}", container, name))
+  }
+
+  // Whether the symbol is reached from the class or object literal `container` through `path`,
+  // e.g. `["nested", "load"]` for `container.nested.load`
+  pub fn is_reached_from(&self, container: &SymbolId, path: &[String]) -> bool {
+    let Some((name, inner_path)) = path.split_last() else {
+      return false;
+    };
+    let mut scope = container.scope.clone();
+    scope.push(container.name.clone());
+    scope.extend(inner_path.iter().cloned());
+    self.file == container.file && &self.name == name && self.scope == scope
+  }
+}
+
+impl fmt::Display for SymbolId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some(file) = &self.file {
+      write!(f, "{}#", file.display())?;
+    }
+    for scope in &self.scope {
+      write!(f, "{}.", scope)?;
+    }
+    write!(f, "{}", self.name)
+  }
+}
+
+#[derive(Clone, Debug)]
+struct Scope {
+  span: Span,
+  name: String,
+  kind: SymbolKind,
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+  outer.lo <= inner.lo && inner.hi <= outer.hi
+}
+
+fn is_function_like(kind: SymbolKind) -> bool {
+  matches!(
+    kind,
+    SymbolKind::Function
+      | SymbolKind::Method
+      | SymbolKind::Getter
+      | SymbolKind::Setter
+      | SymbolKind::Constructor
+      | SymbolKind::StaticBlock
+  )
+}
+
+// An export of another module that a module exports as its own, e.g.
+// `export { parse as parseDate } from './dates'`, so that imports from barrel files can be
+// followed to the module declaring what they import
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReExport {
+  // the module specifier, as written
+  pub source: String,
+  pub kind: ReExportKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReExportKind {
+  // `export * from './x'`: every export of `./x` but its default export
+  All,
+  // `export * as ns from './x'`: the module itself, exported as `ns`
+  Namespace(String),
+  // `export { orig as exported } from './x'`
+  Named { orig: String, exported: String },
+}
+
+impl ReExport {
+  // The export path in the re-exported module that `export_path` reaches through this
+  // re-export, e.g. `["parse"]` for `["parseDate"]` and `export { parse as parseDate } from`.
+  // `None` when the re-export does not cover it.
+  pub fn follow(&self, export_path: &[String]) -> Option<Vec<String>> {
+    let (first, rest) = export_path.split_first()?;
+    match &self.kind {
+      ReExportKind::All => (first != "default").then(|| export_path.to_vec()),
+      ReExportKind::Namespace(name) => (name == first && !rest.is_empty()).then(|| rest.to_vec()),
+      ReExportKind::Named { orig, exported } => (exported == first).then(|| {
+        let mut followed = vec![orig.clone()];
+        followed.extend(rest.iter().cloned());
+        followed
+      }),
+    }
+  }
+}
+
+// The named scopes of a module (functions, methods, classes and object literals), and what it exports.
+// Built once per module and shared by the finders, so that they all agree on symbol identity.
+#[derive(Debug, Default)]
+pub struct ModuleSymbols {
+  file: Option<PathBuf>,
+  // in source order, outer scopes before the scopes they contain
+  scopes: Vec<Scope>,
+  // top-level binding -> names it is exported under, in source order
+  exports: HashMap<String, Vec<String>>,
+  // exports of other modules this one re-exports, in source order
+  re_exports: Vec<ReExport>,
+  // the symbol of each scope, by index in `scopes`
+  symbols: Vec<SymbolId>,
+  // scope name -> indices of the scopes with that name, in source order
+  scopes_by_name: HashMap<String, Vec<usize>>,
+}
+
+impl ModuleSymbols {
+  pub fn new(module: &Module, file: Option<PathBuf>) -> Self {
+    let mut collector = ScopeCollector::default();
+    collector.visit_module(module);
+    let mut scopes = collector.scopes;
+    scopes.sort_by_key(|scope| (scope.span.lo, std::cmp::Reverse(scope.span.hi)));
+    let mut module_symbols = Self {
+      file,
+      scopes,
+      exports: collector.exports,
+      re_exports: collector.re_exports,
+      ..Default::default()
+    };
+    module_symbols.index_symbols();
+    module_symbols
+  }
+
+  // Computes the symbol of every scope once, so that lookups don't rescan the scopes around it
+  fn index_symbols(&mut self) {
+    // indices of the scopes around the current one, outermost first
+    let mut open: Vec<usize> = vec![];
+    for (index, scope) in self.scopes.iter().enumerate() {
+      while let Some(&outer) = open.last() {
+        if contains(self.scopes[outer].span, scope.span) {
+          break;
+        }
+        open.pop();
+      }
+      let enclosing: Vec<String> = open
+        .iter()
+        .map(|&outer| &self.scopes[outer])
+        .filter(|outer| outer.span != scope.span)
+        .map(|outer| outer.name.clone())
+        .collect();
+      let top_level_name = enclosing.first().unwrap_or(&scope.name);
+      self.symbols.push(SymbolId {
+        file: self.file.clone(),
+        export_names: self
+          .exports
+          .get(top_level_name)
+          .cloned()
+          .unwrap_or_default(),
+        scope: enclosing,
+        name: scope.name.clone(),
+        kind: scope.kind,
+      });
+      self
+        .scopes_by_name
+        .entry(scope.name.clone())
+        .or_default()
+        .push(index);
+      open.push(index);
+    }
+  }
+
+  // The indices of the scopes named `name`
+  fn scopes_named(&self, name: &str) -> impl Iterator<Item = usize> + '_ {
+    self.scopes_by_name.get(name).into_iter().flatten().copied()
+  }
+
+  pub fn re_exports(&self) -> &[ReExport] {
+    &self.re_exports
+  }
+
+  fn symbol_for(&self, index: usize) -> SymbolId {
+    self.symbols[index].clone()
+  }
+
+  // The symbol declared by the function, method or declarator spanning `span`,
+  // e.g. the arrow function of `foo = () => {}` for the span of the whole declarator
+  pub fn symbol_at(&self, span: Span) -> SymbolId {
+    let declared = self
+      .scopes
+      .iter()
+      .position(|scope| is_function_like(scope.kind) && contains(span, scope.span));
+    match declared {
+      Some(index) => self.symbol_for(index),
+      None => self.enclosing_symbol(span),
+    }
+  }
+
+  // The function, class or object literal spanning exactly `span`
+  pub fn symbol_declared_at(&self, span: Span) -> Option<SymbolId> {
+    self
+      .scopes
+      .iter()
+      .position(|scope| scope.span == span)
+      .map(|index| self.symbol_for(index))
+  }
+
+  // The function or method reached from the class or object literal `container` through `path`
+  pub fn member(&self, container: &SymbolId, path: &[String]) -> Option<SymbolId> {
+    let name = path.last()?;
+    self
+      .scopes_named(name)
+      .filter(|&index| is_function_like(self.scopes[index].kind))
+      .map(|index| self.symbol_for(index))
+      .find(|symbol| symbol.is_reached_from(container, path))
+  }
+
+  // The class or object literal declaring a method
+  pub fn container_of(&self, symbol: &SymbolId) -> Option<SymbolId> {
+    let (name, scope) = symbol.scope.split_last()?;
+    self
+      .scopes_named(name)
+      .filter(|&index| {
+        matches!(
+          self.scopes[index].kind,
+          SymbolKind::Class | SymbolKind::Object
+        )
+      })
+      .map(|index| self.symbol_for(index))
+      .find(|container| container.file == symbol.file && container.scope == scope)
+  }
+
+  // The innermost class or object literal around `span`, which `this` refers to in its methods
+  pub fn enclosing_container(&self, span: Span) -> Option<SymbolId> {
+    self
+      .scopes
+      .iter()
+      .rposition(|scope| {
+        matches!(scope.kind, SymbolKind::Class | SymbolKind::Object) && contains(scope.span, span)
+      })
+      .map(|index| self.symbol_for(index))
+  }
+
+  // The innermost function or method around `span`, or the module itself for top-level code
+  pub fn enclosing_symbol(&self, span: Span) -> SymbolId {
+    let enclosing = self
+      .scopes
+      .iter()
+      .rposition(|scope| is_function_like(scope.kind) && contains(scope.span, span));
+    match enclosing {
+      Some(index) => self.symbol_for(index),
+      None => SymbolId {
+        file: self.file.clone(),
+        name: "<module>".to_string(),
+        kind: SymbolKind::Module,
+        ..Default::default()
+      },
+    }
+  }
+}
+
+// The name of a class or object literal member, e.g. `parse`, `'content-type'` as
+// `content-type`, or `[Symbol.iterator]` for a computed key.
+// `None` for computed keys that are neither a literal nor a dotted name.
+pub fn prop_name(prop_name: &PropName) -> Option<String> {
+  match prop_name {
+    PropName::Ident(ident) => Some(ident.sym.to_string()),
+    PropName::Str(str_) => Some(str_.value.to_string()),
+    PropName::Num(num) => Some(num.value.to_string()),
+    PropName::BigInt(big_int) => Some(big_int.value.to_string()),
+    PropName::Computed(computed) => computed_name(&computed.expr),
+  }
+}
+
+// `#validate` for a private member
+pub fn private_name(private_name: &PrivateName) -> String {
+  format!("#{}", private_name.id.sym)
+}
+
+// The member a property access refers to, named like `prop_name` names declarations, so that
+// `this.#validate()` and `this[Symbol.iterator]()` can be matched to the member they call
+pub fn member_prop_name(member_prop: &MemberProp) -> Option<String> {
+  match member_prop {
+    MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+    MemberProp::PrivateName(name) => Some(private_name(name)),
+    MemberProp::Computed(computed) => computed_name(&computed.expr),
+  }
+}
+
+fn computed_name(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Lit(Lit::Str(str_)) => Some(str_.value.to_string()),
+    Expr::Lit(Lit::Num(num)) => Some(num.value.to_string()),
+    Expr::Lit(Lit::BigInt(big_int)) => Some(big_int.value.to_string()),
+    _ => dotted_name(expr).map(|name| format!("[{}]", name)),
+  }
+}
+
+// `a`, `a.b` and `a.b.c` style names, `None` for anything computed
+pub fn dotted_name(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Ident(ident) => Some(ident.sym.to_string()),
+    Expr::Member(member_expr) => match &member_expr.prop {
+      MemberProp::Ident(prop) => {
+        dotted_name(&member_expr.obj).map(|obj| format!("{}.{}", obj, prop.sym))
+      }
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn method_symbol_kind(method_kind: MethodKind) -> SymbolKind {
+  match method_kind {
+    MethodKind::Method => SymbolKind::Method,
+    MethodKind::Getter => SymbolKind::Getter,
+    MethodKind::Setter => SymbolKind::Setter,
+  }
+}
+
+// Functions assigned to a property are named after it, e.g. `handler = () => {}`
+fn names_value(value: &Option<Box<Expr>>) -> bool {
+  matches!(
+    value.as_deref(),
+    Some(Expr::Fn(_) | Expr::Arrow(_) | Expr::Object(_) | Expr::Class(_))
+  )
+}
+
+// Anonymous default exports are named after their export, e.g. `default` for
+// `export default function () {}`
+pub fn default_export_name(ident: Option<&Ident>) -> String {
+  ident.map_or_else(|| "default".to_string(), |ident| ident.sym.to_string())
+}
+
+fn export_name(export_name: &ModuleExportName) -> String {
+  match export_name {
+    ModuleExportName::Ident(ident) => ident.sym.to_string(),
+    ModuleExportName::Str(str_) => str_.value.to_string(),
+  }
+}
+
+#[derive(Default)]
+struct ScopeCollector {
+  scopes: Vec<Scope>,
+  exports: HashMap<String, Vec<String>>,
+  re_exports: Vec<ReExport>,
+  // a name for the next function, class or object literal, from the declaration around it
+  pending_name: Option<String>,
+  // how many anonymous functions each scope has, to tell them apart
+  anonymous_counts: HashMap<Vec<String>, usize>,
+  // names of the scopes around the current position
+  scope_stack: Vec<String>,
+}
+
+impl ScopeCollector {
+  fn name_or_anonymous(&mut self, name: Option<String>) -> String {
+    name.unwrap_or_else(|| {
+      let count = self
+        .anonymous_counts
+        .entry(self.scope_stack.clone())
+        .or_insert(0);
+      *count += 1;
+      format!("<anonymous#{}>", count)
+    })
+  }
+
+  // A binding can be exported under several names, e.g. `export { parse, parse as other }`
+  fn export(&mut self, local: String, exported: String) {
+    let export_names = self.exports.entry(local).or_default();
+    if !export_names.contains(&exported) {
+      export_names.push(exported);
+    }
+  }
+
+  // `exports.name = value`: a local binding assigned is exported under `name`, and a function,
+  // class or object literal assigned is named after it
+  fn export_commonjs(&mut self, name: String, value: &Expr) {
+    match value {
+      Expr::Ident(ident) => {
+        self.export(ident.sym.to_string(), name);
+      }
+      Expr::Fn(_) | Expr::Arrow(_) | Expr::Object(_) | Expr::Class(_) => {
+        self.export(name.clone(), name.clone());
+        self.pending_name = Some(name);
+      }
+      _ => {}
+    }
+  }
+
+  // `module.exports = value`: the value is the module's default export, and the properties of an
+  // object literal assigned are each exported under their key
+  fn export_module_exports(&mut self, value: &Expr) {
+    let default_name = match value {
+      Expr::Ident(ident) => {
+        self.export(ident.sym.to_string(), "default".to_string());
+        return;
+      }
+      Expr::Fn(fn_expr) => default_export_name(fn_expr.ident.as_ref()),
+      Expr::Class(class_expr) => default_export_name(class_expr.ident.as_ref()),
+      Expr::Arrow(_) => default_export_name(None),
+      Expr::Object(object_lit) => {
+        for prop in &object_lit.props {
+          let PropOrSpread::Prop(prop) = prop else {
+            continue;
+          };
+          match &**prop {
+            Prop::Shorthand(ident) => {
+              self.export(ident.sym.to_string(), ident.sym.to_string());
+            }
+            Prop::KeyValue(key_value_prop) => {
+              let Some(key) = prop_name(&key_value_prop.key) else {
+                continue;
+              };
+              match &*key_value_prop.value {
+                Expr::Ident(ident) => {
+                  self.export(ident.sym.to_string(), key);
+                }
+                _ => {
+                  self.export(key.clone(), key);
+                }
+              }
+            }
+            Prop::Method(method_prop) => {
+              if let Some(key) = prop_name(&method_prop.key) {
+                self.export(key.clone(), key);
+              }
+            }
+            _ => {}
+          }
+        }
+        return;
+      }
+      _ => return,
+    };
+    self.export(default_name.clone(), "default".to_string());
+    self.pending_name = Some(default_name);
+  }
+
+  fn enter<F: FnOnce(&mut Self)>(&mut self, span: Span, name: String, kind: SymbolKind, visit: F) {
+    self.scopes.push(Scope {
+      span,
+      name: name.clone(),
+      kind,
+    });
+    self.scope_stack.push(name);
+    visit(self);
+    self.scope_stack.pop();
+  }
+}
+
+impl Visit for ScopeCollector {
+  fn visit_export_decl(&mut self, export_decl: &ExportDecl) {
+    match &export_decl.decl {
+      swc_ecma_ast::Decl::Fn(fn_decl) => {
+        let name = fn_decl.ident.sym.to_string();
+        self.export(name.clone(), name);
+      }
+      swc_ecma_ast::Decl::Class(class_decl) => {
+        let name = class_decl.ident.sym.to_string();
+        self.export(name.clone(), name);
+      }
+      swc_ecma_ast::Decl::Var(var_decl) => {
+        for declarator in &var_decl.decls {
+          if let Some(ident) = declarator.name.as_ident() {
+            let name = ident.sym.to_string();
+            self.export(name.clone(), name);
+          }
+        }
+      }
+      _ => {}
+    }
+    swc_ecma_visit::visit_export_decl(self, export_decl);
+  }
+
+  fn visit_export_default_decl(&mut self, export_default_decl: &ExportDefaultDecl) {
+    let name = match &export_default_decl.decl {
+      DefaultDecl::Fn(fn_expr) => default_export_name(fn_expr.ident.as_ref()),
+      DefaultDecl::Class(class_expr) => default_export_name(class_expr.ident.as_ref()),
+      DefaultDecl::TsInterfaceDecl(_) => "default".to_string(),
+    };
+    self.export(name.clone(), "default".to_string());
+    self.pending_name = Some(name);
+    swc_ecma_visit::visit_export_default_decl(self, export_default_decl);
+  }
+
+  fn visit_export_default_expr(&mut self, export_default_expr: &ExportDefaultExpr) {
+    match &*export_default_expr.expr {
+      Expr::Ident(ident) => {
+        self.export(ident.sym.to_string(), "default".to_string());
+      }
+      // `export default { ... }` and `export default () => {}` are named after their export
+      Expr::Object(_) | Expr::Arrow(_) => {
+        let name = default_export_name(None);
+        self.export(name.clone(), "default".to_string());
+        self.pending_name = Some(name);
+      }
+      _ => {}
+    }
+    swc_ecma_visit::visit_export_default_expr(self, export_default_expr);
+  }
+
+  fn visit_named_export(&mut self, named_export: &NamedExport) {
+    // re-exports from other modules do not export local bindings
+    if let Some(src) = &named_export.src {
+      for specifier in &named_export.specifiers {
+        let kind = match specifier {
+          ExportSpecifier::Namespace(namespace) => {
+            ReExportKind::Namespace(export_name(&namespace.name))
+          }
+          ExportSpecifier::Default(default) => ReExportKind::Named {
+            orig: "default".to_string(),
+            exported: default.exported.sym.to_string(),
+          },
+          ExportSpecifier::Named(named) => {
+            let orig = export_name(&named.orig);
+            let exported = named
+              .exported
+              .as_ref()
+              .map(export_name)
+              .unwrap_or_else(|| orig.clone());
+            ReExportKind::Named { orig, exported }
+          }
+        };
+        self.re_exports.push(ReExport {
+          source: src.value.to_string(),
+          kind,
+        });
+      }
+      return;
+    }
+    for specifier in &named_export.specifiers {
+      if let ExportSpecifier::Named(named) = specifier {
+        let local = export_name(&named.orig);
+        let exported = named
+          .exported
+          .as_ref()
+          .map(export_name)
+          .unwrap_or_else(|| local.clone());
+        self.export(local, exported);
+      }
+    }
+  }
+
+  fn visit_export_all(&mut self, export_all: &ExportAll) {
+    self.re_exports.push(ReExport {
+      source: export_all.src.value.to_string(),
+      kind: ReExportKind::All,
+    });
+  }
+
+  fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+    self.pending_name = Some(fn_decl.ident.sym.to_string());
+    self.visit_function(&fn_decl.function);
+  }
+
+  fn visit_function(&mut self, function: &Function) {
+    let name = self.pending_name.take();
+    let name = self.name_or_anonymous(name);
+    self.enter(function.span, name, SymbolKind::Function, |collector| {
+      swc_ecma_visit::visit_function(collector, function)
+    });
+  }
+
+  fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr) {
+    let name = self.pending_name.take();
+    let name = self.name_or_anonymous(name);
+    self.enter(arrow_expr.span, name, SymbolKind::Function, |collector| {
+      swc_ecma_visit::visit_arrow_expr(collector, arrow_expr)
+    });
+  }
+
+  fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+    let name = class_decl.ident.sym.to_string();
+    self.enter(
+      class_decl.class.span,
+      name,
+      SymbolKind::Class,
+      |collector| swc_ecma_visit::visit_class(collector, &class_decl.class),
+    );
+  }
+
+  fn visit_class_expr(&mut self, class_expr: &swc_ecma_ast::ClassExpr) {
+    let name = self
+      .pending_name
+      .take()
+      .or_else(|| class_expr.ident.as_ref().map(|ident| ident.sym.to_string()));
+    let name = self.name_or_anonymous(name);
+    self.enter(
+      class_expr.class.span,
+      name,
+      SymbolKind::Class,
+      |collector| swc_ecma_visit::visit_class(collector, &class_expr.class),
+    );
+  }
+
+  fn visit_class_method(&mut self, class_method: &ClassMethod) {
+    self.pending_name = None;
+    let name = prop_name(&class_method.key);
+    let name = self.name_or_anonymous(name);
+    let kind = method_symbol_kind(class_method.kind);
+    self.enter(class_method.span, name, kind, |collector| {
+      swc_ecma_visit::visit_function(collector, &class_method.function)
+    });
+  }
+
+  fn visit_private_method(&mut self, private_method: &PrivateMethod) {
+    self.pending_name = None;
+    let name = private_name(&private_method.key);
+    let kind = method_symbol_kind(private_method.kind);
+    self.enter(private_method.span, name, kind, |collector| {
+      swc_ecma_visit::visit_function(collector, &private_method.function)
+    });
+  }
+
+  fn visit_class_prop(&mut self, class_prop: &ClassProp) {
+    self.visit_prop_name(&class_prop.key);
+    if let Some(value) = &class_prop.value {
+      if names_value(&class_prop.value) {
+        self.pending_name = prop_name(&class_prop.key);
+      }
+      self.visit_expr(value);
+      self.pending_name = None;
+    }
+  }
+
+  fn visit_private_prop(&mut self, private_prop: &PrivateProp) {
+    if let Some(value) = &private_prop.value {
+      if names_value(&private_prop.value) {
+        self.pending_name = Some(private_name(&private_prop.key));
+      }
+      self.visit_expr(value);
+      self.pending_name = None;
+    }
+  }
+
+  fn visit_static_block(&mut self, static_block: &StaticBlock) {
+    self.pending_name = None;
+    self.enter(
+      static_block.span,
+      "<static>".to_string(),
+      SymbolKind::StaticBlock,
+      |collector| swc_ecma_visit::visit_static_block(collector, static_block),
+    );
+  }
+
+  fn visit_constructor(&mut self, constructor: &Constructor) {
+    self.pending_name = None;
+    self.enter(
+      constructor.span,
+      "constructor".to_string(),
+      SymbolKind::Constructor,
+      |collector| swc_ecma_visit::visit_constructor(collector, constructor),
+    );
+  }
+
+  fn visit_object_lit(&mut self, object_lit: &ObjectLit) {
+    let name = self.pending_name.take();
+    let visit_props = |collector: &mut Self| {
+      for prop in &object_lit.props {
+        match prop {
+          PropOrSpread::Prop(prop) => match &**prop {
+            Prop::Method(method_prop) => {
+              let name = prop_name(&method_prop.key);
+              let name = collector.name_or_anonymous(name);
+              collector.enter(
+                method_prop.function.span,
+                name,
+                SymbolKind::Method,
+                |collector| swc_ecma_visit::visit_function(collector, &method_prop.function),
+              );
+            }
+            Prop::KeyValue(key_value_prop) => {
+              if matches!(
+                &*key_value_prop.value,
+                Expr::Fn(_) | Expr::Arrow(_) | Expr::Object(_) | Expr::Class(_)
+              ) {
+                collector.pending_name = prop_name(&key_value_prop.key);
+              }
+              collector.visit_expr(&key_value_prop.value);
+              collector.pending_name = None;
+            }
+            _ => collector.visit_prop(prop),
+          },
+          PropOrSpread::Spread(spread) => collector.visit_expr(&spread.expr),
+        }
+      }
+    };
+    match name {
+      Some(name) => self.enter(object_lit.span, name, SymbolKind::Object, visit_props),
+      None => visit_props(self),
+    }
+  }
+
+  fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+    self.visit_pat(&declarator.name);
+    if let Some(init) = &declarator.init {
+      if let Some(ident) = declarator.name.as_ident() {
+        if matches!(
+          &**init,
+          Expr::Fn(_) | Expr::Arrow(_) | Expr::Object(_) | Expr::Class(_)
+        ) {
+          self.pending_name = Some(ident.sym.to_string());
+        }
+      }
+      self.visit_expr(init);
+      self.pending_name = None;
+    }
+  }
+
+  fn visit_assign_expr(&mut self, assign_expr: &AssignExpr) {
+    if let PatOrExpr::Expr(expr) = &assign_expr.left {
+      if let Expr::Ident(ident) = &**expr {
+        if matches!(&*assign_expr.right, Expr::Fn(_) | Expr::Arrow(_)) {
+          self.pending_name = Some(ident.sym.to_string());
+        }
+      }
+    }
+    swc_ecma_visit::visit_pat_or_expr(self, &assign_expr.left);
+    if let Some(left) = assigned_expr(&assign_expr.left) {
+      if let Some(name) = exports_member(left) {
+        self.export_commonjs(name, &assign_expr.right);
+      } else if is_module_exports(left) {
+        self.export_module_exports(&assign_expr.right);
+      }
+    }
+    self.visit_expr(&assign_expr.right);
+    self.pending_name = None;
+  }
+
+  fn visit_expr(&mut self, expr: &Expr) {
+    // a pending name only applies to the function, class or object right after it
+    if !matches!(
+      expr,
+      Expr::Fn(_) | Expr::Arrow(_) | Expr::Object(_) | Expr::Class(_) | Expr::Paren(_)
+    ) {
+      self.pending_name = None;
+    }
+    swc_ecma_visit::visit_expr(self, expr);
+  }
+}
@@ -1,25 +1,35 @@
+extern crate serde;
 extern crate swc_common;
 extern crate swc_ecma_ast;
 extern crate swc_ecma_parser;
 extern crate swc_ecma_visit;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::vec;
 
 use swc_ecma_ast::{
-  ArrowExpr, AssignExpr, BlockStmtOrExpr, Callee, ClassDecl, ClassMethod, Constructor, Decl,
-  ExportDecl, FnDecl, ObjectLit, PatOrExpr, Prop, PropName, PropOrSpread, Stmt, TryStmt,
+  ArrowExpr, AssignExpr, BinaryOp, BlockStmtOrExpr, Callee, Class, ClassDecl, ClassMethod,
+  ClassProp, Constructor, Decl, DefaultDecl, ExportAll, ExportDecl, ExportDefaultDecl,
+  ExportDefaultExpr, FnDecl, IfStmt, MethodKind, NamedExport, ObjectLit, PrivateMethod,
+  PrivateProp, Prop, PropName, PropOrSpread, StaticBlock, Stmt, TryStmt, UnaryOp, VarDecl,
   VarDeclarator,
 };
 
-use self::swc_common::{comments::Comments, sync::Lrc, Span};
+use self::serde::{Deserialize, Serialize};
+use self::swc_common::{comments::Comments, sync::Lrc, BytePos, Span, Spanned};
 use self::swc_ecma_ast::{
-  CallExpr, Expr, Function, ImportDecl, ImportSpecifier, MemberProp, ModuleExportName, ThrowStmt,
+  CallExpr, Expr, ExprOrSpread, Function, ImportDecl, ImportSpecifier, Lit, MemberProp,
+  ModuleExportName, ThrowStmt,
 };
 
 use self::swc_ecma_visit::Visit;
 
+use crate::class_hierarchy::ClassHierarchy;
+use crate::commonjs::{assigned_expr, exports_member, is_module_exports, required_bindings};
+use crate::jsdoc::{doc_comment_throws_tags, ThrowsTag};
+use crate::symbol::{default_export_name, ModuleSymbols, SymbolId};
+
 fn prop_name_to_string(prop_name: &PropName) -> String {
   match prop_name {
     PropName::Ident(ident) => ident.sym.to_string(),
@@ -29,43 +39,475 @@ fn prop_name_to_string(prop_name: &PropName) -> String {
   }
 }
 
-#[derive(Clone)]
-struct BlockContext {
-  try_count: usize,
-  catch_count: usize,
+// Built-in error constructors, which also construct an error when called without `new`
+const BUILTIN_ERROR_CLASSES: [&str; 9] = [
+  "Error",
+  "AggregateError",
+  "EvalError",
+  "RangeError",
+  "ReferenceError",
+  "SyntaxError",
+  "TypeError",
+  "URIError",
+  "DOMException",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ThrownValueKind {
+  // `throw new ValidationError("...")`
+  Constructor,
+  // `throw createError()`, or `throw Error("...")` without `new`
+  Call,
+  // `throw error`
+  Identifier(String),
+  // `throw e` inside `catch (e)`, rethrowing the caught error
+  Rethrow(String),
+  // `throw "message"`, `throw 404`
+  Literal,
+  // anything else, e.g. `throw this.error` or `throw cond ? a : b`
+  Other,
+}
+
+// What a throw statement throws, as far as it is known without type information.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ThrownValue {
+  pub throw_span: Span,
+  // span of the thrown expression, e.g. the whole `new ValidationError("...")`
+  pub expression_span: Span,
+  pub kind: ThrownValueKind,
+  // e.g. `ValidationError` or `errors.NotFound`
+  pub error_class: Option<String>,
+  // the first argument of the constructor (or the thrown literal), when it is a string
+  pub message: Option<String>,
+  pub region: ThrowRegion,
+  // For `throw e` in `catch (e)`: the values thrown in the try block that can reach this rethrow
+  pub rethrown: Vec<ThrownValue>,
+  // For `throw new X({ cause: e })` in `catch (e)`: the values thrown in the try block it may wrap
+  pub causes: Vec<ThrownValue>,
+}
+
+// `a`, `a.b` and `a.b.c` style names, `None` for anything computed
+fn expr_to_name(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Ident(ident) => Some(ident.sym.to_string()),
+    Expr::Member(member_expr) => match &member_expr.prop {
+      MemberProp::Ident(prop) => {
+        expr_to_name(&member_expr.obj).map(|obj| format!("{}.{}", obj, prop.sym))
+      }
+      _ => None,
+    },
+    Expr::Paren(paren_expr) => expr_to_name(&paren_expr.expr),
+    _ => None,
+  }
+}
+
+fn expr_to_string_literal(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Lit(Lit::Str(str_)) => Some(str_.value.to_string()),
+    Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+      .quasis
+      .first()
+      .and_then(|quasi| quasi.cooked.as_ref())
+      .map(|cooked| cooked.to_string()),
+    Expr::Paren(paren_expr) => expr_to_string_literal(&paren_expr.expr),
+    _ => None,
+  }
+}
+
+fn first_arg_message(args: Option<&Vec<ExprOrSpread>>) -> Option<String> {
+  args
+    .and_then(|args| args.first())
+    .filter(|arg| arg.spread.is_none())
+    .and_then(|arg| expr_to_string_literal(&arg.expr))
+}
+
+impl ThrownValue {
+  pub fn from_throw_stmt(node: &ThrowStmt) -> Self {
+    Self::from_expr(node.span, &node.arg)
+  }
+
+  // `throw_span` is the statement (or call, e.g. `reject(error)`) that throws `arg`
+  pub fn from_expr(throw_span: Span, arg: &Expr) -> Self {
+    let mut expr = arg;
+    while let Expr::Paren(paren_expr) = expr {
+      expr = &paren_expr.expr;
+    }
+    let (kind, error_class, message) = match expr {
+      Expr::New(new_expr) => (
+        ThrownValueKind::Constructor,
+        expr_to_name(&new_expr.callee),
+        first_arg_message(new_expr.args.as_ref()),
+      ),
+      Expr::Call(call_expr) => {
+        let callee_name = match &call_expr.callee {
+          Callee::Expr(callee) => expr_to_name(callee),
+          _ => None,
+        };
+        match callee_name {
+          Some(name) if BUILTIN_ERROR_CLASSES.contains(&name.as_str()) => (
+            ThrownValueKind::Call,
+            Some(name),
+            first_arg_message(Some(&call_expr.args)),
+          ),
+          _ => (ThrownValueKind::Call, None, None),
+        }
+      }
+      Expr::Ident(ident) => (
+        ThrownValueKind::Identifier(ident.sym.to_string()),
+        None,
+        None,
+      ),
+      Expr::Lit(_) | Expr::Tpl(_) => (ThrownValueKind::Literal, None, expr_to_string_literal(expr)),
+      _ => (ThrownValueKind::Other, None, None),
+    };
+    ThrownValue {
+      throw_span,
+      expression_span: expr.span(),
+      kind,
+      error_class,
+      message,
+      region: ThrowRegion::Body,
+      rethrown: vec![],
+      causes: vec![],
+    }
+  }
+
+  // What an enclosing catch clause receives when this value is thrown:
+  // a rethrow passes on the values it rethrows, anything else is caught as is
+  fn caught_values(&self) -> Vec<ThrownValue> {
+    if self.rethrown.is_empty() {
+      vec![self.clone()]
+    } else {
+      self.rethrown.clone()
+    }
+  }
+}
+
+// Error classes in order of first appearance, without duplicates.
+// Rethrows contribute the classes of the caught values that escape the catch clause.
+pub fn error_classes(thrown_values: &[ThrownValue]) -> Vec<String> {
+  let mut classes: Vec<String> = vec![];
+  for thrown_value in thrown_values {
+    let value_classes = if thrown_value.rethrown.is_empty() {
+      thrown_value.error_class.iter().cloned().collect()
+    } else {
+      error_classes(&thrown_value.rethrown)
+    };
+    for error_class in value_classes {
+      if !classes.contains(&error_class) {
+        classes.push(error_class);
+      }
+    }
+  }
+  classes
+}
+
+// What is known about the caught binding inside part of a catch clause
+#[derive(Clone, Debug, PartialEq)]
+enum Narrowing {
+  // e.g. inside `if (e instanceof A || e instanceof B) { ... }`
+  Only(Vec<String>),
+  // e.g. inside `if (!(e instanceof A)) { ... }`, or after `if (e instanceof A) return;`
+  Except(Vec<String>),
+}
+
+impl Narrowing {
+  fn negate(self) -> Self {
+    match self {
+      Narrowing::Only(classes) => Narrowing::Except(classes),
+      Narrowing::Except(classes) => Narrowing::Only(classes),
+    }
+  }
+
+  // Values of unknown class are always admitted, they could be an instance of anything.
+  // A guard class covers its subclasses, and `Error` every error class.
+  fn admits(&self, thrown_value: &ThrownValue, class_hierarchy: &ClassHierarchy) -> bool {
+    let Some(error_class) = &thrown_value.error_class else {
+      return true;
+    };
+    let covered = |classes: &[String]| {
+      classes
+        .iter()
+        .any(|class| class_hierarchy.is_instance_of(error_class, class))
+    };
+    match self {
+      Narrowing::Only(classes) => covered(classes),
+      Narrowing::Except(classes) => !covered(classes),
+    }
+  }
+}
+
+// Reads `e instanceof A`, `!(e instanceof A)`, `e instanceof A || e instanceof B` and
+// `!(e instanceof A) && !(e instanceof B)` into the binding name and what it narrows it to
+fn instanceof_guard(test: &Expr) -> Option<(String, Narrowing)> {
+  match test {
+    Expr::Paren(paren_expr) => instanceof_guard(&paren_expr.expr),
+    Expr::Unary(unary_expr) if unary_expr.op == UnaryOp::Bang => {
+      instanceof_guard(&unary_expr.arg).map(|(binding, narrowing)| (binding, narrowing.negate()))
+    }
+    Expr::Bin(bin_expr) => match bin_expr.op {
+      BinaryOp::InstanceOf => match (&*bin_expr.left, expr_to_name(&bin_expr.right)) {
+        (Expr::Ident(binding), Some(class_name)) => {
+          Some((binding.sym.to_string(), Narrowing::Only(vec![class_name])))
+        }
+        _ => None,
+      },
+      BinaryOp::LogicalOr | BinaryOp::LogicalAnd => {
+        match (
+          instanceof_guard(&bin_expr.left),
+          instanceof_guard(&bin_expr.right),
+        ) {
+          (
+            Some((left_binding, Narrowing::Only(mut left))),
+            Some((right_binding, Narrowing::Only(right))),
+          ) if bin_expr.op == BinaryOp::LogicalOr && left_binding == right_binding => {
+            left.extend(right);
+            Some((left_binding, Narrowing::Only(left)))
+          }
+          (
+            Some((left_binding, Narrowing::Except(mut left))),
+            Some((right_binding, Narrowing::Except(right))),
+          ) if bin_expr.op == BinaryOp::LogicalAnd && left_binding == right_binding => {
+            left.extend(right);
+            Some((left_binding, Narrowing::Except(left)))
+          }
+          _ => None,
+        }
+      }
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+// Whether control never continues past this statement
+fn always_exits(stmt: &Stmt) -> bool {
+  match stmt {
+    Stmt::Return(_) | Stmt::Throw(_) | Stmt::Break(_) | Stmt::Continue(_) => true,
+    Stmt::Block(block_stmt) => block_stmt.stmts.iter().any(always_exits),
+    Stmt::If(if_stmt) => {
+      always_exits(&if_stmt.cons) && if_stmt.alt.as_ref().is_some_and(|alt| always_exits(alt))
+    }
+    _ => false,
+  }
+}
+
+// `e` in `new X("...", { cause: e })`
+fn wrapped_binding(arg: &Expr) -> Option<String> {
+  let args = match arg {
+    Expr::New(new_expr) => new_expr.args.as_ref()?,
+    Expr::Call(call_expr) => &call_expr.args,
+    Expr::Paren(paren_expr) => return wrapped_binding(&paren_expr.expr),
+    _ => return None,
+  };
+  args.iter().find_map(|arg| match &*arg.expr {
+    Expr::Object(object_lit) => object_lit.props.iter().find_map(|prop| match prop {
+      PropOrSpread::Prop(prop) => match &**prop {
+        Prop::KeyValue(key_value_prop) if prop_name_to_string(&key_value_prop.key) == "cause" => {
+          match &*key_value_prop.value {
+            Expr::Ident(ident) => Some(ident.sym.to_string()),
+            _ => None,
+          }
+        }
+        _ => None,
+      },
+      _ => None,
+    }),
+    _ => None,
+  })
+}
+
+// A catch clause being visited, with what its try block may throw
+struct CatchScope {
+  binding: Option<String>,
+  caught_values: Vec<ThrownValue>,
+  narrowings: Vec<Narrowing>,
+}
+
+// A call that is known to reach a throw statement, found by the propagation pass in `analyze_code`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThrowingCall {
+  // ids from the called function down to the function containing the throw statement
+  pub propagation_path: Vec<String>,
+  pub thrown_values: Vec<ThrownValue>,
+}
+
+// Where a throw statement sits relative to the innermost enclosing try statement
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ThrowRegion {
+  // not inside a try statement
+  Body,
+  Try,
+  Catch,
+  Finally,
+}
+
+// A region of a try statement being visited
+struct RegionFrame {
+  region: ThrowRegion,
+  // only the try block of a try statement with a catch clause catches what is thrown in it
+  catches: bool,
+  // what is thrown in a catching try block, for its catch clause
+  caught_values: Vec<ThrownValue>,
 }
 
 pub struct ThrowFinderSettings<'throwfinder_settings> {
   pub include_try_statements: &'throwfinder_settings bool,
   pub ignore_statements: &'throwfinder_settings Vec<String>,
+  // Calls known to reach a throw, keyed by call span.
+  // Populated by the propagation pass in `analyze_code`, empty on the first pass.
+  pub throwing_calls: &'throwfinder_settings HashMap<Span, ThrowingCall>,
+  // Whether uncaught `throwing_calls` make a function throw. When this is off, `throwing_calls`
+  // are only used to know what is thrown inside try blocks.
+  pub propagate_throwing_calls: bool,
+  // to tell which caught errors an `instanceof` guard handles
+  pub class_hierarchy: &'throwfinder_settings ClassHierarchy,
 }
 
 impl<'throwfinder_settings> Clone for ThrowFinderSettings<'throwfinder_settings> {
   fn clone(&self) -> ThrowFinderSettings<'throwfinder_settings> {
-      ThrowFinderSettings {
-          include_try_statements: self.include_try_statements,
-          ignore_statements: self.ignore_statements,
-      }
+    ThrowFinderSettings {
+      include_try_statements: self.include_try_statements,
+      ignore_statements: self.ignore_statements,
+      throwing_calls: self.throwing_calls,
+      propagate_throwing_calls: self.propagate_throwing_calls,
+      class_hierarchy: self.class_hierarchy,
+    }
   }
 }
 
 pub struct ThrowFinder<'throwfinder_settings> {
   comments: Lrc<dyn Comments>,
   pub throw_spans: Vec<Span>,
-  context_stack: Vec<BlockContext>, // Stack to track try/catch context
+  pub thrown_values: Vec<ThrownValue>,
+  pub throwing_call_spans: Vec<Span>,
+  pub propagation_path: Vec<String>,
+  // Stack of the try, catch and finally blocks around the current position, innermost last
+  regions: Vec<RegionFrame>,
+  catch_scopes: Vec<CatchScope>,
   pub throwfinder_settings: &'throwfinder_settings ThrowFinderSettings<'throwfinder_settings>,
 }
 
 impl<'throwfinder_settings> ThrowFinder<'throwfinder_settings> {
-  fn current_context(&self) -> Option<&BlockContext> {
-    self.context_stack.last()
+  fn current_region(&self) -> ThrowRegion {
+    self
+      .regions
+      .last()
+      .map_or(ThrowRegion::Body, |frame| frame.region)
+  }
+
+  // Throws escape unless a try block with a catch clause encloses them. Throws in catch and
+  // finally blocks escape too, unless the whole try statement is inside another catching try block.
+  fn is_uncaught(&self) -> bool {
+    *self.throwfinder_settings.include_try_statements
+      || !self.regions.iter().any(|frame| frame.catches)
+  }
+
+  fn visit_region<F: FnOnce(&mut Self)>(
+    &mut self,
+    region: ThrowRegion,
+    catches: bool,
+    visit: F,
+  ) -> Vec<ThrownValue> {
+    self.regions.push(RegionFrame {
+      region,
+      catches,
+      caught_values: vec![],
+    });
+    visit(self);
+    self
+      .regions
+      .pop()
+      .map(|frame| frame.caught_values)
+      .unwrap_or_default()
+  }
+
+  // A function may throw if it has a throw statement of its own, or if it makes
+  // an uncaught call to a function that is already known to throw.
+  pub fn may_throw(&self) -> bool {
+    !self.throw_spans.is_empty() || !self.throwing_call_spans.is_empty()
+  }
+
+  fn record_in_try_block(&mut self, thrown_value: &ThrownValue) {
+    if let Some(try_block) = self.regions.iter_mut().rev().find(|frame| frame.catches) {
+      for caught_value in thrown_value.caught_values() {
+        if !try_block.caught_values.contains(&caught_value) {
+          try_block.caught_values.push(caught_value);
+        }
+      }
+    }
+  }
+
+  fn catch_scope_index(&self, binding: &str) -> Option<usize> {
+    self
+      .catch_scopes
+      .iter()
+      .rposition(|scope| scope.binding.as_deref() == Some(binding))
   }
 
-  pub fn new(throwfinder_settings: &'throwfinder_settings ThrowFinderSettings<'throwfinder_settings>, comments: Lrc<dyn Comments>) -> Self {
+  // The caught values that get past every `instanceof` guard around the current position
+  fn escaping_values(&self, scope_index: usize) -> Vec<ThrownValue> {
+    let scope = &self.catch_scopes[scope_index];
+    scope
+      .caught_values
+      .iter()
+      .filter(|value| {
+        scope
+          .narrowings
+          .iter()
+          .all(|narrowing| narrowing.admits(value, self.throwfinder_settings.class_hierarchy))
+      })
+      .cloned()
+      .collect()
+  }
+
+  // A rethrow that the guards around it have filtered every caught value out of, e.g. `throw e`
+  // after `if (e instanceof A) return` when the try block only throws `A`
+  fn rethrows_nothing(&self, thrown_value: &ThrownValue) -> bool {
+    let ThrownValueKind::Rethrow(binding) = &thrown_value.kind else {
+      return false;
+    };
+    thrown_value.rethrown.is_empty()
+      && self
+        .catch_scope_index(binding)
+        .is_some_and(|scope_index| !self.catch_scopes[scope_index].caught_values.is_empty())
+  }
+
+  fn catch_guard(&self, test: &Expr) -> Option<(usize, Narrowing)> {
+    let (binding, narrowing) = instanceof_guard(test)?;
+    self
+      .catch_scope_index(&binding)
+      .map(|scope_index| (scope_index, narrowing))
+  }
+
+  fn thrown_value(&self, node: &ThrowStmt) -> ThrownValue {
+    let mut thrown_value = ThrownValue::from_throw_stmt(node);
+    thrown_value.region = self.current_region();
+    if let ThrownValueKind::Identifier(binding) = thrown_value.kind.clone() {
+      if let Some(scope_index) = self.catch_scope_index(&binding) {
+        thrown_value.kind = ThrownValueKind::Rethrow(binding);
+        thrown_value.rethrown = self.escaping_values(scope_index);
+      }
+    } else if let Some(binding) = wrapped_binding(&node.arg) {
+      if let Some(scope_index) = self.catch_scope_index(&binding) {
+        thrown_value.causes = self.escaping_values(scope_index);
+      }
+    }
+    thrown_value
+  }
+
+  pub fn new(
+    throwfinder_settings: &'throwfinder_settings ThrowFinderSettings<'throwfinder_settings>,
+    comments: Lrc<dyn Comments>,
+  ) -> Self {
     Self {
       comments,
       throw_spans: vec![],
-      context_stack: vec![],
+      thrown_values: vec![],
+      throwing_call_spans: vec![],
+      propagation_path: vec![],
+      regions: vec![],
+      catch_scopes: vec![],
       throwfinder_settings,
     }
   }
@@ -87,89 +529,256 @@ impl<'throwfinder_settings> Visit for ThrowFinder<'throwfinder_settings> {
       })
       .is_some();
 
-    if !has_it_throws_comment {
-      if *self.throwfinder_settings.include_try_statements {
-        self.throw_spans.push(node.span);
-      } else {
-        let context = self.current_context();
-        if context.map_or(true, |ctx| ctx.try_count == ctx.catch_count) {
-          // Add throw span if not within an unbalanced try block
-          self.throw_spans.push(node.span);
+    if has_it_throws_comment {
+      return;
+    }
+    let thrown_value = self.thrown_value(node);
+    if self.rethrows_nothing(&thrown_value) {
+      return;
+    }
+    self.record_in_try_block(&thrown_value);
+
+    if self.is_uncaught() {
+      self.throw_spans.push(node.span);
+      self.thrown_values.push(thrown_value);
+    }
+  }
+
+  fn visit_call_expr(&mut self, node: &CallExpr) {
+    if let Some(throwing_call) = self.throwfinder_settings.throwing_calls.get(&node.span) {
+      for thrown_value in &throwing_call.thrown_values {
+        self.record_in_try_block(thrown_value);
+      }
+      if self.throwfinder_settings.propagate_throwing_calls && self.is_uncaught() {
+        if self.propagation_path.is_empty() {
+          self.propagation_path = throwing_call.propagation_path.clone();
+        }
+        for thrown_value in &throwing_call.thrown_values {
+          if !self.thrown_values.contains(thrown_value) {
+            self.thrown_values.push(thrown_value.clone());
+          }
         }
+        self.throwing_call_spans.push(node.span);
       }
     }
+    swc_ecma_visit::visit_call_expr(self, node);
   }
 
   fn visit_try_stmt(&mut self, node: &TryStmt) {
-    // Entering a try block
-    let current_context = self.current_context().cloned().unwrap_or(BlockContext {
-      try_count: 0,
-      catch_count: 0,
-    });
-    self.context_stack.push(BlockContext {
-      try_count: current_context.try_count + 1,
-      catch_count: current_context.catch_count,
+    let caught_values = self.visit_region(ThrowRegion::Try, node.handler.is_some(), |finder| {
+      finder.visit_block_stmt(&node.block)
     });
-    swc_ecma_visit::visit_block_stmt_or_expr(self, &BlockStmtOrExpr::BlockStmt(node.block.clone()));
 
     if let Some(catch_clause) = &node.handler {
-      let catch_context = self.context_stack.last_mut().unwrap();
-      catch_context.catch_count += 1; // Increment catch count within the same try context
-      swc_ecma_visit::visit_catch_clause(self, catch_clause);
+      self.catch_scopes.push(CatchScope {
+        binding: catch_clause
+          .param
+          .as_ref()
+          .and_then(|param| param.as_ident())
+          .map(|ident| ident.id.sym.to_string()),
+        caught_values,
+        narrowings: vec![],
+      });
+      self.visit_region(ThrowRegion::Catch, false, |finder| {
+        finder.visit_catch_clause(catch_clause)
+      });
+      self.catch_scopes.pop();
     }
 
-    // Leaving try-catch block
-    self.context_stack.pop();
+    if let Some(finalizer) = &node.finalizer {
+      self.visit_region(ThrowRegion::Finally, false, |finder| {
+        finder.visit_block_stmt(finalizer)
+      });
+    }
+  }
+
+  fn visit_if_stmt(&mut self, node: &IfStmt) {
+    self.visit_expr(&node.test);
+    match self.catch_guard(&node.test) {
+      Some((scope_index, narrowing)) => {
+        self.catch_scopes[scope_index]
+          .narrowings
+          .push(narrowing.clone());
+        self.visit_stmt(&node.cons);
+        self.catch_scopes[scope_index].narrowings.pop();
+        if let Some(alt) = &node.alt {
+          self.catch_scopes[scope_index]
+            .narrowings
+            .push(narrowing.negate());
+          self.visit_stmt(alt);
+          self.catch_scopes[scope_index].narrowings.pop();
+        }
+      }
+      None => {
+        self.visit_stmt(&node.cons);
+        if let Some(alt) = &node.alt {
+          self.visit_stmt(alt);
+        }
+      }
+    }
+  }
+
+  fn visit_stmts(&mut self, stmts: &[Stmt]) {
+    // `if (e instanceof A) return;` narrows `e` for the rest of the block
+    let mut narrowed_scopes: Vec<usize> = vec![];
+    for stmt in stmts {
+      self.visit_stmt(stmt);
+      if let Stmt::If(if_stmt) = stmt {
+        if if_stmt.alt.is_none() && always_exits(&if_stmt.cons) {
+          if let Some((scope_index, narrowing)) = self.catch_guard(&if_stmt.test) {
+            self.catch_scopes[scope_index]
+              .narrowings
+              .push(narrowing.negate());
+            narrowed_scopes.push(scope_index);
+          }
+        }
+      }
+    }
+    for scope_index in narrowed_scopes.into_iter().rev() {
+      self.catch_scopes[scope_index].narrowings.pop();
+    }
   }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct IdentifierUsage {
   pub usage_span: Span,
   pub identifier_name: String,
   pub usage_context: String,
   pub id: String,
+  // The imported export being called, matched against `ThrowMap::symbol` across files
+  pub symbol: SymbolId,
 }
 
 impl IdentifierUsage {
-  pub fn new(usage_span: Span, identifier_name: String, usage_context: String, id: String) -> Self {
+  pub fn new(
+    usage_span: Span,
+    identifier_name: String,
+    usage_context: String,
+    id: String,
+    symbol: SymbolId,
+  ) -> Self {
     Self {
       usage_span,
       identifier_name,
       usage_context,
       id,
+      symbol,
     }
   }
 }
 
+impl IdentifierUsage {
+  // The id of the export this usage calls, see `SymbolId::import_id`
+  pub fn import_id(&self) -> String {
+    self.symbol.import_id().unwrap_or_else(|| self.id.clone())
+  }
+}
+
 impl Eq for IdentifierUsage {}
 
 impl PartialEq for IdentifierUsage {
   fn eq(&self, other: &Self) -> bool {
-    self.id == other.id
+    // must agree with `Hash`, otherwise usages of the same symbol are deduplicated at random
+    self.symbol == other.symbol
+      && self.usage_span.lo == other.usage_span.lo
+      && self.usage_span.hi == other.usage_span.hi
   }
 }
 
 impl Hash for IdentifierUsage {
   fn hash<H: Hasher>(&self, state: &mut H) {
-    self.id.hash(state);
+    self.symbol.hash(state);
     self.usage_span.lo.hash(state);
     self.usage_span.hi.hash(state);
   }
 }
 
-#[derive(Clone)]
+// How a class member that runs code is declared
+#[derive(
+  Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum MemberKind {
+  #[default]
+  Method,
+  Getter,
+  Setter,
+  Constructor,
+  // a property initialized with a function, e.g. `handler = () => {}`
+  Property,
+  // `static { ... }`, which runs once when the class is defined
+  StaticBlock,
+}
+
+impl MemberKind {
+  fn of_method(method_kind: MethodKind) -> Self {
+    match method_kind {
+      MethodKind::Method => MemberKind::Method,
+      MethodKind::Getter => MemberKind::Getter,
+      MethodKind::Setter => MemberKind::Setter,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClassMember {
+  pub kind: MemberKind,
+  pub is_static: bool,
+  // `#name` members, only reachable from inside the class
+  pub is_private: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct ThrowMap {
   pub throw_spans: Vec<Span>,
+  // Ids of the functions a call chain passes through before reaching a throw statement,
+  // e.g. `["NOT_SET-b", "NOT_SET-c"]` for `a -> b -> c(throws)`.
+  // Empty unless the function may throw through one of its calls.
+  pub propagation_path: Vec<String>,
+  // What the throw statements in `throw_spans` throw, followed by what may be thrown
+  // through calls to other functions that throw (see `propagation_path`).
+  pub thrown_values: Vec<ThrownValue>,
+  // The `@throws` tags of the function's doc comment, `None` when it has no doc comment
+  pub throws_tags: Option<Vec<ThrowsTag>>,
   pub throw_statement: Span,
   pub function_or_method_name: String,
   pub class_name: Option<String>,
+  // Display id, e.g. `Service-load`. Not unique: use `symbol` to tell functions apart.
   pub id: String,
+  pub symbol: SymbolId,
+  // `None` for functions that are not a member of a class
+  pub class_member: Option<ClassMember>,
+}
+
+impl ThrowMap {
+  pub fn error_classes(&self) -> Vec<String> {
+    error_classes(&self.thrown_values)
+  }
+
+  // The id calls from other modules are matched by, see `SymbolId::import_id`
+  pub fn import_id(&self) -> String {
+    self.symbol.import_id().unwrap_or_else(|| self.id.clone())
+  }
+
+  // The throw statements of this function that rethrow the error of their catch clause
+  pub fn rethrow_spans(&self) -> Vec<Span> {
+    self
+      .thrown_values
+      .iter()
+      .filter(|thrown_value| matches!(thrown_value.kind, ThrownValueKind::Rethrow(_)))
+      .map(|thrown_value| thrown_value.throw_span)
+      .filter(|throw_span| self.throw_spans.contains(throw_span))
+      .collect()
+  }
+
+  pub fn is_rethrow(&self, throw_span: Span) -> bool {
+    self.rethrow_spans().contains(&throw_span)
+  }
 }
 
 impl PartialEq for ThrowMap {
   fn eq(&self, other: &Self) -> bool {
-    self.throw_statement == other.throw_statement
+    self.symbol == other.symbol && self.throw_statement == other.throw_statement
   }
 }
 
@@ -177,6 +786,7 @@ impl Eq for ThrowMap {}
 
 impl Hash for ThrowMap {
   fn hash<H: Hasher>(&self, state: &mut H) {
+    self.symbol.hash(state);
     self.throw_statement.lo.hash(state);
     self.throw_statement.hi.hash(state);
     self.throw_statement.ctxt.hash(state);
@@ -186,105 +796,217 @@ impl Hash for ThrowMap {
 pub struct ThrowAnalyzer<'throwfinder_settings> {
   pub comments: Lrc<dyn Comments>,
   pub functions_with_throws: HashSet<ThrowMap>,
-  pub json_parse_calls: Vec<String>,
-  pub fs_access_calls: Vec<String>,
+  // Functions with a doc comment, whether they throw or not, to check their `@throws` tags
+  pub documented_functions: HashSet<ThrowMap>,
+  // Where else the doc comment of a declaration starting at a position may be, e.g. in front of
+  // the `export` keyword of `export function foo() {}`, or the `const` of `const foo = () => {}`
+  pub doc_comment_positions: HashMap<BytePos, BytePos>,
   pub import_sources: HashSet<String>,
   pub imported_identifiers: Vec<String>,
   pub function_name_stack: Vec<String>,
   pub current_class_name: Option<String>,
   pub current_method_name: Option<String>,
   pub throwfinder_settings: ThrowFinderSettings<'throwfinder_settings>,
+  pub symbols: Lrc<ModuleSymbols>,
 }
 
 impl<'throwfinder_settings> ThrowAnalyzer<'throwfinder_settings> {
+  fn throws_tags(&self, positions: &[BytePos]) -> Option<Vec<ThrowsTag>> {
+    let mut doc_comment_positions = vec![];
+    for position in positions {
+      let mut position = Some(*position);
+      while let Some(current) = position {
+        doc_comment_positions.push(current);
+        position = self.doc_comment_positions.get(&current).cloned();
+      }
+    }
+    doc_comment_throws_tags(&*self.comments, &doc_comment_positions)
+  }
+
+  fn insert_throw_map(&mut self, throw_map: ThrowMap, may_throw: bool) {
+    if throw_map.throws_tags.is_some() {
+      self.documented_functions.insert(throw_map.clone());
+    }
+    if may_throw {
+      self.functions_with_throws.insert(throw_map);
+    }
+  }
+
   fn check_function_for_throws(&mut self, function: &Function) {
     let mut throw_finder = ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
     throw_finder.visit_function(function);
-    if !throw_finder.throw_spans.is_empty() {
-      let throw_map = ThrowMap {
-        throw_spans: throw_finder.throw_spans,
-        throw_statement: function.span,
-        function_or_method_name: self
+    let may_throw = throw_finder.may_throw();
+    let throw_map = ThrowMap {
+      throw_spans: throw_finder.throw_spans,
+      propagation_path: throw_finder.propagation_path,
+      thrown_values: throw_finder.thrown_values,
+      throws_tags: self.throws_tags(&[function.span.lo]),
+      throw_statement: function.span,
+      symbol: self.symbols.symbol_at(function.span),
+      class_member: None,
+      function_or_method_name: self
+        .function_name_stack
+        .last()
+        .cloned()
+        .unwrap_or_else(|| "<anonymous>".to_string()),
+      class_name: None,
+      id: format!(
+        "{}-{}",
+        self
+          .current_class_name
+          .clone()
+          .unwrap_or_else(|| "NOT_SET".to_string()),
+        self
           .function_name_stack
           .last()
           .cloned()
-          .unwrap_or_else(|| "<anonymous>".to_string()),
-        class_name: None,
-        id: format!(
-          "{}-{}",
-          self
-            .current_class_name
-            .clone()
-            .unwrap_or_else(|| "NOT_SET".to_string()),
-          self
-            .function_name_stack
-            .last()
-            .cloned()
-            .unwrap_or_else(|| "<anonymous>".to_string())
-        ),
-      };
-      self.functions_with_throws.insert(throw_map);
-    }
+          .unwrap_or_else(|| "<anonymous>".to_string())
+      ),
+    };
+    self.insert_throw_map(throw_map, may_throw);
   }
 
   fn check_arrow_function_for_throws(&mut self, arrow_function: &ArrowExpr) {
     let mut throw_finder = ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
     throw_finder.visit_arrow_expr(arrow_function);
-    if !throw_finder.throw_spans.is_empty() {
-      let throw_map = ThrowMap {
-        throw_spans: throw_finder.throw_spans,
-        throw_statement: arrow_function.span,
-        function_or_method_name: self
+    let may_throw = throw_finder.may_throw();
+    let throw_map = ThrowMap {
+      throw_spans: throw_finder.throw_spans,
+      propagation_path: throw_finder.propagation_path,
+      thrown_values: throw_finder.thrown_values,
+      throws_tags: self.throws_tags(&[arrow_function.span.lo]),
+      throw_statement: arrow_function.span,
+      symbol: self.symbols.symbol_at(arrow_function.span),
+      class_member: None,
+      function_or_method_name: self
+        .function_name_stack
+        .last()
+        .cloned()
+        .unwrap_or_else(|| "<anonymous>".to_string()),
+      class_name: None,
+      id: format!(
+        "{}-{}",
+        self
+          .current_class_name
+          .clone()
+          .unwrap_or_else(|| "NOT_SET".to_string()),
+        self
           .function_name_stack
           .last()
           .cloned()
-          .unwrap_or_else(|| "<anonymous>".to_string()),
-        class_name: None,
-        id: format!(
-          "{}-{}",
-          self
-            .current_class_name
-            .clone()
-            .unwrap_or_else(|| "NOT_SET".to_string()),
-          self
-            .function_name_stack
-            .last()
-            .cloned()
-            .unwrap_or_else(|| "<anonymous>".to_string())
-        ),
-      };
-      self.functions_with_throws.insert(throw_map);
-    }
+          .unwrap_or_else(|| "<anonymous>".to_string())
+      ),
+    };
+    self.insert_throw_map(throw_map, may_throw);
   }
 
   fn check_constructor_for_throws(&mut self, constructor: &Constructor) {
     let mut throw_finder = ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
     throw_finder.visit_constructor(constructor);
-    if !throw_finder.throw_spans.is_empty() {
-      let throw_map = ThrowMap {
-        throw_spans: throw_finder.throw_spans,
-        throw_statement: constructor.span,
-        function_or_method_name: self
+    let may_throw = throw_finder.may_throw();
+    let throw_map = ThrowMap {
+      throw_spans: throw_finder.throw_spans,
+      propagation_path: throw_finder.propagation_path,
+      thrown_values: throw_finder.thrown_values,
+      throws_tags: self.throws_tags(&[constructor.span.lo]),
+      throw_statement: constructor.span,
+      symbol: self.symbols.symbol_at(constructor.span),
+      class_member: Some(ClassMember {
+        kind: MemberKind::Constructor,
+        ..Default::default()
+      }),
+      function_or_method_name: self
+        .current_method_name
+        .clone()
+        .unwrap_or_else(|| "<constructor>".to_string()),
+      class_name: self.current_class_name.clone(),
+      id: format!(
+        "{}-{}",
+        self
+          .current_class_name
+          .clone()
+          .unwrap_or_else(|| "NOT_SET".to_string()),
+        self
           .current_method_name
           .clone()
-          .unwrap_or_else(|| "<constructor>".to_string()),
-        class_name: self.current_class_name.clone(),
-        id: format!(
-          "{}-{}",
-          self
-            .current_class_name
-            .clone()
-            .unwrap_or_else(|| "NOT_SET".to_string()),
-          self
-            .current_method_name
-            .clone()
-            .unwrap_or_else(|| "<constructor>".to_string())
-        ),
-      };
-      self.functions_with_throws.insert(throw_map);
+          .unwrap_or_else(|| "<constructor>".to_string())
+      ),
+    };
+    self.insert_throw_map(throw_map, may_throw);
+  }
+
+  // Records a member of the current class: a method, accessor, function-valued property or
+  // static block. `visit` walks the code the member runs.
+  fn check_class_member_for_throws<F>(
+    &mut self,
+    span: Span,
+    key_span: Option<Span>,
+    class_member: ClassMember,
+    visit: F,
+  ) where
+    F: FnOnce(&mut ThrowFinder),
+  {
+    let mut throw_finder = ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
+    visit(&mut throw_finder);
+    let may_throw = throw_finder.may_throw();
+    let symbol = self.symbols.symbol_at(span);
+    let mut doc_comment_positions = vec![span.lo];
+    doc_comment_positions.extend(key_span.map(|key_span| key_span.lo));
+    let throw_map = ThrowMap {
+      throw_spans: throw_finder.throw_spans,
+      propagation_path: throw_finder.propagation_path,
+      thrown_values: throw_finder.thrown_values,
+      throws_tags: self.throws_tags(&doc_comment_positions),
+      throw_statement: span,
+      function_or_method_name: symbol.name.clone(),
+      class_name: self.current_class_name.clone(),
+      id: format!(
+        "{}-{}",
+        self
+          .current_class_name
+          .clone()
+          .unwrap_or_else(|| "NOT_SET".to_string()),
+        symbol.name
+      ),
+      symbol,
+      class_member: Some(class_member),
+    };
+    self.insert_throw_map(throw_map, may_throw);
+  }
+
+  // Function-valued properties are recorded with the span of their function, the same
+  // `ThrowMap` `visit_function` records for function expressions
+  fn check_class_prop_for_throws(
+    &mut self,
+    key_span: Span,
+    value: &Option<Box<Expr>>,
+    class_member: ClassMember,
+  ) {
+    match value.as_deref() {
+      Some(Expr::Arrow(arrow_expr)) => self.check_class_member_for_throws(
+        arrow_expr.span,
+        Some(key_span),
+        class_member,
+        |throw_finder| throw_finder.visit_arrow_expr(arrow_expr),
+      ),
+      Some(Expr::Fn(fn_expr)) => self.check_class_member_for_throws(
+        fn_expr.function.span,
+        Some(key_span),
+        class_member,
+        |throw_finder| throw_finder.visit_function(&fn_expr.function),
+      ),
+      _ => {}
     }
   }
 
+  // `current_class_name` is restored afterwards, for classes declared inside the methods of
+  // another class
+  fn visit_class_named(&mut self, class_name: String, class: &Class) {
+    let outer_class_name = self.current_class_name.replace(class_name);
+    self.visit_class(class);
+    self.current_class_name = outer_class_name;
+  }
+
   fn register_import(&mut self, import: &ImportDecl) {
     self.import_sources.insert(import.src.value.to_string());
     for specifier in &import.specifiers {
@@ -368,33 +1090,38 @@ impl<'throwfinder_settings> Visit for ThrowAnalyzer<'throwfinder_settings> {
         }
 
         Expr::Arrow(arrow_expr) => {
-          let mut throw_finder = ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
+          let mut throw_finder =
+            ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
           throw_finder.visit_arrow_expr(arrow_expr);
-          if !throw_finder.throw_spans.is_empty() {
-            let throw_map = ThrowMap {
-              throw_spans: throw_finder.throw_spans,
-              throw_statement: arrow_expr.span,
-              function_or_method_name: self
+          let may_throw = throw_finder.may_throw();
+          let throw_map = ThrowMap {
+            throw_spans: throw_finder.throw_spans,
+            propagation_path: throw_finder.propagation_path,
+            thrown_values: throw_finder.thrown_values,
+            throws_tags: self.throws_tags(&[arrow_expr.span.lo]),
+            throw_statement: arrow_expr.span,
+            symbol: self.symbols.symbol_at(arrow_expr.span),
+            class_member: None,
+            function_or_method_name: self
+              .function_name_stack
+              .last()
+              .cloned()
+              .unwrap_or_else(|| "<anonymous>".to_string()),
+            class_name: None,
+            id: format!(
+              "{}-{}",
+              self
+                .current_class_name
+                .clone()
+                .unwrap_or_else(|| "NOT_SET".to_string()),
+              self
                 .function_name_stack
                 .last()
                 .cloned()
-                .unwrap_or_else(|| "<anonymous>".to_string()),
-              class_name: None,
-              id: format!(
-                "{}-{}",
-                self
-                  .current_class_name
-                  .clone()
-                  .unwrap_or_else(|| "NOT_SET".to_string()),
-                self
-                  .function_name_stack
-                  .last()
-                  .cloned()
-                  .unwrap_or_else(|| "<anonymous>".to_string())
-              ),
-            };
-            self.functions_with_throws.insert(throw_map);
-          }
+                .unwrap_or_else(|| "<anonymous>".to_string())
+            ),
+          };
+          self.insert_throw_map(throw_map, may_throw);
         }
         _ => {}
       }
@@ -413,101 +1140,131 @@ impl<'throwfinder_settings> Visit for ThrowAnalyzer<'throwfinder_settings> {
   fn visit_object_lit(&mut self, object_lit: &ObjectLit) {
     // Iterate over the properties of the object literal
     for prop in &object_lit.props {
-      match prop {
-        // Check for method properties (e.g., someImportedThrow: () => { ... })
-        PropOrSpread::Prop(prop) => {
-          if let Prop::Method(method_prop) = &**prop {
-            if let Some(method_name) = &method_prop.key.as_ident() {
-              let method_name: String = method_name.sym.to_string();
+      // Check for method properties (e.g., someImportedThrow: () => { ... })
+      if let PropOrSpread::Prop(prop) = prop {
+        if let Prop::Method(method_prop) = &**prop {
+          if let Some(method_name) = &method_prop.key.as_ident() {
+            let method_name: String = method_name.sym.to_string();
+
+            self.function_name_stack.push(method_name.clone());
 
-              self.function_name_stack.push(method_name.clone());
+            let mut throw_finder =
+              ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
+            throw_finder.visit_function(&method_prop.function);
+
+            let may_throw = throw_finder.may_throw();
+            let throw_map = ThrowMap {
+              throw_spans: throw_finder.throw_spans,
+              propagation_path: throw_finder.propagation_path,
+              thrown_values: throw_finder.thrown_values,
+              throws_tags: self
+                .throws_tags(&[method_prop.function.span.lo, method_prop.key.span().lo]),
+              throw_statement: method_prop.function.span,
+              symbol: self.symbols.symbol_at(method_prop.function.span),
+              class_member: None,
+              function_or_method_name: method_name.clone(),
+              class_name: self.current_class_name.clone(),
+              id: format!(
+                "{}-{}",
+                self
+                  .current_class_name
+                  .clone()
+                  .unwrap_or_else(|| "NOT_SET".to_string()),
+                method_name
+              ),
+            };
+            self.insert_throw_map(throw_map, may_throw);
 
+            self.function_name_stack.pop();
+          }
+        }
+        if let Prop::KeyValue(key_value_prop) = &**prop {
+          match &*key_value_prop.value {
+            Expr::Fn(fn_expr) => {
               let mut throw_finder =
                 ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
-              throw_finder.visit_function(&method_prop.function);
-
-              if !throw_finder.throw_spans.is_empty() {
-                let throw_map = ThrowMap {
-                  throw_spans: throw_finder.throw_spans,
-                  throw_statement: method_prop.function.span,
-                  function_or_method_name: method_name.clone(),
-                  class_name: self.current_class_name.clone(),
-                  id: format!(
-                    "{}-{}",
-                    self
-                      .current_class_name
-                      .clone()
-                      .unwrap_or_else(|| "NOT_SET".to_string()),
-                    method_name
-                  ),
-                };
-                self.functions_with_throws.insert(throw_map);
-              }
-
-              self.function_name_stack.pop();
+              throw_finder.visit_function(&fn_expr.function);
+              let function_name = prop_name_to_string(&key_value_prop.key);
+
+              let may_throw = throw_finder.may_throw();
+              let throw_map = ThrowMap {
+                throw_spans: throw_finder.throw_spans,
+                propagation_path: throw_finder.propagation_path,
+                thrown_values: throw_finder.thrown_values,
+                throws_tags: self
+                  .throws_tags(&[fn_expr.function.span.lo, key_value_prop.key.span().lo]),
+                throw_statement: fn_expr.function.span,
+                symbol: self.symbols.symbol_at(fn_expr.function.span),
+                class_member: None,
+                function_or_method_name: function_name.clone(),
+                class_name: self.current_class_name.clone(),
+                id: format!(
+                  "{}-{}",
+                  self
+                    .current_class_name
+                    .clone()
+                    .unwrap_or_else(|| "NOT_SET".to_string()),
+                  function_name
+                ),
+              };
+              self.insert_throw_map(throw_map, may_throw);
             }
-          }
-          if let Prop::KeyValue(key_value_prop) = &**prop {
-            match &*key_value_prop.value {
-              Expr::Fn(fn_expr) => {
-                let mut throw_finder =
-                  ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
-                throw_finder.visit_function(&fn_expr.function);
-                let function_name = prop_name_to_string(&key_value_prop.key);
-
-                if !throw_finder.throw_spans.is_empty() {
-                  let throw_map = ThrowMap {
-                    throw_spans: throw_finder.throw_spans,
-                    throw_statement: fn_expr.function.span,
-                    function_or_method_name: function_name.clone(),
-                    class_name: self.current_class_name.clone(),
-                    id: format!(
-                      "{}-{}",
-                      self
-                        .current_class_name
-                        .clone()
-                        .unwrap_or_else(|| "NOT_SET".to_string()),
-                      function_name
-                    ),
-                  };
-                  self.functions_with_throws.insert(throw_map);
-                }
-              }
-              Expr::Arrow(arrow_expr) => {
-                let mut throw_finder =
-                  ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
-                throw_finder.visit_arrow_expr(arrow_expr);
-                let function_name = prop_name_to_string(&key_value_prop.key);
-
-                if !throw_finder.throw_spans.is_empty() {
-                  let throw_map = ThrowMap {
-                    throw_spans: throw_finder.throw_spans,
-                    throw_statement: arrow_expr.span,
-                    function_or_method_name: function_name.clone(),
-                    class_name: self.current_class_name.clone(),
-                    id: format!(
-                      "{}-{}",
-                      self
-                        .current_class_name
-                        .clone()
-                        .unwrap_or_else(|| "NOT_SET".to_string()),
-                      function_name
-                    ),
-                  };
-                  self.functions_with_throws.insert(throw_map);
-                }
-              }
-              _ => {}
+            Expr::Arrow(arrow_expr) => {
+              let mut throw_finder =
+                ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
+              throw_finder.visit_arrow_expr(arrow_expr);
+              let function_name = prop_name_to_string(&key_value_prop.key);
+
+              let may_throw = throw_finder.may_throw();
+              let throw_map = ThrowMap {
+                throw_spans: throw_finder.throw_spans,
+                propagation_path: throw_finder.propagation_path,
+                thrown_values: throw_finder.thrown_values,
+                throws_tags: self.throws_tags(&[arrow_expr.span.lo, key_value_prop.key.span().lo]),
+                throw_statement: arrow_expr.span,
+                symbol: self.symbols.symbol_at(arrow_expr.span),
+                class_member: None,
+                function_or_method_name: function_name.clone(),
+                class_name: self.current_class_name.clone(),
+                id: format!(
+                  "{}-{}",
+                  self
+                    .current_class_name
+                    .clone()
+                    .unwrap_or_else(|| "NOT_SET".to_string()),
+                  function_name
+                ),
+              };
+              self.insert_throw_map(throw_map, may_throw);
             }
+            _ => {}
           }
         }
-        _ => {}
       }
     }
     swc_ecma_visit::visit_object_lit(self, object_lit);
   }
 
   fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+    if let Some((source, bindings)) = required_bindings(declarator) {
+      self.import_sources.insert(source);
+      // like `import` specifiers: the exported name, or the local name of the whole module
+      for binding in bindings {
+        let imported_name = match binding.export_path.first() {
+          Some(export_name) => export_name.clone(),
+          None => binding.local.sym.to_string(),
+        };
+        self.imported_identifiers.push(imported_name);
+      }
+      return;
+    }
+    // class expressions are named after their binding, e.g. `const Service = class { ... }`
+    if let (Some(ident), Some(Expr::Class(class_expr))) =
+      (declarator.name.as_ident(), declarator.init.as_deref())
+    {
+      self.visit_class_named(ident.sym.to_string(), &class_expr.class);
+      return;
+    }
     if let Some(ident) = &declarator.name.as_ident() {
       if let Some(init) = &declarator.init {
         let function_name = ident.sym.to_string();
@@ -532,45 +1289,58 @@ impl<'throwfinder_settings> Visit for ThrowAnalyzer<'throwfinder_settings> {
           self.current_class_name = None;
         }
 
-        if !throw_finder.throw_spans.is_empty() {
-          let throw_map = ThrowMap {
-            throw_spans: throw_finder.throw_spans,
-            throw_statement: declarator.span,
-            function_or_method_name: function_name.clone(),
-            class_name: self.current_class_name.clone(),
-            id: format!(
-              "{}-{}",
-              self
-                .current_class_name
-                .clone()
-                .unwrap_or_else(|| "NOT_SET".to_string()),
-              function_name
-            ),
-          };
-          self.functions_with_throws.insert(throw_map);
-        }
+        let may_throw = throw_finder.may_throw();
+        let throw_map = ThrowMap {
+          throw_spans: throw_finder.throw_spans,
+          propagation_path: throw_finder.propagation_path,
+          thrown_values: throw_finder.thrown_values,
+          throws_tags: self.throws_tags(&[declarator.span.lo]),
+          throw_statement: declarator.span,
+          symbol: self.symbols.symbol_at(declarator.span),
+          class_member: None,
+          function_or_method_name: function_name.clone(),
+          class_name: self.current_class_name.clone(),
+          id: format!(
+            "{}-{}",
+            self
+              .current_class_name
+              .clone()
+              .unwrap_or_else(|| "NOT_SET".to_string()),
+            function_name
+          ),
+        };
+        self.insert_throw_map(throw_map, may_throw);
       }
     }
     swc_ecma_visit::visit_var_declarator(self, declarator);
   }
   fn visit_assign_expr(&mut self, assign_expr: &AssignExpr) {
-    if let PatOrExpr::Expr(expr) = &assign_expr.left {
-      if let Expr::Ident(ident) = &**expr {
-        if matches!(&*assign_expr.right, Expr::Fn(_) | Expr::Arrow(_)) {
-          let function_name = ident.sym.to_string();
-          self.function_name_stack.push(function_name);
-        }
+    let function_name = match (assigned_expr(&assign_expr.left), &*assign_expr.right) {
+      (Some(Expr::Ident(ident)), Expr::Fn(_) | Expr::Arrow(_)) => Some(ident.sym.to_string()),
+      // CommonJS exports are named after their export, like their ES module counterparts
+      (Some(left), Expr::Fn(_) | Expr::Arrow(_)) if exports_member(left).is_some() => {
+        exports_member(left)
+      }
+      (Some(left), Expr::Fn(fn_expr)) if is_module_exports(left) => {
+        Some(default_export_name(fn_expr.ident.as_ref()))
+      }
+      (Some(left), Expr::Arrow(_)) if is_module_exports(left) => Some(default_export_name(None)),
+      _ => None,
+    };
+
+    if let Some(function_name) = &function_name {
+      self.function_name_stack.push(function_name.clone());
+      // function expressions are recorded when visited, arrow functions only through the
+      // declaration or assignment naming them
+      if let Expr::Arrow(arrow_expr) = &*assign_expr.right {
+        self.check_arrow_function_for_throws(arrow_expr);
       }
     }
 
     swc_ecma_visit::visit_assign_expr(self, assign_expr);
 
-    if let PatOrExpr::Expr(expr) = &assign_expr.left {
-      if let Expr::Ident(_) = &**expr {
-        if matches!(&*assign_expr.right, Expr::Fn(_) | Expr::Arrow(_)) {
-          self.function_name_stack.pop();
-        }
-      }
+    if function_name.is_some() {
+      self.function_name_stack.pop();
     }
   }
 
@@ -580,6 +1350,19 @@ impl<'throwfinder_settings> Visit for ThrowAnalyzer<'throwfinder_settings> {
     swc_ecma_visit::visit_import_decl(self, import);
   }
 
+  // re-exported modules are imported too, so that barrel files resolve to the modules behind them
+  fn visit_named_export(&mut self, named_export: &NamedExport) {
+    if let Some(src) = &named_export.src {
+      self.import_sources.insert(src.value.to_string());
+    }
+    swc_ecma_visit::visit_named_export(self, named_export);
+  }
+
+  fn visit_export_all(&mut self, export_all: &ExportAll) {
+    self.import_sources.insert(export_all.src.value.to_string());
+    swc_ecma_visit::visit_export_all(self, export_all);
+  }
+
   fn visit_function(&mut self, function: &Function) {
     if let Some(block_stmt) = &function.body {
       for stmt in &block_stmt.stmts {
@@ -653,51 +1436,145 @@ impl<'throwfinder_settings> Visit for ThrowAnalyzer<'throwfinder_settings> {
   }
 
   fn visit_class_method(&mut self, class_method: &ClassMethod) {
-    if let Some(method_name) = &class_method.key.as_ident() {
-      let method_name = method_name.sym.to_string();
-
-      self.function_name_stack.push(method_name.clone());
+    self.check_class_member_for_throws(
+      class_method.span,
+      Some(class_method.key.span()),
+      ClassMember {
+        kind: MemberKind::of_method(class_method.kind),
+        is_static: class_method.is_static,
+        is_private: false,
+      },
+      |throw_finder| throw_finder.visit_class_method(class_method),
+    );
 
-      let mut throw_finder = ThrowFinder::new(&self.throwfinder_settings, self.comments.clone());
-      throw_finder.visit_class_method(class_method);
+    swc_ecma_visit::visit_class_method(self, class_method);
+  }
 
-      if !throw_finder.throw_spans.is_empty() {
-        let throw_map = ThrowMap {
-          throw_spans: throw_finder.throw_spans,
-          throw_statement: class_method.span,
-          function_or_method_name: method_name.clone(),
-          class_name: self.current_class_name.clone(),
-          id: format!(
-            "{}-{}",
-            self
-              .current_class_name
-              .clone()
-              .unwrap_or_else(|| "NOT_SET".to_string()),
-            method_name
-          ),
-        };
-        self.functions_with_throws.insert(throw_map);
-      }
+  fn visit_private_method(&mut self, private_method: &PrivateMethod) {
+    self.check_class_member_for_throws(
+      private_method.span,
+      Some(private_method.key.span),
+      ClassMember {
+        kind: MemberKind::of_method(private_method.kind),
+        is_static: private_method.is_static,
+        is_private: true,
+      },
+      |throw_finder| throw_finder.visit_private_method(private_method),
+    );
+
+    swc_ecma_visit::visit_private_method(self, private_method);
+  }
 
-      self.function_name_stack.pop();
-    }
+  fn visit_class_prop(&mut self, class_prop: &ClassProp) {
+    self.check_class_prop_for_throws(
+      class_prop.key.span(),
+      &class_prop.value,
+      ClassMember {
+        kind: MemberKind::Property,
+        is_static: class_prop.is_static,
+        is_private: false,
+      },
+    );
+    swc_ecma_visit::visit_class_prop(self, class_prop);
+  }
 
-    self.function_name_stack.pop();
+  fn visit_private_prop(&mut self, private_prop: &PrivateProp) {
+    self.check_class_prop_for_throws(
+      private_prop.key.span,
+      &private_prop.value,
+      ClassMember {
+        kind: MemberKind::Property,
+        is_static: private_prop.is_static,
+        is_private: true,
+      },
+    );
+    swc_ecma_visit::visit_private_prop(self, private_prop);
+  }
 
-    swc_ecma_visit::visit_class_method(self, class_method);
+  fn visit_static_block(&mut self, static_block: &StaticBlock) {
+    self.check_class_member_for_throws(
+      static_block.span,
+      None,
+      ClassMember {
+        kind: MemberKind::StaticBlock,
+        is_static: true,
+        is_private: false,
+      },
+      |throw_finder| throw_finder.visit_static_block(static_block),
+    );
+    swc_ecma_visit::visit_static_block(self, static_block);
   }
 
   fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
-    self.current_class_name = Some(class_decl.ident.sym.to_string());
-    self.visit_class(&class_decl.class);
-    self.current_class_name = None;
+    self.visit_class_named(class_decl.ident.sym.to_string(), &class_decl.class);
+  }
+
+  fn visit_var_decl(&mut self, var_decl: &VarDecl) {
+    for declarator in &var_decl.decls {
+      self
+        .doc_comment_positions
+        .insert(declarator.span.lo, var_decl.span.lo);
+    }
+    swc_ecma_visit::visit_var_decl(self, var_decl);
+  }
+
+  fn visit_export_default_decl(&mut self, export_default_decl: &ExportDefaultDecl) {
+    match &export_default_decl.decl {
+      DefaultDecl::Fn(fn_expr) => {
+        self
+          .doc_comment_positions
+          .insert(fn_expr.function.span.lo, export_default_decl.span.lo);
+        self
+          .function_name_stack
+          .push(default_export_name(fn_expr.ident.as_ref()));
+        self.visit_function(&fn_expr.function);
+        self.function_name_stack.pop();
+      }
+      DefaultDecl::Class(class_expr) => {
+        self.visit_class_named(
+          default_export_name(class_expr.ident.as_ref()),
+          &class_expr.class,
+        );
+      }
+      DefaultDecl::TsInterfaceDecl(_) => {}
+    }
+  }
+
+  // `export default { ... }` and `export default () => {}` are named after their export, like
+  // anonymous default-exported functions and classes
+  fn visit_export_default_expr(&mut self, export_default_expr: &ExportDefaultExpr) {
+    match &*export_default_expr.expr {
+      Expr::Object(object_lit) => {
+        let outer_class_name = self.current_class_name.replace(default_export_name(None));
+        self.visit_object_lit(object_lit);
+        self.current_class_name = outer_class_name;
+      }
+      Expr::Arrow(arrow_expr) => {
+        self.function_name_stack.push(default_export_name(None));
+        self.check_arrow_function_for_throws(arrow_expr);
+        self.visit_arrow_expr(arrow_expr);
+        self.function_name_stack.pop();
+      }
+      _ => swc_ecma_visit::visit_export_default_expr(self, export_default_expr),
+    }
   }
 
   fn visit_export_decl(&mut self, export_decl: &ExportDecl) {
+    match &export_decl.decl {
+      Decl::Fn(fn_decl) => {
+        self
+          .doc_comment_positions
+          .insert(fn_decl.function.span.lo, export_decl.span.lo);
+      }
+      Decl::Var(var_decl) => {
+        self
+          .doc_comment_positions
+          .insert(var_decl.span.lo, export_decl.span.lo);
+      }
+      _ => {}
+    }
     if let Decl::Class(class_decl) = &export_decl.decl {
-      self.current_class_name = Some(class_decl.ident.sym.to_string());
-      self.visit_class(&class_decl.class);
-      self.current_class_name = None;
+      self.visit_class_named(class_decl.ident.sym.to_string(), &class_decl.class);
     }
     // else if let Decl::Var(var_decl) = &export_decl.decl {
     //   for declar in &var_decl.decls {
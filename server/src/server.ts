@@ -1,17 +1,40 @@
 import {
+  CodeAction,
+  CodeActionKind,
   DidChangeConfigurationNotification,
+  Hover,
   InitializeParams,
   InitializeResult,
+  Location,
   ProposedFeatures,
+  RequestType,
+  TextDocumentPositionParams,
   TextDocumentSyncKind,
   TextDocuments,
   createConnection
 } from 'vscode-languageserver/node'
 
-import { access, constants, readFile } from 'fs/promises'
+import { createHash } from 'crypto'
+import { readFileSync, statSync } from 'fs'
+import { mkdir, readFile, writeFile } from 'fs/promises'
+import { tmpdir } from 'os'
 import { TextDocument } from 'vscode-languageserver-textdocument'
-import { InputData, ParseResult, parse_js } from './rust/does_it_throw_wasm'
+import {
+  CallSiteLocation,
+  FileSystemHost,
+  InputData,
+  ProjectParseResult,
+  ThrowSiteLocation,
+  code_actions_js,
+  hover_js,
+  load_cache_js,
+  parse_project_js,
+  project_throw_sites_js,
+  references_js,
+  save_cache_js
+} from './rust/does_it_throw_wasm'
 import path = require('path')
+import { fileURLToPath, pathToFileURL } from 'url'
 import { inspect } from 'util'
 
 const connection = createConnection(ProposedFeatures.all)
@@ -19,6 +42,10 @@ const connection = createConnection(ProposedFeatures.all)
 const documents: TextDocuments<TextDocument> = new TextDocuments(TextDocument)
 let hasConfigurationCapability = false
 let hasWorkspaceFolderCapability = false
+// Where the analysis cache of the workspace is saved between sessions
+let cacheFile: string | undefined
+// Where `tsconfig.json` is looked up, the first workspace folder
+let workspaceRoot: string | undefined
 
 connection.onInitialize((params: InitializeParams) => {
   const capabilities = params.capabilities
@@ -28,9 +55,23 @@ connection.onInitialize((params: InitializeParams) => {
   hasConfigurationCapability = !!(capabilities.workspace && !!capabilities.workspace.configuration)
   hasWorkspaceFolderCapability = !!(capabilities.workspace && !!capabilities.workspace.workspaceFolders)
 
+  const workspaceUri = params.workspaceFolders?.[0]?.uri ?? params.rootUri
+  if (workspaceUri) {
+    const workspaceHash = createHash('sha1').update(workspaceUri).digest('hex')
+    cacheFile = path.join(tmpdir(), 'does-it-throw', `${workspaceHash}.json`)
+    if (workspaceUri.startsWith('file:')) {
+      workspaceRoot = fileURLToPath(workspaceUri)
+    }
+  }
+
   const result: InitializeResult = {
     capabilities: {
-      textDocumentSync: TextDocumentSyncKind.Incremental
+      textDocumentSync: TextDocumentSyncKind.Incremental,
+      hoverProvider: true,
+      codeActionProvider: {
+        codeActionKinds: [CodeActionKind.QuickFix]
+      },
+      referencesProvider: true
     }
   }
   if (hasWorkspaceFolderCapability) {
@@ -43,7 +84,7 @@ connection.onInitialize((params: InitializeParams) => {
   return result
 })
 
-connection.onInitialized(() => {
+connection.onInitialized(async () => {
   if (hasConfigurationCapability) {
     // Register for all configuration changes.
     connection.client.register(DidChangeConfigurationNotification.type, undefined)
@@ -53,6 +94,24 @@ connection.onInitialized(() => {
       connection.console.log(`Workspace folder change event received. ${JSON.stringify(_event)}`)
     })
   }
+  if (cacheFile) {
+    try {
+      load_cache_js(await readFile(cacheFile, 'utf-8'))
+    } catch {
+      // no cache yet
+    }
+  }
+})
+
+connection.onShutdown(async () => {
+  if (cacheFile) {
+    try {
+      await mkdir(path.dirname(cacheFile), { recursive: true })
+      await writeFile(cacheFile, save_cache_js())
+    } catch (e) {
+      connection.console.error(`Error saving the analysis cache ${inspect(e)}`)
+    }
+  }
 })
 
 type DiagnosticSeverity = 'Error' | 'Warning' | 'Information' | 'Hint'
@@ -66,6 +125,14 @@ interface Settings {
   callToImportedThrowSeverity: DiagnosticSeverity
   includeTryStatementThrows: boolean
   ignoreStatements: string[]
+  maxPropagationDepth: number
+  promiseRejectionSeverity: DiagnosticSeverity
+  floatingPromiseSeverity: DiagnosticSeverity
+  undocumentedThrowSeverity: DiagnosticSeverity
+  staleThrowsTagSeverity: DiagnosticSeverity
+  callToThrowingBuiltinSeverity: DiagnosticSeverity
+  throwingOverrideSeverity: DiagnosticSeverity
+  throwingBuiltins: { callee: string; errorClasses?: string[] }[]
 }
 
 // The global settings, used when the `workspace/configuration` request is not supported by the client.
@@ -78,7 +145,15 @@ const defaultSettings: Settings = {
   callToThrowSeverity: 'Hint',
   callToImportedThrowSeverity: 'Hint',
   includeTryStatementThrows: false,
-  ignoreStatements: ['@it-throws', '@does-it-throw-ignore']
+  ignoreStatements: ['@it-throws', '@does-it-throw-ignore'],
+  maxPropagationDepth: 0,
+  promiseRejectionSeverity: 'Hint',
+  floatingPromiseSeverity: 'Hint',
+  undocumentedThrowSeverity: 'Hint',
+  staleThrowsTagSeverity: 'Hint',
+  callToThrowingBuiltinSeverity: 'Hint',
+  throwingOverrideSeverity: 'Hint',
+  throwingBuiltins: []
 }
 // 👆 very unlikely someone will have more than 1 million throw statements, lol
 // if you do, might want to rethink your code?
@@ -131,25 +206,72 @@ documents.onDidSave((change) => {
   validateTextDocument(change.document)
 })
 
-const _checkAccessOnFile = async (file: string) => {
+function inputDataFor(textDocument: TextDocument, settings: Settings) {
+  return {
+    uri: textDocument.uri,
+    file_content: textDocument.getText(),
+    ids_to_check: [],
+    typescript_settings: {
+      decorators: true
+    },
+    function_throw_severity: settings?.functionThrowSeverity ?? defaultSettings.functionThrowSeverity,
+    throw_statement_severity: settings?.throwStatementSeverity ?? defaultSettings.throwStatementSeverity,
+    call_to_imported_throw_severity:
+      settings?.callToImportedThrowSeverity ?? defaultSettings.callToImportedThrowSeverity,
+    call_to_throw_severity: settings?.callToThrowSeverity ?? defaultSettings.callToThrowSeverity,
+    include_try_statement_throws: settings?.includeTryStatementThrows ?? defaultSettings.includeTryStatementThrows,
+    ignore_statements: settings?.ignoreStatements ?? defaultSettings.ignoreStatements,
+    max_propagation_depth: settings?.maxPropagationDepth ?? defaultSettings.maxPropagationDepth,
+    promise_rejection_severity: settings?.promiseRejectionSeverity ?? defaultSettings.promiseRejectionSeverity,
+    floating_promise_severity: settings?.floatingPromiseSeverity ?? defaultSettings.floatingPromiseSeverity,
+    undocumented_throw_severity: settings?.undocumentedThrowSeverity ?? defaultSettings.undocumentedThrowSeverity,
+    stale_throws_tag_severity: settings?.staleThrowsTagSeverity ?? defaultSettings.staleThrowsTagSeverity,
+    call_to_throwing_builtin_severity:
+      settings?.callToThrowingBuiltinSeverity ?? defaultSettings.callToThrowingBuiltinSeverity,
+    throwing_override_severity: settings?.throwingOverrideSeverity ?? defaultSettings.throwingOverrideSeverity,
+    throwing_builtins: (settings?.throwingBuiltins ?? defaultSettings.throwingBuiltins).map((builtin) => ({
+      callee: builtin.callee,
+      error_classes: builtin.errorClasses
+    }))
+  } satisfies InputData
+}
+
+// Files are known to the analysis by path, decoded from the document's `file:` URI
+const uriPath = (uri: string) => (uri.startsWith('file:') ? fileURLToPath(uri) : uri)
+const documentPath = (textDocument: TextDocument) => uriPath(textDocument.uri)
+
+const rootFor = (textDocument: TextDocument) => workspaceRoot ?? path.dirname(documentPath(textDocument))
+
+const openDocumentText = (file: string) =>
+  documents
+    .all()
+    .find((document) => documentPath(document) === file)
+    ?.getText()
+
+const statOf = (file: string) => {
   try {
-    await access(file, constants.R_OK)
-    return Promise.resolve(file)
-  } catch (e) {
-    return Promise.reject(e)
+    return statSync(file, { throwIfNoEntry: false })
+  } catch {
+    return undefined
   }
 }
 
-const findFirstFileThatExists = async (uri: string, relative_import: string) => {
-  const isTs = uri.endsWith('.ts') || uri.endsWith('.tsx')
-  const baseUri = `${path.resolve(path.dirname(uri.replace('file://', '')), relative_import)}`
-  let files = Array(4)
-  if (isTs) {
-    files = [`${baseUri}.ts`, `${baseUri}.tsx`, `${baseUri}.js`, `${baseUri}.jsx`]
-  } else {
-    files = [`${baseUri}.js`, `${baseUri}.jsx`, `${baseUri}.ts`, `${baseUri}.tsx`]
+// The files of the workspace as the analysis reads them: open documents with their unsaved content,
+// other files from disk. Imports are resolved by the analysis, the same way as on the command line.
+const fileSystemHost: FileSystemHost = {
+  isFile: (file) => openDocumentText(file) !== undefined || !!statOf(file)?.isFile(),
+  isDirectory: (file) => !!statOf(file)?.isDirectory(),
+  readFile: (file) => {
+    const text = openDocumentText(file)
+    if (text !== undefined) {
+      return text
+    }
+    try {
+      return readFileSync(file, 'utf-8')
+    } catch {
+      return undefined
+    }
   }
-  return Promise.any(files.map(_checkAccessOnFile))
 }
 
 async function validateTextDocument(textDocument: TextDocument): Promise<void> {
@@ -160,68 +282,17 @@ async function validateTextDocument(textDocument: TextDocument): Promise<void> {
     settings = defaultSettings
   }
   try {
-    const opts = {
-      uri: textDocument.uri,
-      file_content: textDocument.getText(),
-      ids_to_check: [],
-      typescript_settings: {
-        decorators: true
-      },
-      function_throw_severity: settings?.functionThrowSeverity ?? defaultSettings.functionThrowSeverity,
-      throw_statement_severity: settings?.throwStatementSeverity ?? defaultSettings.throwStatementSeverity,
-      call_to_imported_throw_severity:
-        settings?.callToImportedThrowSeverity ?? defaultSettings.callToImportedThrowSeverity,
-      call_to_throw_severity: settings?.callToThrowSeverity ?? defaultSettings.callToThrowSeverity,
-      include_try_statement_throws: settings?.includeTryStatementThrows ?? defaultSettings.includeTryStatementThrows,
-      ignore_statements: settings?.ignoreStatements ?? defaultSettings.ignoreStatements
-    } satisfies InputData
-    const analysis = parse_js(opts) as ParseResult
-
-    if (analysis.relative_imports.length > 0) {
-      const filePromises = analysis.relative_imports.map(async (relative_import) => {
-        try {
-          const file = await findFirstFileThatExists(textDocument.uri, relative_import)
-          return {
-            fileContent: await readFile(file, 'utf-8'),
-            fileUri: file
-          }
-        } catch (e) {
-          connection.console.log(`Error reading file ${inspect(e)}`)
-          return undefined
-        }
-      })
-      const files = (await Promise.all(filePromises)).filter((file) => !!file)
-      const analysisArr = files.map((file) => {
-        if (!file) {
-          return undefined
-        }
-        const opts = {
-          uri: file.fileUri,
-          file_content: file.fileContent,
-          ids_to_check: [],
-          typescript_settings: {
-            decorators: true
-          }
-        } satisfies InputData
-        return parse_js(opts) as ParseResult
-      })
-      // TODO - this is a bit of a mess, but it works for now.
-      // The original analysis is the one that has the throw statements Map()
-      // We get the get the throw_ids from the imported analysis and then
-      // check the original analysis for existing throw_ids.
-      // This allows to to get the diagnostics from the imported analysis (one level deep for now)
-      for (const import_analysis of analysisArr) {
-        if (!import_analysis) {
-          return
-        }
-        if (import_analysis.throw_ids.length) {
-          for (const throw_id of import_analysis.throw_ids) {
-            const newDiagnostics = analysis.imported_identifiers_diagnostics.get(throw_id)
-            if (newDiagnostics?.diagnostics?.length) {
-              analysis.diagnostics.push(...newDiagnostics.diagnostics)
-            }
-          }
-        }
+    // imported files are only analyzed again when their content changed
+    const analysis = parse_project_js(
+      inputDataFor(textDocument, settings),
+      rootFor(textDocument),
+      documentPath(textDocument),
+      fileSystemHost
+    ) as ProjectParseResult
+    // documents importing this one need new diagnostics when what it exports starts or stops throwing
+    for (const dependent of documents.all()) {
+      if (analysis.invalidated.includes(documentPath(dependent))) {
+        validateTextDocument(dependent)
       }
     }
     connection.sendDiagnostics({
@@ -237,6 +308,110 @@ async function validateTextDocument(textDocument: TextDocument): Promise<void> {
   }
 }
 
+// Explains why the call under the cursor may throw
+connection.onHover(async (params): Promise<Hover | null> => {
+  const textDocument = documents.get(params.textDocument.uri)
+  if (!textDocument) {
+    return null
+  }
+  const settings = (await getDocumentSettings(textDocument.uri)) ?? defaultSettings
+  try {
+    const { line, character } = params.position
+    // imported files are read from the cache, as in `validateTextDocument`
+    return hover_js(
+      inputDataFor(textDocument, settings),
+      rootFor(textDocument),
+      documentPath(textDocument),
+      fileSystemHost,
+      line,
+      character
+    ) as Hover | null
+  } catch (e) {
+    connection.console.error(`Error: ${e instanceof Error ? e.message : JSON.stringify(e)} error`)
+    return null
+  }
+})
+
+// Wraps throwing calls in try/catch, adds `.catch()` to floating promises, ignores or documents throw statements
+connection.onCodeAction(async (params): Promise<CodeAction[]> => {
+  const textDocument = documents.get(params.textDocument.uri)
+  if (!textDocument) {
+    return []
+  }
+  const settings = (await getDocumentSettings(textDocument.uri)) ?? defaultSettings
+  try {
+    const { line, character } = params.range.start
+    return code_actions_js(
+      inputDataFor(textDocument, settings),
+      rootFor(textDocument),
+      documentPath(textDocument),
+      fileSystemHost,
+      line,
+      character
+    ) as CodeAction[]
+  } catch (e) {
+    connection.console.error(`Error: ${e instanceof Error ? e.message : JSON.stringify(e)} error`)
+    return []
+  }
+})
+
+// Custom request: the `throw` statements the call under the cursor may reach, in this file or in the
+// files it imports
+const throwSitesRequest = new RequestType<TextDocumentPositionParams, Location[], void>('doesItThrow/throwSites')
+
+connection.onRequest(throwSitesRequest, async (params): Promise<Location[]> => {
+  const textDocument = documents.get(params.textDocument.uri)
+  if (!textDocument) {
+    return []
+  }
+  const settings = (await getDocumentSettings(textDocument.uri)) ?? defaultSettings
+  try {
+    const { line, character } = params.position
+    const throwSites = project_throw_sites_js(
+      inputDataFor(textDocument, settings),
+      rootFor(textDocument),
+      documentPath(textDocument),
+      fileSystemHost,
+      line,
+      character
+    ) as ThrowSiteLocation[]
+    return throwSites.map((throwSite) => {
+      const uri = throwSite.path === documentPath(textDocument) ? textDocument.uri : pathToFileURL(throwSite.path).href
+      return Location.create(uri, throwSite.range)
+    })
+  } catch (e) {
+    connection.console.error(`Error: ${e instanceof Error ? e.message : JSON.stringify(e)} error`)
+    return []
+  }
+})
+
+// The calls to the throwing function under the cursor, in the document and in the files importing it
+connection.onReferences(async (params): Promise<Location[]> => {
+  const textDocument = documents.get(params.textDocument.uri)
+  if (!textDocument) {
+    return []
+  }
+  const settings = (await getDocumentSettings(textDocument.uri)) ?? defaultSettings
+  try {
+    const { line, character } = params.position
+    const callSites = references_js(
+      inputDataFor(textDocument, settings),
+      rootFor(textDocument),
+      documentPath(textDocument),
+      fileSystemHost,
+      line,
+      character
+    ) as CallSiteLocation[]
+    return callSites.map((callSite) => {
+      const uri = callSite.path === documentPath(textDocument) ? textDocument.uri : pathToFileURL(callSite.path).href
+      return Location.create(uri, callSite.range)
+    })
+  } catch (e) {
+    connection.console.error(`Error: ${e instanceof Error ? e.message : JSON.stringify(e)} error`)
+    return []
+  }
+})
+
 connection.onDidChangeWatchedFiles((_change) => {
   // Monitored files have change in VSCode
   connection.console.log(`We received an file change event ${_change}, not implemented yet`)